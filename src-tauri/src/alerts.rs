@@ -0,0 +1,318 @@
+use crate::database::with_db;
+use crate::error::TrendrError;
+use crate::refresh::parse_timestamp;
+use crate::settings;
+use rusqlite::{params, Connection};
+use std::fs;
+use std::path::Path;
+
+/// Most recent alerts included in the RSS feed
+const RSS_ITEM_LIMIT: i64 = 100;
+
+/// Alerts of this type are user-written notes, never removed by retention cleanup
+pub const NOTE_ALERT_TYPE: &str = "note";
+
+fn insert_alert(
+    conn: &Connection,
+    alert_type: &str,
+    topic_id: Option<&str>,
+    message: &str,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO alerts (id, alert_type, topic_id, message) VALUES (?1, ?2, ?3, ?4)",
+        params![uuid::Uuid::new_v4().to_string(), alert_type, topic_id, message],
+    )?;
+    Ok(())
+}
+
+/// Insert an alert unless its type is disabled in settings or one of the same type for the
+/// same topic was already raised in the last `dedupe_hours`. Returns whether a new alert
+/// was created.
+pub fn create_alert_once(
+    alert_type: &str,
+    topic_id: Option<&str>,
+    message: &str,
+    dedupe_hours: i64,
+) -> Result<bool, TrendrError> {
+    if !settings::load_settings().alerts.is_enabled(alert_type) {
+        return Ok(false);
+    }
+
+    let created = with_db(|conn| {
+        let existing: i64 = conn.query_row(
+            r#"SELECT COUNT(*) FROM alerts
+               WHERE alert_type = ?1 AND topic_id IS ?2
+               AND created_at > datetime('now', ?3)"#,
+            params![alert_type, topic_id, format!("-{} hours", dedupe_hours)],
+            |row| row.get(0),
+        )?;
+
+        if existing > 0 {
+            return Ok(false);
+        }
+
+        insert_alert(conn, alert_type, topic_id, message)?;
+        Ok(true)
+    })?;
+
+    if created {
+        refresh_rss_feed();
+    }
+    Ok(created)
+}
+
+/// Delete read alerts older than `retention_days`, keeping unread alerts and notes.
+/// Returns how many were removed; 0 days disables cleanup.
+pub fn cleanup_read_alerts(retention_days: u32) -> Result<usize, TrendrError> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+
+    with_db(|conn| {
+        conn.execute(
+            r#"DELETE FROM alerts
+               WHERE read = 1 AND alert_type != ?1
+               AND created_at < datetime('now', ?2)"#,
+            params![NOTE_ALERT_TYPE, format!("-{} days", retention_days)],
+        )
+    })
+}
+
+/// Track consecutive runs that stored nothing for a platform. When the streak reaches
+/// `threshold`, raise one `collection_stalled` alert (if that type is enabled); any stored
+/// item resets the streak.
+pub fn record_collection_run(platform: &str, items_stored: u32, threshold: u32) -> Result<(), TrendrError> {
+    let enabled = settings::load_settings().alerts.is_enabled("collection_stalled");
+
+    let alerted = with_db(|conn| {
+        if items_stored > 0 {
+            conn.execute(
+                r#"INSERT INTO collection_health (platform, consecutive_empty_runs, last_collected_at)
+                   VALUES (?1, 0, CURRENT_TIMESTAMP)
+                   ON CONFLICT(platform) DO UPDATE SET
+                   consecutive_empty_runs = 0, last_collected_at = CURRENT_TIMESTAMP"#,
+                params![platform],
+            )?;
+            return Ok(false);
+        }
+
+        let empty_runs: i64 = conn.query_row(
+            r#"INSERT INTO collection_health (platform, consecutive_empty_runs)
+               VALUES (?1, 1)
+               ON CONFLICT(platform) DO UPDATE SET
+               consecutive_empty_runs = consecutive_empty_runs + 1
+               RETURNING consecutive_empty_runs"#,
+            params![platform],
+            |row| row.get(0),
+        )?;
+
+        if enabled && threshold > 0 && empty_runs == threshold as i64 {
+            let message = format!(
+                "{} collection has found no new content in {} consecutive runs. Check credentials and configured sources.",
+                platform_label(platform),
+                empty_runs
+            );
+            insert_alert(conn, "collection_stalled", None, &message)?;
+            return Ok(true);
+        }
+        Ok(false)
+    })?;
+
+    if alerted {
+        refresh_rss_feed();
+    }
+    Ok(())
+}
+
+fn platform_label(platform: &str) -> &str {
+    match platform {
+        "reddit" => "Reddit",
+        "x" => "X",
+        "youtube" => "YouTube",
+        other => other,
+    }
+}
+
+/// Rewrite the configured RSS feed file, if any, after a new alert. Failures are logged.
+fn refresh_rss_feed() {
+    let path = match settings::load_settings().alerts_rss_path {
+        Some(path) if !path.trim().is_empty() => path,
+        _ => return,
+    };
+
+    if let Err(e) = export_rss(Path::new(&path), RSS_ITEM_LIMIT) {
+        log::warn!("Failed to update alerts RSS feed at {}: {}", path, e);
+    }
+}
+
+/// Write the most recent `limit` alerts to `path` as an RSS 2.0 feed. Returns the number
+/// of items written.
+pub fn export_rss(path: &Path, limit: i64) -> Result<usize, TrendrError> {
+    let alerts: Vec<(String, String, Option<String>, String, String)> = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT a.id, a.alert_type, t.name, a.message, a.created_at
+               FROM alerts a
+               LEFT JOIN topics t ON t.id = a.topic_id
+               ORDER BY a.created_at DESC
+               LIMIT ?1"#,
+        )?;
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })?;
+        rows.collect()
+    })?;
+
+    let mut items = String::new();
+    for (id, alert_type, topic_name, message, created_at) in &alerts {
+        let title = match topic_name {
+            Some(topic) => format!("{}: {}", humanize(alert_type), topic),
+            None => humanize(alert_type),
+        };
+        let pub_date = parse_timestamp(created_at)
+            .map(|dt| dt.to_rfc2822())
+            .unwrap_or_else(|| created_at.clone());
+
+        items.push_str(&format!(
+            "    <item>\n      <title>{}</title>\n      <description>{}</description>\n      <pubDate>{}</pubDate>\n      <guid isPermaLink=\"false\">{}</guid>\n    </item>\n",
+            escape_xml(&title),
+            escape_xml(message),
+            escape_xml(&pub_date),
+            escape_xml(id)
+        ));
+    }
+
+    let feed = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n  <channel>\n    <title>Trendr alerts</title>\n    <link>trendr://alerts</link>\n    <description>Alerts raised by Trendr</description>\n    <lastBuildDate>{}</lastBuildDate>\n{}  </channel>\n</rss>\n",
+        chrono::Utc::now().to_rfc2822(),
+        items
+    );
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, feed)?;
+
+    Ok(alerts.len())
+}
+
+/// "sentiment_shift" -> "Sentiment shift"
+fn humanize(alert_type: &str) -> String {
+    let spaced = alert_type.replace('_', " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => spaced,
+    }
+}
+
+/// Escape the five XML special characters so alert text can't break the feed markup
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab/newline are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands;
+
+    fn seed_alerts(alerts: &[(&str, &str, bool, &str)]) {
+        with_db(|conn| {
+            for (id, alert_type, read, created_at) in alerts {
+                conn.execute(
+                    r#"INSERT INTO alerts (id, alert_type, message, read, created_at)
+                       VALUES (?1, ?2, 'message', ?3, datetime('now', ?4))"#,
+                    params![id, alert_type, read, created_at],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    fn alert_ids() -> Vec<String> {
+        with_db(|conn| {
+            let mut stmt = conn.prepare("SELECT id FROM alerts ORDER BY id")?;
+            let ids = stmt.query_map([], |row| row.get(0))?;
+            ids.collect()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn housekeeping_removes_read_alerts_and_keeps_notes_and_unread_ones() {
+        let _db = crate::database::test_database();
+        seed_alerts(&[
+            ("old_read", "trend_spike", true, "-10 days"),
+            ("new_read", "trend_spike", true, "-1 hours"),
+            ("old_unread", "trend_spike", false, "-10 days"),
+            ("old_note", NOTE_ALERT_TYPE, true, "-10 days"),
+        ]);
+        assert_eq!(commands::get_unread_alert_count().unwrap(), 1);
+
+        assert_eq!(cleanup_read_alerts(0).unwrap(), 0);
+        assert_eq!(cleanup_read_alerts(7).unwrap(), 1);
+        assert_eq!(alert_ids(), ["new_read", "old_note", "old_unread"]);
+
+        assert_eq!(commands::clear_read_alerts().unwrap(), 1);
+        assert_eq!(alert_ids(), ["old_note", "old_unread"]);
+        assert_eq!(commands::get_unread_alert_count().unwrap(), 1);
+
+        assert_eq!(commands::mark_all_alerts_read().unwrap(), 1);
+        assert_eq!(commands::get_unread_alert_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn xml_special_characters_are_escaped_and_control_characters_dropped() {
+        assert_eq!(
+            escape_xml(r#"Tom & Jerry <3 "cats" 'n' dogs"#),
+            "Tom &amp; Jerry &lt;3 &quot;cats&quot; &apos;n&apos; dogs"
+        );
+        assert_eq!(escape_xml("bell\u{7}\u{0}\u{1b}[0m done"), "bell[0m done");
+        assert_eq!(escape_xml("tab\tline\r\nend"), "tab\tline\r\nend");
+        assert_eq!(escape_xml("Ünïcödé 🚀"), "Ünïcödé 🚀");
+    }
+
+    #[test]
+    fn rss_export_writes_the_newest_alerts_as_items() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug) VALUES ('rust', 'Rust & Cargo', 'rust');
+                   INSERT INTO alerts (id, alert_type, topic_id, message, created_at) VALUES
+                       ('spike', 'trend_spike', 'rust', 'Mentions of <Rust> tripled', '2024-03-02 10:00:00'),
+                       ('stall', 'collection_stalled', NULL, 'X found nothing', '2024-03-01 09:30:00'),
+                       ('oldest', 'trend_spike', 'rust', 'Too old to include', '2024-02-01 00:00:00');"#,
+            )
+        })
+        .unwrap();
+        let path = std::env::temp_dir()
+            .join(format!("trendr-rss-{}", uuid::Uuid::new_v4()))
+            .join("alerts.xml");
+
+        assert_eq!(export_rss(&path, 2).unwrap(), 2);
+
+        let feed = fs::read_to_string(&path).unwrap();
+        assert!(feed.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">"));
+        let spike = feed.find("<title>Trend spike: Rust &amp; Cargo</title>").unwrap();
+        let stall = feed.find("<title>Collection stalled</title>").unwrap();
+        assert!(spike < stall, "newest alert comes first");
+        assert!(feed.contains("<description>Mentions of &lt;Rust&gt; tripled</description>"));
+        assert!(feed.contains("<pubDate>Sat, 2 Mar 2024 10:00:00 +0000</pubDate>"));
+        assert!(feed.contains("<guid isPermaLink=\"false\">stall</guid>"));
+        assert!(!feed.contains("Too old"));
+        assert_eq!(feed.matches("<item>").count(), 2);
+    }
+}
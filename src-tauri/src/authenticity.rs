@@ -0,0 +1,231 @@
+use crate::content::status;
+use crate::database::with_db;
+use crate::error::TrendrError;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Windows of history the current window's volume is compared against
+const BASELINE_WINDOWS: i64 = 7;
+
+/// Current volume must be at least this multiple of the baseline to count as a spike
+const SPIKE_FACTOR: f64 = 2.0;
+
+/// Fewest items in the window before any signal is trusted
+const MIN_ITEMS: i64 = 10;
+
+/// Creators with fewer known followers than this count as low-follower accounts
+const LOW_FOLLOWER_COUNT: i64 = 100;
+
+/// How many of the most active creators make up the "narrow creator set"
+const TOP_CREATORS: usize = 3;
+
+/// Share of window content above which a signal is considered suspicious
+const NEW_ACCOUNT_SHARE: f64 = 0.5;
+const LOW_FOLLOWER_SHARE: f64 = 0.5;
+const DUPLICATE_TEXT_SHARE: f64 = 0.3;
+const TOP_CREATOR_SHARE: f64 = 0.6;
+
+/// Heuristic signs of inorganic activity behind a topic's recent volume.
+///
+/// Shares are fractions (0–1) of the content in the window:
+/// - newAccountShare: from creators Trendr first saw within the window. Platform account
+///   ages aren't collected, so this stands in for newly created accounts. Only flagged
+///   when the topic has content from before the window.
+/// - lowFollowerShare: from creators with fewer than 100 followers, among content whose
+///   creator's follower count is known (X only)
+/// - duplicateTextShare: text identical to another item in the window once case,
+///   punctuation and whitespace are ignored, or flagged as a repost of an earlier link
+/// - topCreatorShare: from the 3 most active creators
+///
+/// `suspectedCoordinated` is set when the topic is spiking (at least 2× its average
+/// volume over the previous 7 windows), has at least 10 items, and any share passes its
+/// threshold; `reasons` lists which ones did.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendAuthenticity {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    #[serde(rename = "windowHours")]
+    pub window_hours: i64,
+    #[serde(rename = "contentCount")]
+    pub content_count: i64,
+    /// Mean item count per window over the previous windows
+    pub baseline: f64,
+    /// content_count / max(baseline, 1)
+    #[serde(rename = "spikeFactor")]
+    pub spike_factor: f64,
+    pub spiking: bool,
+    #[serde(rename = "distinctCreators")]
+    pub distinct_creators: i64,
+    #[serde(rename = "newAccountShare")]
+    pub new_account_share: f64,
+    #[serde(rename = "lowFollowerShare")]
+    pub low_follower_share: Option<f64>,
+    #[serde(rename = "duplicateTextShare")]
+    pub duplicate_text_share: f64,
+    #[serde(rename = "topCreatorShare")]
+    pub top_creator_share: f64,
+    #[serde(rename = "suspectedCoordinated")]
+    pub suspected_coordinated: bool,
+    pub reasons: Vec<String>,
+}
+
+struct WindowItem {
+    text: String,
+    creator_id: Option<String>,
+    follower_count: Option<i64>,
+    new_creator: bool,
+    repost: bool,
+}
+
+/// Check the last `window_hours` of a topic's content for signs of coordinated activity.
+pub fn assess_topic(topic_id: &str, window_hours: i64) -> Result<TrendAuthenticity, TrendrError> {
+    let window_hours = window_hours.clamp(1, 24 * 30);
+    let window = format!("-{} hours", window_hours);
+    let history = format!("-{} hours", window_hours * (BASELINE_WINDOWS + 1));
+
+    let (topic_name, items, previous_count) = with_db(|conn| {
+        let topic_name: Option<String> = conn
+            .query_row("SELECT name FROM topics WHERE id = ?1", params![topic_id], |row| row.get(0))
+            .optional()?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT c.text_content, c.creator_id, cr.follower_count,
+               cr.created_at > datetime('now', ?3), c.duplicate_of IS NOT NULL
+               FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               LEFT JOIN creators cr ON cr.id = c.creator_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2
+               AND COALESCE(datetime(c.published_at), datetime(c.collected_at)) > datetime('now', ?3)"#,
+        )?;
+        let rows = stmt.query_map(params![topic_id, status::DELETED, &window], |row| {
+            Ok(WindowItem {
+                text: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                creator_id: row.get(1)?,
+                follower_count: row.get(2)?,
+                new_creator: row.get::<_, Option<bool>>(3)?.unwrap_or(false),
+                repost: row.get(4)?,
+            })
+        })?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            items.push(row?);
+        }
+
+        let previous_count: i64 = conn.query_row(
+            r#"SELECT COUNT(*)
+               FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2
+               AND COALESCE(datetime(c.published_at), datetime(c.collected_at)) <= datetime('now', ?3)
+               AND COALESCE(datetime(c.published_at), datetime(c.collected_at)) > datetime('now', ?4)"#,
+            params![topic_id, status::DELETED, &window, &history],
+            |row| row.get(0),
+        )?;
+
+        Ok((topic_name, items, previous_count))
+    })?;
+
+    let topic_name = topic_name.ok_or_else(|| TrendrError::Other(format!("Topic not found: {}", topic_id)))?;
+
+    let content_count = items.len() as i64;
+    let baseline = previous_count as f64 / BASELINE_WINDOWS as f64;
+    let spike_factor = content_count as f64 / baseline.max(1.0);
+    let spiking = spike_factor >= SPIKE_FACTOR;
+
+    let share = |matching: usize| {
+        if items.is_empty() {
+            0.0
+        } else {
+            matching as f64 / items.len() as f64
+        }
+    };
+
+    let new_account_share = share(items.iter().filter(|item| item.new_creator).count());
+
+    let known_followers: Vec<i64> = items.iter().filter_map(|item| item.follower_count).collect();
+    let low_follower_share = if known_followers.is_empty() {
+        None
+    } else {
+        let low = known_followers.iter().filter(|&&count| count < LOW_FOLLOWER_COUNT).count();
+        Some(low as f64 / known_followers.len() as f64)
+    };
+
+    let mut texts: HashMap<String, usize> = HashMap::new();
+    for item in &items {
+        let normalized = normalize_text(&item.text);
+        if !normalized.is_empty() {
+            *texts.entry(normalized).or_insert(0) += 1;
+        }
+    }
+    let duplicates = items
+        .iter()
+        .filter(|item| item.repost || texts.get(&normalize_text(&item.text)).is_some_and(|&n| n > 1))
+        .count();
+    let duplicate_text_share = share(duplicates);
+
+    let mut per_creator: HashMap<&str, usize> = HashMap::new();
+    for item in &items {
+        if let Some(creator_id) = &item.creator_id {
+            *per_creator.entry(creator_id.as_str()).or_insert(0) += 1;
+        }
+    }
+    let mut creator_counts: Vec<usize> = per_creator.values().copied().collect();
+    creator_counts.sort_unstable_by(|a, b| b.cmp(a));
+    let top_creator_share = share(creator_counts.iter().take(TOP_CREATORS).sum());
+
+    let mut reasons = Vec::new();
+    // Every account is new to a fresh install, so this needs earlier content to compare with
+    if previous_count > 0 && new_account_share >= NEW_ACCOUNT_SHARE {
+        reasons.push(format!("{:.0}% of content is from newly seen accounts", new_account_share * 100.0));
+    }
+    if let Some(low_share) = low_follower_share.filter(|&share| share >= LOW_FOLLOWER_SHARE) {
+        reasons.push(format!(
+            "{:.0}% of content is from accounts with fewer than {} followers",
+            low_share * 100.0,
+            LOW_FOLLOWER_COUNT
+        ));
+    }
+    if duplicate_text_share >= DUPLICATE_TEXT_SHARE {
+        reasons.push(format!("{:.0}% of content is near-identical or reposted", duplicate_text_share * 100.0));
+    }
+    if top_creator_share >= TOP_CREATOR_SHARE {
+        reasons.push(format!(
+            "{:.0}% of content comes from {} creators",
+            top_creator_share * 100.0,
+            TOP_CREATORS
+        ));
+    }
+
+    let suspected_coordinated = spiking && content_count >= MIN_ITEMS && !reasons.is_empty();
+
+    Ok(TrendAuthenticity {
+        topic_id: topic_id.to_string(),
+        topic_name,
+        window_hours,
+        content_count,
+        baseline,
+        spike_factor,
+        spiking,
+        distinct_creators: per_creator.len() as i64,
+        new_account_share,
+        low_follower_share,
+        duplicate_text_share,
+        top_creator_share,
+        suspected_coordinated,
+        reasons,
+    })
+}
+
+/// Lowercase words with punctuation and extra whitespace removed, so trivially edited
+/// copies of the same text compare equal
+fn normalize_text(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,466 @@
+use crate::database::{with_db_tx, SCHEMA_VERSION};
+use crate::error::TrendrError;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+type BackupRow = Map<String, Value>;
+
+/// A backed-up table and how its rows are matched against existing data on import.
+struct BackupTable {
+    name: &'static str,
+    /// Columns identifying the same item under a different id (e.g. content collected
+    /// again after a wipe). Empty for link tables.
+    natural_key: &'static [&'static str],
+    /// (column, referenced table) pairs rewritten to the ids rows were imported under
+    references: &'static [(&'static str, &'static str)],
+}
+
+/// Parents before children, so references resolve as rows are imported
+const BACKUP_TABLES: [BackupTable; 4] = [
+    BackupTable {
+        name: "topics",
+        natural_key: &["slug"],
+        references: &[("parent_topic_id", "topics")],
+    },
+    BackupTable {
+        name: "creators",
+        natural_key: &["platform", "platform_id"],
+        references: &[],
+    },
+    BackupTable {
+        name: "content",
+        natural_key: &["platform", "platform_id"],
+        references: &[("creator_id", "creators"), ("duplicate_of", "content")],
+    },
+    BackupTable {
+        name: "content_topics",
+        natural_key: &[],
+        references: &[("content_id", "content"), ("topic_id", "topics")],
+    },
+];
+
+/// Topics, creators, content and topic links as written by `export_backup`. Rows are
+/// column → value maps, so a backup only loads into the schema version it came from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    #[serde(rename = "schemaVersion")]
+    pub schema_version: u32,
+    #[serde(rename = "exportedAt")]
+    pub exported_at: String,
+    pub topics: Vec<BackupRow>,
+    pub creators: Vec<BackupRow>,
+    pub content: Vec<BackupRow>,
+    #[serde(rename = "contentTopics")]
+    pub content_topics: Vec<BackupRow>,
+}
+
+impl Backup {
+    fn rows(&self, table: &str) -> &[BackupRow] {
+        match table {
+            "topics" => &self.topics,
+            "creators" => &self.creators,
+            "content" => &self.content,
+            "content_topics" => &self.content_topics,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableImportCount {
+    pub table: String,
+    pub inserted: usize,
+    /// Rows already present, or whose referenced rows are missing
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportResult {
+    pub tables: Vec<TableImportCount>,
+    pub inserted: usize,
+    pub skipped: usize,
+}
+
+/// Write every topic, creator, content row and topic link to `path` as one JSON document.
+/// Returns the number of rows written.
+pub fn export_backup(path: &Path) -> Result<usize, TrendrError> {
+    // One transaction, so all tables come from the same snapshot
+    let backup = with_db_tx(|tx| {
+        Ok(Backup {
+            schema_version: SCHEMA_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            topics: dump_table(tx, "topics")?,
+            creators: dump_table(tx, "creators")?,
+            content: dump_table(tx, "content")?,
+            content_topics: dump_table(tx, "content_topics")?,
+        })
+    })?;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(path)?;
+    serde_json::to_writer(BufWriter::new(file), &backup)?;
+
+    Ok(BACKUP_TABLES
+        .iter()
+        .map(|table| backup.rows(table.name).len())
+        .sum())
+}
+
+/// Load a backup written by `export_backup` without overwriting anything: rows that
+/// already exist (by id or unique key) are skipped, and references to them are pointed
+/// at the existing rows. Everything is imported in one transaction.
+pub fn import_backup(path: &Path) -> Result<ImportResult, TrendrError> {
+    let file = File::open(path)?;
+    let backup: Backup = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| TrendrError::Parse(format!("Not a Trendr backup: {}", e)))?;
+
+    if backup.schema_version != SCHEMA_VERSION {
+        return Err(TrendrError::Parse(format!(
+            "Backup is from schema version {}, but this database is at version {}",
+            backup.schema_version, SCHEMA_VERSION
+        )));
+    }
+
+    with_db_tx(|tx| {
+        // Topic parents and duplicated content may appear after the rows pointing at
+        // them; checked at commit instead
+        tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+
+        let mut id_maps: HashMap<&str, HashMap<String, String>> = HashMap::new();
+        let mut tables = Vec::new();
+        for table in &BACKUP_TABLES {
+            let (inserted, skipped) =
+                import_table(tx, table, backup.rows(table.name), &mut id_maps)?;
+            tables.push(TableImportCount {
+                table: table.name.to_string(),
+                inserted,
+                skipped,
+            });
+        }
+
+        for table in &BACKUP_TABLES {
+            resolve_self_references(tx, table, &id_maps)?;
+        }
+
+        Ok(ImportResult {
+            inserted: tables.iter().map(|t| t.inserted).sum(),
+            skipped: tables.iter().map(|t| t.skipped).sum(),
+            tables,
+        })
+    })
+}
+
+/// Re-point references within a table (topic parents, duplicated content) at rows that
+/// were matched to existing ones, then drop any the backup couldn't satisfy
+fn resolve_self_references(
+    conn: &Connection,
+    table: &BackupTable,
+    id_maps: &HashMap<&str, HashMap<String, String>>,
+) -> Result<(), rusqlite::Error> {
+    for (column, _) in table
+        .references
+        .iter()
+        .filter(|(_, referenced)| *referenced == table.name)
+    {
+        if let Some(ids) = id_maps.get(table.name) {
+            for (from, to) in ids.iter().filter(|(from, to)| from != to) {
+                conn.execute(
+                    &format!("UPDATE {0} SET {1} = ?1 WHERE {1} = ?2", table.name, column),
+                    [to, from],
+                )?;
+            }
+        }
+        conn.execute(
+            &format!(
+                "UPDATE {0} SET {1} = NULL WHERE {1} IS NOT NULL AND {1} NOT IN (SELECT id FROM {0})",
+                table.name, column
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn dump_table(conn: &Connection, table: &str) -> Result<Vec<BackupRow>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {}", table))?;
+    let columns: Vec<String> = stmt
+        .column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+
+    let mut rows = stmt.query([])?;
+    let mut dumped = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut values = Map::new();
+        for (i, column) in columns.iter().enumerate() {
+            let value = match row.get_ref(i)? {
+                ValueRef::Null => Value::Null,
+                ValueRef::Integer(n) => Value::from(n),
+                ValueRef::Real(f) => Value::from(f),
+                ValueRef::Text(text) | ValueRef::Blob(text) => {
+                    Value::from(String::from_utf8_lossy(text))
+                }
+            };
+            values.insert(column.clone(), value);
+        }
+        dumped.push(values);
+    }
+    Ok(dumped)
+}
+
+/// Insert one table's rows, returning (inserted, skipped)
+fn import_table<'a>(
+    conn: &Connection,
+    table: &'a BackupTable,
+    rows: &[BackupRow],
+    id_maps: &mut HashMap<&'a str, HashMap<String, String>>,
+) -> Result<(usize, usize), rusqlite::Error> {
+    let known_columns = table_columns(conn, table.name)?;
+
+    let mut inserted = 0;
+    let mut skipped = 0;
+    for row in rows {
+        let mut row = row.clone();
+        if let Some(unknown) = row.keys().find(|column| !known_columns.contains(*column)) {
+            return Err(rusqlite::Error::InvalidColumnName(format!(
+                "{}.{}",
+                table.name, unknown
+            )));
+        }
+
+        // Point references at the ids their rows were imported under, skipping rows
+        // whose referenced row doesn't exist. Topic parents are resolved afterwards.
+        let mut resolved = true;
+        for (column, referenced) in table.references {
+            let Some(id) = row.get(*column).and_then(Value::as_str).map(str::to_string) else {
+                continue;
+            };
+            // Resolved once the whole table is in
+            if *referenced == table.name {
+                continue;
+            }
+            let id = id_maps
+                .get(referenced)
+                .and_then(|ids| ids.get(&id))
+                .cloned()
+                .unwrap_or(id);
+            if !row_exists(conn, referenced, &id)? {
+                resolved = false;
+                break;
+            }
+            row.insert(column.to_string(), Value::from(id));
+        }
+        if !resolved {
+            skipped += 1;
+            continue;
+        }
+
+        let columns: Vec<&String> = row.keys().collect();
+        let sql = format!(
+            "INSERT OR IGNORE INTO {} ({}) VALUES ({})",
+            table.name,
+            columns
+                .iter()
+                .map(|c| c.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            (1..=columns.len())
+                .map(|i| format!("?{}", i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let values = row.values().map(json_to_sql);
+        let changed = conn.execute(&sql, params_from_iter(values))?;
+
+        if changed > 0 {
+            inserted += 1;
+        } else {
+            skipped += 1;
+        }
+
+        if let Some(id) = row.get("id").and_then(Value::as_str) {
+            let existing_id = if changed > 0 {
+                None
+            } else {
+                find_by_natural_key(conn, table, &row)?
+            };
+            id_maps.entry(table.name).or_default().insert(
+                id.to_string(),
+                existing_id.unwrap_or_else(|| id.to_string()),
+            );
+        }
+    }
+
+    Ok((inserted, skipped))
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<HashSet<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(columns)
+}
+
+fn row_exists(conn: &Connection, table: &str, id: &str) -> Result<bool, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", table),
+        [id],
+        |row| row.get(0),
+    )
+}
+
+/// Id of the existing row sharing this row's natural key, if any
+fn find_by_natural_key(
+    conn: &Connection,
+    table: &BackupTable,
+    row: &BackupRow,
+) -> Result<Option<String>, rusqlite::Error> {
+    if table.natural_key.is_empty() {
+        return Ok(None);
+    }
+
+    let conditions = table
+        .natural_key
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("{} = ?{}", column, i + 1))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let values = table
+        .natural_key
+        .iter()
+        .map(|column| json_to_sql(row.get(*column).unwrap_or(&Value::Null)));
+
+    conn.query_row(
+        &format!("SELECT id FROM {} WHERE {}", table.name, conditions),
+        params_from_iter(values),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn json_to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::with_db;
+
+    fn seed() {
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug, keywords) VALUES ('lang', 'Languages', 'languages', '[]');
+                   INSERT INTO topics (id, name, slug, parent_topic_id, keywords)
+                   VALUES ('rust', 'Rust', 'rust', 'lang', '["rust"]');
+                   INSERT INTO creators (id, platform, platform_id, username) VALUES ('ferris', 'x', 'u1', 'ferris');
+                   INSERT INTO content (id, platform, platform_id, creator_id, content_type, text_content)
+                   VALUES ('original', 'x', 't1', 'ferris', 'post', 'rust 2.0 is out'),
+                          ('repost', 'x', 't2', 'ferris', 'post', 'rust 2.0 is out!');
+                   UPDATE content SET duplicate_of = 'original' WHERE id = 'repost';
+                   INSERT INTO content_topics (content_id, topic_id, confidence)
+                   VALUES ('original', 'rust', 1.0), ('repost', 'rust', 0.5);"#,
+            )
+        })
+        .unwrap();
+    }
+
+    fn wipe() {
+        with_db(|conn| {
+            conn.execute_batch("DELETE FROM content_topics; DELETE FROM content; DELETE FROM creators; DELETE FROM topics;")
+        })
+        .unwrap();
+    }
+
+    fn dump_all() -> Vec<Vec<BackupRow>> {
+        with_db(|conn| {
+            BACKUP_TABLES
+                .iter()
+                .map(|table| dump_table(conn, table.name))
+                .collect()
+        })
+        .unwrap()
+    }
+
+    fn duplicate_of(id: &str) -> Option<String> {
+        with_db(|conn| {
+            conn.query_row("SELECT duplicate_of FROM content WHERE id = ?1", [id], |row| {
+                row.get(0)
+            })
+        })
+        .unwrap()
+    }
+
+    fn backup_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("trendr-backup-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn export_wipe_import_restores_every_row() {
+        let _db = crate::database::test_database();
+        seed();
+        let before = dump_all();
+        let path = backup_path();
+
+        assert_eq!(export_backup(&path).unwrap(), 7);
+        wipe();
+        let result = import_backup(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!((result.inserted, result.skipped), (7, 0));
+        assert_eq!(dump_all(), before);
+        assert_eq!(duplicate_of("repost").as_deref(), Some("original"));
+    }
+
+    #[test]
+    fn duplicates_follow_content_matched_to_existing_rows() {
+        let _db = crate::database::test_database();
+        seed();
+        let path = backup_path();
+        export_backup(&path).unwrap();
+        wipe();
+
+        // The original was collected again under a new id; the repost's target is gone
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO content (id, platform, platform_id, content_type, text_content)
+                   VALUES ('recollected', 'x', 't1', 'post', 'rust 2.0 is out');"#,
+            )
+        })
+        .unwrap();
+        import_backup(&path).unwrap();
+        assert_eq!(duplicate_of("repost").as_deref(), Some("recollected"));
+
+        let mut backup: Backup = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+        backup.content.retain(|row| row["id"] == "repost");
+        backup.content[0].insert("platform_id".to_string(), Value::from("t3"));
+        backup.content[0].insert("id".to_string(), Value::from("orphan"));
+        backup.content_topics.clear();
+        serde_json::to_writer(File::create(&path).unwrap(), &backup).unwrap();
+        with_db(|conn| conn.execute("DELETE FROM content WHERE id = 'recollected'", [])).unwrap();
+
+        import_backup(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(duplicate_of("orphan"), None);
+    }
+}
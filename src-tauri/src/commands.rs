@@ -1,11 +1,37 @@
-use crate::database::with_db;
+use crate::alerts;
+use crate::authenticity::{self, TrendAuthenticity};
+use crate::backup::{self, ImportResult};
+use crate::content::{status, ContentType, ItemOutcome};
+use crate::creators::{self, Creator, CreatorGroup, CreatorSummary};
+use crate::database::{self, has_fulltext_index, slugify, with_db, with_db_tx};
+use crate::entities::{self, EntityTagResult, TrendingEntity};
+use crate::error::TrendrError;
+use crate::export::{self, ContentExportFilter, ExportFormat};
+use crate::forecast::{self, TopicForecast};
+use crate::hashtags::{self, TrendingTag};
+use crate::log_stream;
+use crate::progress;
 use crate::reddit;
+use crate::retention::{self, PruneResult};
+use crate::refresh::{self, RefreshResult};
+use crate::scheduler;
+use crate::sentiment::{self, SentimentShift};
+use crate::snapshots::{self, SnapshotComparison, SnapshotSummary};
 use crate::x;
 use crate::youtube;
-use crate::settings::{self, AppSettings};
-use rusqlite::params;
+use crate::settings::{self, AlertSettings, AppSettings, RedditCredentials, XCredentials, YouTubeCredentials};
+use crate::topics::{
+    self, parse_keywords, parse_string_list, Keyword, KeywordStats, KeywordSuggestion, ReextractResult, TextAnalysis,
+};
+use crate::trends::{self, SimulatedAlert, TrendScore};
+use chrono::Datelike;
+use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
 
 // Collection state
 static COLLECTION_STATE: once_cell::sync::Lazy<Mutex<CollectionState>> =
@@ -16,6 +42,56 @@ struct CollectionState {
     is_running: bool,
     last_run_at: Option<String>,
     last_error: Option<String>,
+    /// Set by `cancel_collection`; collectors check it before each subreddit or query
+    #[serde(skip)]
+    cancel: Arc<AtomicBool>,
+}
+
+/// `last_error` for a run stopped by `cancel_collection`
+const CANCELLED_ERROR: &str = "Collection cancelled";
+
+/// Mark a collection as running and return its cancel flag, or fail if one already is.
+fn begin_collection() -> Result<Arc<AtomicBool>, TrendrError> {
+    let mut state = COLLECTION_STATE.lock().map_err(|e| TrendrError::Other(e.to_string()))?;
+    if state.is_running {
+        return Err(TrendrError::Other("Collection already in progress".to_string()));
+    }
+    state.is_running = true;
+    state.last_error = None;
+    state.cancel.store(false, Ordering::Relaxed);
+    Ok(Arc::clone(&state.cancel))
+}
+
+/// Record the end of a collection started with `begin_collection`.
+fn finish_collection(error: Option<String>) -> Result<(), TrendrError> {
+    let mut state = COLLECTION_STATE.lock().map_err(|e| TrendrError::Other(e.to_string()))?;
+    state.is_running = false;
+    state.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+    state.last_error = error;
+    Ok(())
+}
+
+/// What a finished run leaves in `last_error`: its failure, or that it was cancelled.
+fn run_error(result: &Result<CollectionResult, TrendrError>) -> Option<String> {
+    match result {
+        Ok(r) if r.cancelled => Some(CANCELLED_ERROR.to_string()),
+        Ok(_) => None,
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// SQLite expression for when a content row (aliased `c`) happened. Platforms store
+/// `published_at` as ISO-8601 with a `Z` suffix; `datetime()` normalizes those to UTC
+/// so they compare correctly against `datetime('now', ...)`. Rows without a usable
+/// publish time fall back to when they were collected.
+const CONTENT_TIME_SQL: &str = "COALESCE(datetime(c.published_at), datetime(c.collected_at))";
+
+/// Topic ids bound per `IN (...)` query, well under SQLite's host parameter limit
+const TOPIC_ID_CHUNK: usize = 500;
+
+/// Build a `datetime('now', ?)` modifier for a look-back window of `days`.
+fn days_ago_modifier(days: i64) -> String {
+    format!("-{} days", days.max(0))
 }
 
 // Response types
@@ -26,11 +102,19 @@ pub struct Topic {
     slug: String,
     #[serde(rename = "parentTopicId")]
     parent_topic_id: Option<String>,
+    keywords: Vec<Keyword>,
     aliases: Vec<String>,
+    /// Terms that keep content from being linked to the topic
+    exclusions: Vec<String>,
     #[serde(rename = "createdAt")]
     created_at: String,
     #[serde(rename = "contentCount")]
     content_count: Option<i64>,
+    #[serde(rename = "trendScore")]
+    trend_score: Option<f64>,
+    /// Hashtag the topic was automatically created from; None for user-defined topics
+    #[serde(rename = "autoCreatedFrom")]
+    auto_created_from: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -49,10 +133,124 @@ pub struct Content {
     engagement_likes: i64,
     #[serde(rename = "engagementComments")]
     engagement_comments: i64,
+    /// Retweets on X; always 0 for Reddit and YouTube, which don't report shares
+    #[serde(rename = "engagementShares")]
+    engagement_shares: i64,
+    /// Views or impressions, when the platform reports them (not Reddit)
+    #[serde(rename = "engagementViews")]
+    engagement_views: Option<i64>,
     #[serde(rename = "publishedAt")]
     published_at: Option<String>,
     #[serde(rename = "collectedAt")]
     collected_at: String,
+    #[serde(rename = "mediaUrl")]
+    media_url: Option<String>,
+    #[serde(rename = "thumbnailUrl")]
+    thumbnail_url: Option<String>,
+    status: String,
+    /// Earlier content linking the same page, when URL dedup flagged this as a repost
+    #[serde(rename = "duplicateOf")]
+    duplicate_of: Option<String>,
+    /// Link to the original post, tweet or video
+    #[serde(rename = "url")]
+    url: Option<String>,
+    /// Video length for YouTube rows; None for other platforms and live streams
+    #[serde(rename = "durationSeconds")]
+    duration_seconds: Option<i64>,
+    /// Language X detected for the tweet; None for other platforms
+    language: Option<String>,
+}
+
+/// Columns read into `Content`, for a content table aliased `c`
+const CONTENT_COLUMNS: &str = "c.id, c.platform, c.platform_id, c.creator_id, c.content_type, c.text_content,
+    c.engagement_likes, c.engagement_comments, c.engagement_shares, c.engagement_views,
+    c.published_at, c.collected_at, c.media_url, c.thumbnail_url, c.status, c.duplicate_of, c.url,
+    c.duration_seconds, c.language";
+
+fn content_from_row(row: &rusqlite::Row) -> Result<Content, rusqlite::Error> {
+    Ok(Content {
+        id: row.get(0)?,
+        platform: row.get(1)?,
+        platform_id: row.get(2)?,
+        creator_id: row.get(3)?,
+        content_type: row.get(4)?,
+        text_content: row.get(5)?,
+        engagement_likes: row.get(6)?,
+        engagement_comments: row.get(7)?,
+        engagement_shares: row.get::<_, Option<i64>>(8)?.unwrap_or(0),
+        engagement_views: row.get(9)?,
+        published_at: row.get(10)?,
+        collected_at: row.get(11)?,
+        media_url: row.get(12)?,
+        thumbnail_url: row.get(13)?,
+        status: row.get(14)?,
+        duplicate_of: row.get(15)?,
+        url: row.get(16)?,
+        duration_seconds: row.get(17)?,
+        language: row.get(18)?,
+    })
+}
+
+/// A topic's content item with its engagement, raw and relative to the creator's audience
+#[derive(Debug, Serialize)]
+pub struct TopContent {
+    #[serde(flatten)]
+    content: Content,
+    /// Likes + comments + shares
+    engagement: i64,
+    #[serde(rename = "followerCount")]
+    follower_count: Option<i64>,
+    /// engagement / followerCount; None when the follower count is unknown or 0
+    #[serde(rename = "engagementRate")]
+    engagement_rate: Option<f64>,
+    /// Set when ranking by rate, for items that had to be ranked by raw engagement
+    #[serde(rename = "rankedByRaw")]
+    ranked_by_raw: bool,
+}
+
+/// A creator with their most recent content
+#[derive(Debug, Serialize)]
+pub struct CreatorDetails {
+    #[serde(flatten)]
+    creator: CreatorSummary,
+    #[serde(rename = "recentContent")]
+    recent_content: Vec<Content>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContentDateRange {
+    #[serde(rename = "earliestPublishedAt")]
+    earliest_published_at: Option<String>,
+    #[serde(rename = "latestPublishedAt")]
+    latest_published_at: Option<String>,
+    #[serde(rename = "earliestCollectedAt")]
+    earliest_collected_at: Option<String>,
+    #[serde(rename = "latestCollectedAt")]
+    latest_collected_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContentTypeCount {
+    #[serde(rename = "contentType")]
+    content_type: String,
+    count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagResult {
+    scanned: u32,
+    tagged: u32,
+    #[serde(rename = "linksCreated")]
+    links_created: u32,
+}
+
+/// Emitted after each batch of `reextract_topics`
+const REEXTRACT_PROGRESS_EVENT: &str = "reextract://progress";
+
+#[derive(Debug, Clone, Serialize)]
+struct ReextractProgress {
+    processed: u32,
+    total: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -78,10 +276,145 @@ pub struct DashboardStats {
     total_creators: i64,
     #[serde(rename = "contentLast7Days")]
     content_last_7_days: i64,
+    #[serde(rename = "windowDays")]
+    window_days: i64,
+    /// Content collected in the last `window_days`
+    #[serde(rename = "contentInWindow")]
+    content_in_window: i64,
+    /// Topics with the most content collected in the window
     #[serde(rename = "topTopics")]
     top_topics: Vec<TopicCount>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SlugPreview {
+    slug: String,
+    #[serde(rename = "slugTaken")]
+    slug_taken: bool,
+    #[serde(rename = "nameTaken")]
+    name_taken: bool,
+    #[serde(rename = "conflictingTopicId")]
+    conflicting_topic_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveTopic {
+    id: String,
+    name: String,
+    slug: String,
+    #[serde(rename = "recentCount")]
+    recent_count: i64,
+    #[serde(rename = "lastActivityAt")]
+    last_activity_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopicStats {
+    #[serde(rename = "topicId")]
+    topic_id: String,
+    name: String,
+    #[serde(rename = "contentCount")]
+    content_count: i64,
+    #[serde(rename = "recentCount")]
+    recent_count: i64,
+    #[serde(rename = "totalEngagement")]
+    total_engagement: i64,
+    #[serde(rename = "trendScore")]
+    trend_score: Option<f64>,
+    #[serde(rename = "lastSeenAt")]
+    last_seen_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopicCreator {
+    #[serde(rename = "creatorId")]
+    creator_id: String,
+    platform: String,
+    username: String,
+    #[serde(rename = "displayName")]
+    display_name: Option<String>,
+    /// None when the platform doesn't report followers (Reddit) or it wasn't fetched
+    #[serde(rename = "followerCount")]
+    follower_count: Option<i64>,
+    #[serde(rename = "contentCount")]
+    content_count: i64,
+    /// Share of the topic's content this creator produced, 0–1
+    share: f64,
+    #[serde(rename = "totalEngagement")]
+    total_engagement: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlatformShare {
+    platform: String,
+    count: i64,
+    /// Fraction of the topic's content on this platform, 0–1
+    share: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimilarTopic {
+    id: String,
+    name: String,
+    #[serde(rename = "sharedContent")]
+    shared_content: i64,
+    jaccard: f64,
+    cosine: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesPoint {
+    /// Day (`YYYY-MM-DD`) or, for weekly buckets, the Monday that starts the week
+    bucket: String,
+    count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfidenceBin {
+    /// Inclusive lower edge
+    min: f64,
+    /// Exclusive upper edge, except the last bin which includes 1.0
+    max: f64,
+    count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HierarchyLinkSuggestion {
+    #[serde(rename = "topicAId")]
+    topic_a_id: String,
+    #[serde(rename = "topicAName")]
+    topic_a_name: String,
+    #[serde(rename = "topicBId")]
+    topic_b_id: String,
+    #[serde(rename = "topicBName")]
+    topic_b_name: String,
+    /// How many times the pair was extracted from the same item
+    frequency: i64,
+    #[serde(rename = "sharedContent")]
+    shared_content: i64,
+    /// Shared content as a fraction of the smaller topic's content
+    overlap: f64,
+    /// The topic with more content, the natural parent if the user links them
+    #[serde(rename = "suggestedParentId")]
+    suggested_parent_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopicCooccurrence {
+    #[serde(rename = "topicAId")]
+    topic_a_id: String,
+    #[serde(rename = "topicAName")]
+    topic_a_name: String,
+    #[serde(rename = "topicBId")]
+    topic_b_id: String,
+    #[serde(rename = "topicBName")]
+    topic_b_name: String,
+    /// How many times the pair was extracted from the same item
+    frequency: i64,
+    #[serde(rename = "lastSeen")]
+    last_seen: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TopicCount {
     name: String,
@@ -96,180 +429,657 @@ pub struct CollectionStatus {
     last_run_at: Option<String>,
     #[serde(rename = "lastError")]
     last_error: Option<String>,
+    /// Where each platform's next run starts, when runs are capped by `maxQueriesPerRun`
+    rotation: Vec<RotationPosition>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotationPosition {
+    platform: String,
+    offset: usize,
+    #[serde(rename = "totalSources")]
+    total_sources: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchedulerStatus {
+    #[serde(rename = "isRunning")]
+    is_running: bool,
+    #[serde(rename = "isPaused")]
+    is_paused: bool,
+    #[serde(rename = "intervalMinutes")]
+    interval_minutes: u32,
+    #[serde(rename = "lastRunAt")]
+    last_run_at: Option<String>,
+    #[serde(rename = "nextRunAt")]
+    next_run_at: Option<String>,
+}
+
+/// Per-platform results of `run_all_collection`, plus totals. A platform is null when it
+/// isn't configured or its run failed; failures are listed in `errors`.
+#[derive(Debug, Serialize)]
+pub struct AllCollectionResult {
+    reddit: Option<CollectionResult>,
+    x: Option<CollectionResult>,
+    youtube: Option<CollectionResult>,
+    #[serde(rename = "postsCollected")]
+    posts_collected: u32,
+    #[serde(rename = "topicsExtracted")]
+    topics_extracted: u32,
+    cancelled: bool,
+    errors: Vec<String>,
 }
 
+/// Emitted after each subreddit or query a collection run finishes
+const COLLECTION_PROGRESS_EVENT: &str = "collection-progress";
+
+/// Emitted with the `CollectionResult` when a platform's collection run completes
+const COLLECTION_FINISHED_EVENT: &str = "collection-finished";
+
 #[derive(Debug, Serialize)]
 pub struct CollectionResult {
     #[serde(rename = "postsCollected")]
     posts_collected: u32,
     #[serde(rename = "topicsExtracted")]
     topics_extracted: u32,
+    /// Per-item record of what was stored, skipped, filtered or failed
+    outcomes: Vec<ItemOutcome>,
+    /// Whether the run stopped early because the platform rate limited it
+    #[serde(rename = "rateLimited")]
+    rate_limited: bool,
+    /// Subreddits or queries not collected because of the rate limit
+    #[serde(rename = "skippedSources")]
+    skipped_sources: Vec<String>,
+    /// Whether `cancel_collection` stopped the run early; the counts cover what was
+    /// collected before that
+    cancelled: bool,
+}
+
+/// Window used when checking for sentiment shifts after a collection run
+const SENTIMENT_SHIFT_DAYS: i64 = 7;
+const SENTIMENT_SHIFT_THRESHOLD: f64 = 0.3;
+
+/// Most content scanned for entities after a single collection run
+const ENTITY_TAG_BATCH: i64 = 500;
+
+/// Count one platform's run toward its stalled-collection streak, given how many new items
+/// it stored (or the error it failed with).
+fn record_run(platform: &str, stored: Result<u32, &TrendrError>, settings: &AppSettings) {
+    // A failed run found nothing either, so it counts toward the stalled streak
+    let stored_count = stored.unwrap_or(0);
+    if let Err(e) =
+        alerts::record_collection_run(platform, stored_count, settings.stalled_run_threshold)
+    {
+        log::warn!("Failed to record {} collection run: {}", platform, e);
+    }
+}
+
+/// Times `after_collection` has run, so tests can check it runs once per collection
+#[cfg(test)]
+static AFTER_COLLECTION_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Post-processing shared by every collection run, once all of its platforms are done.
+/// When every platform failed only old alerts are cleaned up. Failures here are logged so
+/// they never fail the run itself.
+fn after_collection(any_succeeded: bool, settings: &AppSettings) {
+    #[cfg(test)]
+    AFTER_COLLECTION_CALLS.fetch_add(1, Ordering::Relaxed);
+
+    match alerts::cleanup_read_alerts(settings.alert_retention_days) {
+        Ok(0) => {}
+        Ok(n) => log::info!("Removed {} read alerts past retention", n),
+        Err(e) => log::warn!("Failed to clean up old alerts: {}", e),
+    }
+
+    if !any_succeeded {
+        return;
+    }
+
+    trends::refresh_after_collection(&settings.trend_score);
+
+    match trends::detect_trends(&settings.alerts) {
+        Ok(0) => {}
+        Ok(n) => log::info!("Raised {} trend spike alerts", n),
+        Err(e) => log::warn!("Failed to check for trend spikes: {}", e),
+    }
+
+    if let Err(e) = entities::tag_content(ENTITY_TAG_BATCH) {
+        log::warn!("Failed to extract entities: {}", e);
+    }
+
+    match hashtags::auto_create_topics(&settings.auto_create_topics) {
+        Ok(created) => {
+            for topic in created {
+                let message = format!(
+                    "Created topic {} from a hashtag used in {} posts. Review it on the Topics page.",
+                    topic.name, topic.mentions
+                );
+                if let Err(e) = alerts::create_alert_once("topic_auto_created", Some(&topic.topic_id), &message, 24) {
+                    log::warn!("Failed to raise alert for auto-created topic {}: {}", topic.name, e);
+                }
+            }
+        }
+        Err(e) => log::warn!("Failed to auto-create topics from hashtags: {}", e),
+    }
+
+    match sentiment::detect_shifts(SENTIMENT_SHIFT_DAYS, SENTIMENT_SHIFT_THRESHOLD)
+        .and_then(|shifts| sentiment::alert_on_shifts(&shifts, SENTIMENT_SHIFT_DAYS))
+    {
+        Ok(0) => {}
+        Ok(n) => log::info!("Raised {} sentiment shift alerts", n),
+        Err(e) => log::warn!("Failed to check sentiment shifts: {}", e),
+    }
+}
+
+/// This run's share of the sources, capped by `maxQueriesPerRun`.
+fn source_window(platform: &str, sources: &[String], settings: &AppSettings) -> Vec<String> {
+    progress::window(platform, sources, settings.max_queries_per_run).unwrap_or_else(|e| {
+        log::warn!("Failed to load {} collection rotation: {}", platform, e);
+        sources.to_vec()
+    })
+}
+
+/// Sources still to collect this cycle, so an interrupted run resumes where it stopped.
+/// Falls back to every source if progress can't be read.
+fn pending_sources(platform: &str, sources: &[String], settings: &AppSettings) -> Vec<String> {
+    progress::pending(platform, sources, settings.collection_interval_minutes).unwrap_or_else(|e| {
+        log::warn!("Failed to load {} collection progress: {}", platform, e);
+        sources.to_vec()
+    })
+}
+
+fn finish_cycle(platform: &str, window: &[String], all_sources: &[String]) {
+    match progress::finish_cycle(platform, window, all_sources) {
+        Ok(true) => log::info!("Completed {} collection cycle across {} sources", platform, window.len()),
+        Ok(false) => {}
+        Err(e) => log::warn!("Failed to update {} collection progress: {}", platform, e),
+    }
+}
+
+// Logging commands
+/// Directory the log file is written to.
+#[tauri::command]
+pub fn get_log_path(app: tauri::AppHandle) -> Result<String, TrendrError> {
+    use tauri::Manager;
+    app.path()
+        .app_log_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .map_err(|e| TrendrError::Other(e.to_string()))
+}
+
+/// Set how verbose the live `log://line` event stream is ("off" through "trace").
+#[tauri::command]
+pub fn set_log_stream_level(level: String) -> Result<(), TrendrError> {
+    log_stream::set_level(&level)
 }
 
 // Settings commands
 #[tauri::command]
-pub fn get_settings() -> Result<AppSettings, String> {
+pub fn get_settings() -> Result<AppSettings, TrendrError> {
     Ok(settings::load_settings())
 }
 
 #[tauri::command]
-pub fn save_settings(settings_data: AppSettings) -> Result<(), String> {
+pub fn save_settings(settings_data: AppSettings) -> Result<(), TrendrError> {
     settings::save_settings(&settings_data)
 }
 
 #[tauri::command]
-pub async fn test_reddit_connection() -> Result<bool, String> {
+pub async fn test_reddit_connection() -> Result<bool, TrendrError> {
     let settings = settings::load_settings();
     match settings.reddit {
         Some(credentials) => reddit::test_connection(&credentials).await,
-        None => Err("Reddit credentials not configured".to_string()),
+        None => Err(TrendrError::NotConfigured("Reddit credentials not configured".to_string())),
     }
 }
 
 #[tauri::command]
-pub async fn test_x_connection(bearer_token: String) -> Result<bool, String> {
+pub async fn test_x_connection(bearer_token: String) -> Result<bool, TrendrError> {
     x::test_connection(&bearer_token).await
 }
 
 #[tauri::command]
-pub async fn run_x_collection() -> Result<CollectionResult, String> {
+pub async fn run_x_collection(app: tauri::AppHandle) -> Result<CollectionResult, TrendrError> {
     let settings = settings::load_settings();
 
-    let credentials = settings.x.ok_or("X credentials not configured")?;
-    let queries = settings.x_queries;
-
-    if queries.is_empty() {
-        return Err("No X search queries configured. Add some topics to search for.".to_string());
+    let credentials = settings
+        .x
+        .clone()
+        .ok_or_else(|| TrendrError::NotConfigured("X credentials not configured".to_string()))?;
+    if settings.x_queries.is_empty() {
+        return Err(TrendrError::NotConfigured(
+            "No X search queries configured. Add some topics to search for.".to_string(),
+        ));
     }
 
-    // Check if already running
-    {
-        let state = COLLECTION_STATE.lock().map_err(|e| e.to_string())?;
-        if state.is_running {
-            return Err("Collection already in progress".to_string());
-        }
-    }
+    let cancel = begin_collection()?;
+    let result = collect_x(&app, &credentials, &settings, &cancel).await;
+    after_collection(result.is_ok(), &settings);
+    emit_finished(&app, &result);
+    finish_collection(run_error(&result))?;
+    result
+}
+
+async fn collect_x(
+    app: &tauri::AppHandle,
+    credentials: &XCredentials,
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+) -> Result<CollectionResult, TrendrError> {
+    let queries = &settings.x_queries;
+    let window = source_window("x", queries, settings);
+    let pending = pending_sources("x", &window, settings);
+    let result = x::collect(credentials, &pending, settings, cancel, |progress| {
+        let _ = app.emit(COLLECTION_PROGRESS_EVENT, progress);
+    })
+    .await;
+    finish_cycle("x", &window, queries);
 
-    let result = x::collect(&credentials, &queries).await;
+    record_run("x", result.as_ref().map(|r| r.posts_collected), settings);
 
     result.map(|r| CollectionResult {
         posts_collected: r.posts_collected,
         topics_extracted: r.topics_extracted,
+        outcomes: r.outcomes,
+        rate_limited: r.rate_limited,
+        skipped_sources: r.skipped_sources,
+        cancelled: r.cancelled,
     })
 }
 
 #[tauri::command]
-pub async fn test_youtube_connection(api_key: String) -> Result<bool, String> {
+pub async fn test_youtube_connection(api_key: String) -> Result<bool, TrendrError> {
     youtube::test_connection(&api_key).await
 }
 
 #[tauri::command]
-pub async fn run_youtube_collection() -> Result<CollectionResult, String> {
+pub async fn run_youtube_collection(app: tauri::AppHandle) -> Result<CollectionResult, TrendrError> {
     let settings = settings::load_settings();
 
-    let credentials = settings.youtube.ok_or("YouTube credentials not configured")?;
-    let queries = settings.youtube_queries;
-
-    if queries.is_empty() {
-        return Err("No YouTube search queries configured. Add some topics to search for.".to_string());
+    let credentials = settings
+        .youtube
+        .clone()
+        .ok_or_else(|| TrendrError::NotConfigured("YouTube credentials not configured".to_string()))?;
+    if settings.youtube_queries.is_empty() {
+        return Err(TrendrError::NotConfigured(
+            "No YouTube search queries configured. Add some topics to search for.".to_string(),
+        ));
     }
 
-    // Check if already running
-    {
-        let state = COLLECTION_STATE.lock().map_err(|e| e.to_string())?;
-        if state.is_running {
-            return Err("Collection already in progress".to_string());
-        }
-    }
+    let cancel = begin_collection()?;
+    let result = collect_youtube(&app, &credentials, &settings, &cancel).await;
+    after_collection(result.is_ok(), &settings);
+    emit_finished(&app, &result);
+    finish_collection(run_error(&result))?;
+    result
+}
+
+async fn collect_youtube(
+    app: &tauri::AppHandle,
+    credentials: &YouTubeCredentials,
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+) -> Result<CollectionResult, TrendrError> {
+    let queries = &settings.youtube_queries;
+    let window = source_window("youtube", queries, settings);
+    let pending = pending_sources("youtube", &window, settings);
+    let result = youtube::collect(credentials, &pending, settings, cancel, |progress| {
+        let _ = app.emit(COLLECTION_PROGRESS_EVENT, progress);
+    })
+    .await;
+    finish_cycle("youtube", &window, queries);
 
-    let result = youtube::collect(&credentials, &queries).await;
+    record_run("youtube", result.as_ref().map(|r| r.posts_collected), settings);
 
     result.map(|r| CollectionResult {
         posts_collected: r.posts_collected,
         topics_extracted: r.topics_extracted,
+        outcomes: r.outcomes,
+        rate_limited: r.rate_limited,
+        skipped_sources: r.skipped_sources,
+        cancelled: r.cancelled,
     })
 }
 
 // Collection commands
 #[tauri::command]
-pub async fn run_collection() -> Result<CollectionResult, String> {
+pub async fn run_collection(app: tauri::AppHandle) -> Result<CollectionResult, TrendrError> {
     let settings = settings::load_settings();
 
     let credentials = settings
         .reddit
-        .ok_or("Reddit credentials not configured")?;
+        .clone()
+        .ok_or_else(|| TrendrError::NotConfigured("Reddit credentials not configured".to_string()))?;
+
+    let cancel = begin_collection()?;
+    let result = collect_reddit(&app, &credentials, &settings, &cancel).await;
+    after_collection(result.is_ok(), &settings);
+    emit_finished(&app, &result);
+    finish_collection(run_error(&result))?;
+    result
+}
 
-    // Update state
-    {
-        let mut state = COLLECTION_STATE.lock().map_err(|e| e.to_string())?;
-        if state.is_running {
-            return Err("Collection already in progress".to_string());
-        }
-        state.is_running = true;
-        state.last_error = None;
+async fn collect_reddit(
+    app: &tauri::AppHandle,
+    credentials: &RedditCredentials,
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+) -> Result<CollectionResult, TrendrError> {
+    let window = source_window("reddit", &settings.subreddits, settings);
+    let pending = pending_sources("reddit", &window, settings);
+    let result = reddit::collect(credentials, &pending, settings, cancel, |progress| {
+        let _ = app.emit(COLLECTION_PROGRESS_EVENT, progress);
+    })
+    .await;
+    finish_cycle("reddit", &window, &settings.subreddits);
+
+    record_run("reddit", result.as_ref().map(|r| r.posts_collected), settings);
+
+    result.map(|r| CollectionResult {
+        posts_collected: r.posts_collected,
+        topics_extracted: r.topics_extracted,
+        outcomes: r.outcomes,
+        rate_limited: r.rate_limited,
+        skipped_sources: r.skipped_sources,
+        cancelled: r.cancelled,
+    })
+}
+
+/// Collect every platform that has credentials and sources configured, in one run.
+/// Platforms hit different hosts, so they run concurrently. A platform that isn't
+/// configured is left out; one that fails is listed in `errors`.
+#[tauri::command]
+pub async fn run_all_collection(app: tauri::AppHandle) -> Result<AllCollectionResult, TrendrError> {
+    let settings = settings::load_settings();
+    if !any_platform_configured(&settings) {
+        return Err(TrendrError::NotConfigured(
+            "No platform is configured. Add credentials and sources in Settings.".to_string(),
+        ));
     }
 
-    let result = reddit::collect(&credentials, &settings.subreddits).await;
+    let cancel = begin_collection()?;
+    let result = collect_all(&app, &settings, &cancel).await;
+    finish_collection(all_run_error(&result, &cancel))?;
+    Ok(result)
+}
 
-    // Update state after completion
-    {
-        let mut state = COLLECTION_STATE.lock().map_err(|e| e.to_string())?;
-        state.is_running = false;
-        state.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+/// One scheduled pass over every configured platform, the same as `run_all_collection`.
+/// Skipped while another collection is running.
+pub(crate) async fn run_scheduled_collection(
+    app: &tauri::AppHandle,
+    settings: &AppSettings,
+) -> Result<(), TrendrError> {
+    if !any_platform_configured(settings) {
+        log::debug!("Skipping scheduled collection: no platform is configured");
+        return Ok(());
+    }
+
+    let cancel = match begin_collection() {
+        Ok(cancel) => cancel,
+        Err(e) => {
+            log::info!("Skipping scheduled collection: {}", e);
+            return Ok(());
+        }
+    };
+
+    let result = collect_all(app, settings, &cancel).await;
+    log::info!("Scheduled collection stored {} items", result.posts_collected);
+    finish_collection(all_run_error(&result, &cancel))
+}
+
+fn any_platform_configured(settings: &AppSettings) -> bool {
+    (settings.reddit.is_some() && !settings.subreddits.is_empty())
+        || (settings.x.is_some() && !settings.x_queries.is_empty())
+        || (settings.youtube.is_some() && !settings.youtube_queries.is_empty())
+}
+
+/// Tell the frontend a platform's run completed, once its post-processing is done
+fn emit_finished(app: &tauri::AppHandle, result: &Result<CollectionResult, TrendrError>) {
+    if let Ok(result) = result {
+        let _ = app.emit(COLLECTION_FINISHED_EVENT, result);
+    }
+}
 
-        match &result {
-            Ok(_) => state.last_error = None,
-            Err(e) => state.last_error = Some(e.clone()),
+async fn collect_all(
+    app: &tauri::AppHandle,
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+) -> AllCollectionResult {
+    let reddit = async {
+        match settings.reddit.as_ref().filter(|_| !settings.subreddits.is_empty()) {
+            Some(credentials) => Some(collect_reddit(app, credentials, settings, cancel).await),
+            None => None,
+        }
+    };
+    let x = async {
+        match settings.x.as_ref().filter(|_| !settings.x_queries.is_empty()) {
+            Some(credentials) => Some(collect_x(app, credentials, settings, cancel).await),
+            None => None,
+        }
+    };
+    let youtube = async {
+        match settings.youtube.as_ref().filter(|_| !settings.youtube_queries.is_empty()) {
+            Some(credentials) => Some(collect_youtube(app, credentials, settings, cancel).await),
+            None => None,
         }
+    };
+    let result = join_platforms(reddit, x, youtube, settings).await;
+    for run in [&result.reddit, &result.x, &result.youtube].into_iter().flatten() {
+        let _ = app.emit(COLLECTION_FINISHED_EVENT, run);
     }
+    result
+}
 
-    result.map(|r| CollectionResult {
-        posts_collected: r.posts_collected,
-        topics_extracted: r.topics_extracted,
-    })
+/// A platform's part of a multi-platform run; None when the platform isn't configured
+type PlatformRun = Option<Result<CollectionResult, TrendrError>>;
+
+/// Wait for every platform's run, total them up, then post-process once for the whole run.
+async fn join_platforms(
+    reddit: impl Future<Output = PlatformRun>,
+    x: impl Future<Output = PlatformRun>,
+    youtube: impl Future<Output = PlatformRun>,
+    settings: &AppSettings,
+) -> AllCollectionResult {
+    let (reddit, x, youtube) = tokio::join!(reddit, x, youtube);
+
+    let mut errors = Vec::new();
+    let mut keep = |platform: &str, result: PlatformRun| match result? {
+        Ok(r) => Some(r),
+        Err(e) => {
+            log::warn!("{} collection failed: {}", platform, e);
+            errors.push(format!("{}: {}", platform, e));
+            None
+        }
+    };
+    let reddit = keep("reddit", reddit);
+    let x = keep("x", x);
+    let youtube = keep("youtube", youtube);
+
+    let runs = [&reddit, &x, &youtube];
+    after_collection(runs.iter().any(|r| r.is_some()), settings);
+
+    AllCollectionResult {
+        posts_collected: runs.iter().filter_map(|r| r.as_ref()).map(|r| r.posts_collected).sum(),
+        topics_extracted: runs.iter().filter_map(|r| r.as_ref()).map(|r| r.topics_extracted).sum(),
+        cancelled: runs.iter().filter_map(|r| r.as_ref()).any(|r| r.cancelled),
+        reddit,
+        x,
+        youtube,
+        errors,
+    }
+}
+
+/// What a finished multi-platform run leaves in `last_error`
+fn all_run_error(result: &AllCollectionResult, cancel: &AtomicBool) -> Option<String> {
+    if result.cancelled || cancel.load(Ordering::Relaxed) {
+        Some(CANCELLED_ERROR.to_string())
+    } else {
+        (!result.errors.is_empty()).then(|| result.errors.join("; "))
+    }
+}
+
+/// Stop the running collection after the subreddit or query it is on. The run returns
+/// what it collected so far, and its `lastError` reads "Collection cancelled".
+#[tauri::command]
+pub fn cancel_collection() -> Result<(), TrendrError> {
+    let state = COLLECTION_STATE.lock().map_err(|e| TrendrError::Other(e.to_string()))?;
+    if !state.is_running {
+        return Err(TrendrError::Other("No collection in progress".to_string()));
+    }
+    state.cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// When the last collection run finished, if any has this session.
+pub(crate) fn last_collection_run_at() -> Result<Option<String>, TrendrError> {
+    Ok(COLLECTION_STATE
+        .lock()
+        .map_err(|e| TrendrError::Other(e.to_string()))?
+        .last_run_at
+        .clone())
 }
 
+/// Refresh engagement metrics for content that is due under the age-based schedule.
 #[tauri::command]
-pub fn get_collection_status() -> Result<CollectionStatus, String> {
-    let state = COLLECTION_STATE.lock().map_err(|e| e.to_string())?;
+pub async fn refresh_engagement() -> Result<RefreshResult, TrendrError> {
+    let settings = settings::load_settings();
+    refresh::refresh_due_engagement(&settings).await
+}
+
+#[tauri::command]
+pub fn get_collection_status() -> Result<CollectionStatus, TrendrError> {
+    let settings = settings::load_settings();
+    let mut rotation = Vec::new();
+    if settings.max_queries_per_run > 0 {
+        for (platform, sources) in [
+            ("reddit", &settings.subreddits),
+            ("x", &settings.x_queries),
+            ("youtube", &settings.youtube_queries),
+        ] {
+            if sources.len() > settings.max_queries_per_run as usize {
+                rotation.push(RotationPosition {
+                    platform: platform.to_string(),
+                    offset: progress::rotation_offset(platform)? % sources.len(),
+                    total_sources: sources.len(),
+                });
+            }
+        }
+    }
+
+    let state = COLLECTION_STATE.lock().map_err(|e| TrendrError::Other(e.to_string()))?;
     Ok(CollectionStatus {
         is_running: state.is_running,
         last_run_at: state.last_run_at.clone(),
         last_error: state.last_error.clone(),
+        rotation,
+    })
+}
+
+#[tauri::command]
+pub fn get_scheduler_status() -> Result<SchedulerStatus, TrendrError> {
+    let interval_minutes = settings::load_settings().collection_interval_minutes;
+    let last_run_at = last_collection_run_at()?;
+    let state = scheduler::state()?;
+    let next_run_at = scheduler::next_run_at(&state, last_run_at.as_deref(), interval_minutes, chrono::Utc::now());
+
+    Ok(SchedulerStatus {
+        is_running: state.running,
+        is_paused: state.paused,
+        interval_minutes,
+        last_run_at,
+        next_run_at: next_run_at.map(|t| t.to_rfc3339()),
+    })
+}
+
+/// Resume interval-based collection. The next run is due one interval after the last one,
+/// or one interval from now if nothing has run since launch.
+#[tauri::command]
+pub fn start_scheduler() -> Result<SchedulerStatus, TrendrError> {
+    scheduler::set_running(true)?;
+    get_scheduler_status()
+}
+
+/// Stop interval-based collection. A run already in progress is left to finish.
+#[tauri::command]
+pub fn stop_scheduler() -> Result<SchedulerStatus, TrendrError> {
+    scheduler::set_running(false)?;
+    get_scheduler_status()
+}
+
+/// Clear stored incremental-collection cursors so the next run does a full pull.
+/// With a `source` (subreddit or search query) only that cursor is cleared; otherwise
+/// every cursor for the platform is. Newest-item watermarks and in-progress cycle state
+/// are cleared alongside. Returns how many rows were removed.
+#[tauri::command]
+pub fn reset_cursor(platform: String, source: Option<String>) -> Result<usize, TrendrError> {
+    with_db(|conn| {
+        let mut removed = 0;
+        for table in ["collection_cursors", "source_watermarks", "collection_progress"] {
+            removed += match &source {
+                Some(source) => conn.execute(
+                    &format!("DELETE FROM {} WHERE platform = ?1 AND source = ?2", table),
+                    params![&platform, source],
+                )?,
+                None => conn.execute(
+                    &format!("DELETE FROM {} WHERE platform = ?1", table),
+                    params![&platform],
+                )?,
+            };
+        }
+        Ok(removed)
     })
 }
 
 // Topics commands
 #[tauri::command]
-pub fn get_topics(limit: Option<i64>, offset: Option<i64>) -> Result<Vec<Topic>, String> {
+pub fn get_topics(
+    limit: Option<i64>,
+    offset: Option<i64>,
+    sort_by: Option<String>,
+) -> Result<Vec<Topic>, TrendrError> {
     let limit = limit.unwrap_or(50);
     let offset = offset.unwrap_or(0);
+    let order_by = match sort_by.as_deref() {
+        Some("trend") => "trend_score IS NULL, trend_score DESC, content_count DESC",
+        Some("name") => "t.name COLLATE NOCASE ASC",
+        _ => "content_count DESC",
+    };
 
     with_db(|conn| {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"SELECT t.id, t.name, t.slug, t.parent_topic_id, t.aliases, t.created_at,
-               COUNT(ct.content_id) as content_count
+               COUNT(ct.content_id) as content_count, s.score as trend_score, t.auto_created_from,
+               t.exclusions, t.keywords
                FROM topics t
                LEFT JOIN content_topics ct ON t.id = ct.topic_id
+               LEFT JOIN topic_trend_scores s ON t.id = s.topic_id
                GROUP BY t.id
-               ORDER BY content_count DESC
+               ORDER BY {}
                LIMIT ?1 OFFSET ?2"#,
-        )?;
+            order_by
+        ))?;
 
         let rows = stmt.query_map(params![limit, offset], |row| {
+            let id: String = row.get(0)?;
             let aliases_json: Option<String> = row.get(4)?;
-            let aliases: Vec<String> = aliases_json
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default();
+            let aliases = parse_string_list(aliases_json.as_deref(), &id, "aliases");
+            let exclusions_json: Option<String> = row.get(9)?;
+            let exclusions = parse_string_list(exclusions_json.as_deref(), &id, "exclusions");
+            let keywords_json: Option<String> = row.get(10)?;
+            let keywords = parse_keywords(keywords_json.as_deref(), &id);
 
             Ok(Topic {
-                id: row.get(0)?,
+                id,
                 name: row.get(1)?,
                 slug: row.get(2)?,
                 parent_topic_id: row.get(3)?,
+                keywords,
                 aliases,
+                exclusions,
                 created_at: row.get::<_, String>(5)?,
                 content_count: row.get(6)?,
+                trend_score: row.get(7)?,
+                auto_created_from: row.get(8)?,
             })
         })?;
 
@@ -282,59 +1092,76 @@ pub fn get_topics(limit: Option<i64>, offset: Option<i64>) -> Result<Vec<Topic>,
 }
 
 #[tauri::command]
-pub fn get_topic_details(topic_id: String) -> Result<Topic, String> {
+pub fn get_topic_details(topic_id: String) -> Result<Topic, TrendrError> {
     with_db(|conn| {
         let mut stmt = conn.prepare(
             r#"SELECT t.id, t.name, t.slug, t.parent_topic_id, t.aliases, t.created_at,
-               (SELECT COUNT(*) FROM content_topics WHERE topic_id = t.id) as content_count
+               (SELECT COUNT(*) FROM content_topics WHERE topic_id = t.id) as content_count,
+               (SELECT score FROM topic_trend_scores WHERE topic_id = t.id) as trend_score,
+               t.auto_created_from, t.exclusions, t.keywords
                FROM topics t WHERE t.id = ?1"#,
         )?;
 
         stmt.query_row(params![&topic_id], |row| {
+            let id: String = row.get(0)?;
             let aliases_json: Option<String> = row.get(4)?;
-            let aliases: Vec<String> = aliases_json
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default();
+            let aliases = parse_string_list(aliases_json.as_deref(), &id, "aliases");
+            let exclusions_json: Option<String> = row.get(9)?;
+            let exclusions = parse_string_list(exclusions_json.as_deref(), &id, "exclusions");
+            let keywords_json: Option<String> = row.get(10)?;
+            let keywords = parse_keywords(keywords_json.as_deref(), &id);
 
             Ok(Topic {
-                id: row.get(0)?,
+                id,
                 name: row.get(1)?,
                 slug: row.get(2)?,
                 parent_topic_id: row.get(3)?,
+                keywords,
                 aliases,
+                exclusions,
                 created_at: row.get(5)?,
                 content_count: row.get(6)?,
+                trend_score: row.get(7)?,
+                auto_created_from: row.get(8)?,
             })
         })
     })
 }
 
 #[tauri::command]
-pub fn search_topics(query: String) -> Result<Vec<Topic>, String> {
+pub fn search_topics(query: String) -> Result<Vec<Topic>, TrendrError> {
     let search_term = format!("%{}%", query);
 
     with_db(|conn| {
         let mut stmt = conn.prepare(
-            r#"SELECT id, name, slug, parent_topic_id, aliases, created_at
+            r#"SELECT id, name, slug, parent_topic_id, aliases, created_at, auto_created_from, exclusions,
+               keywords
                FROM topics
                WHERE name LIKE ?1 OR slug LIKE ?1
                LIMIT 20"#,
         )?;
 
         let rows = stmt.query_map(params![&search_term], |row| {
+            let id: String = row.get(0)?;
             let aliases_json: Option<String> = row.get(4)?;
-            let aliases: Vec<String> = aliases_json
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default();
+            let aliases = parse_string_list(aliases_json.as_deref(), &id, "aliases");
+            let exclusions_json: Option<String> = row.get(7)?;
+            let exclusions = parse_string_list(exclusions_json.as_deref(), &id, "exclusions");
+            let keywords_json: Option<String> = row.get(8)?;
+            let keywords = parse_keywords(keywords_json.as_deref(), &id);
 
             Ok(Topic {
-                id: row.get(0)?,
+                id,
                 name: row.get(1)?,
                 slug: row.get(2)?,
                 parent_topic_id: row.get(3)?,
+                keywords,
                 aliases,
+                exclusions,
                 created_at: row.get(5)?,
                 content_count: None,
+                trend_score: None,
+                auto_created_from: row.get(6)?,
             })
         })?;
 
@@ -346,71 +1173,1002 @@ pub fn search_topics(query: String) -> Result<Vec<Topic>, String> {
     })
 }
 
-// Content commands
+/// Show the slug a new topic name would get and whether it collides with an existing topic.
 #[tauri::command]
-pub fn get_content(limit: Option<i64>, offset: Option<i64>) -> Result<Vec<Content>, String> {
-    let limit = limit.unwrap_or(50);
-    let offset = offset.unwrap_or(0);
+pub fn preview_topic_slug(name: String) -> Result<SlugPreview, TrendrError> {
+    let name = name.trim().to_string();
+    let slug = slugify(&name);
 
     with_db(|conn| {
         let mut stmt = conn.prepare(
-            r#"SELECT id, platform, platform_id, creator_id, content_type, text_content,
-               engagement_likes, engagement_comments, published_at, collected_at
-               FROM content
-               ORDER BY collected_at DESC
-               LIMIT ?1 OFFSET ?2"#,
+            "SELECT id, slug = ?1, name = ?2 COLLATE NOCASE FROM topics WHERE slug = ?1 OR name = ?2 COLLATE NOCASE LIMIT 1",
         )?;
+        let mut rows = stmt.query(params![&slug, &name])?;
+
+        let preview = match rows.next()? {
+            Some(row) => SlugPreview {
+                slug: slug.clone(),
+                slug_taken: row.get(1)?,
+                name_taken: row.get(2)?,
+                conflicting_topic_id: Some(row.get(0)?),
+            },
+            None => SlugPreview {
+                slug: slug.clone(),
+                slug_taken: false,
+                name_taken: false,
+                conflicting_topic_id: None,
+            },
+        };
+        Ok(preview)
+    })
+}
 
-        let rows = stmt.query_map(params![limit, offset], |row| {
-            Ok(Content {
-                id: row.get(0)?,
-                platform: row.get(1)?,
-                platform_id: row.get(2)?,
-                creator_id: row.get(3)?,
-                content_type: row.get(4)?,
-                text_content: row.get(5)?,
-                engagement_likes: row.get(6)?,
-                engagement_comments: row.get(7)?,
-                published_at: row.get(8)?,
-                collected_at: row.get(9)?,
-            })
-        })?;
+/// Recompute and cache trend scores for every topic.
+#[tauri::command]
+pub fn compute_trend_scores() -> Result<Vec<TrendScore>, TrendrError> {
+    let settings = settings::load_settings();
+    let mut scores = trends::compute_trend_scores(&settings.trend_score)?;
+    scores.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(scores)
+}
 
-        let mut content = Vec::new();
-        for row in rows {
-            content.push(row?);
-        }
-        Ok(content)
-    })
+/// Cached trend scores with their component signals, highest first.
+#[tauri::command]
+pub fn get_trend_scores(limit: Option<i64>) -> Result<Vec<TrendScore>, TrendrError> {
+    trends::get_trend_scores(limit.unwrap_or(50))
 }
 
+/// Which spike alerts would have fired over the last `days` days (default 30) with the
+/// given spike `threshold` and the rest of the alert settings, without raising any. For
+/// calibrating sensitivity.
 #[tauri::command]
-pub fn get_content_by_topic(topic_id: String, limit: Option<i64>) -> Result<Vec<Content>, String> {
-    let limit = limit.unwrap_or(20);
+pub fn simulate_trend_detection(threshold: f64, days: Option<i64>) -> Result<Vec<SimulatedAlert>, TrendrError> {
+    if threshold.is_nan() || threshold <= 0.0 {
+        return Err(TrendrError::Other("Threshold must be greater than 0".to_string()));
+    }
+    let config = AlertSettings {
+        trend_spike_ratio: threshold,
+        ..settings::load_settings().alerts
+    };
+    trends::simulate_spikes(&config, days.unwrap_or(30))
+}
 
-    with_db(|conn| {
-        let mut stmt = conn.prepare(
-            r#"SELECT c.id, c.platform, c.platform_id, c.creator_id, c.content_type, c.text_content,
-               c.engagement_likes, c.engagement_comments, c.published_at, c.collected_at
-               FROM content c
+/// Project a topic's daily mentions `horizon_days` ahead (default 7) from its recent trend.
+#[tauri::command]
+pub fn get_topic_forecast(topic_id: String, horizon_days: Option<i64>) -> Result<TopicForecast, TrendrError> {
+    forecast::forecast_topic(&topic_id, horizon_days.unwrap_or(7))
+}
+
+/// Check a topic's last `hours` hours (default 24) of content for signs of bot or
+/// coordinated activity.
+#[tauri::command]
+pub fn get_trend_authenticity(topic_id: String, hours: Option<i64>) -> Result<TrendAuthenticity, TrendrError> {
+    authenticity::assess_topic(&topic_id, hours.unwrap_or(24))
+}
+
+/// Topics with content in the last `days` days, ranked by that recent volume.
+#[tauri::command]
+pub fn get_active_topics(days: Option<i64>, limit: Option<i64>) -> Result<Vec<ActiveTopic>, TrendrError> {
+    let modifier = days_ago_modifier(days.unwrap_or(7));
+    let limit = limit.unwrap_or(20);
+
+    with_db(|conn| {
+        let sql = format!(
+            r#"SELECT t.id, t.name, t.slug, COUNT(*) as recent_count, MAX({time}) as last_activity
+               FROM topics t
+               JOIN content_topics ct ON t.id = ct.topic_id
+               JOIN content c ON c.id = ct.content_id
+               WHERE {time} > datetime('now', ?1) AND c.status != ?3
+               GROUP BY t.id
+               ORDER BY recent_count DESC, last_activity DESC
+               LIMIT ?2"#,
+            time = CONTENT_TIME_SQL
+        );
+        let mut stmt = conn.prepare(&sql)?;
+
+        let rows = stmt.query_map(params![&modifier, limit, status::DELETED], |row| {
+            Ok(ActiveTopic {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                slug: row.get(2)?,
+                recent_count: row.get(3)?,
+                last_activity_at: row.get(4)?,
+            })
+        })?;
+
+        let mut topics = Vec::new();
+        for row in rows {
+            topics.push(row?);
+        }
+        Ok(topics)
+    })
+}
+
+/// Stats for a watchlist of topics in one pass: all-time and last-`days` content counts,
+/// total engagement, trend score and when each was last seen. Results follow the order of
+/// `topic_ids`; unknown ids are skipped.
+#[tauri::command]
+pub fn get_topics_stats(topic_ids: Vec<String>, days: Option<i64>) -> Result<Vec<TopicStats>, TrendrError> {
+    let modifier = days_ago_modifier(days.unwrap_or(7));
+
+    let mut stats = with_db(|conn| {
+        let mut stats = Vec::new();
+        for chunk in topic_ids.chunks(TOPIC_ID_CHUNK) {
+            let placeholders = (0..chunk.len())
+                .map(|i| format!("?{}", i + 2))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let sql = format!(
+                r#"SELECT t.id, t.name,
+                   COUNT(c.id) as content_count,
+                   COALESCE(SUM(CASE WHEN {time} > datetime('now', ?1) THEN 1 ELSE 0 END), 0) as recent_count,
+                   COALESCE(SUM(c.engagement_likes + c.engagement_comments + c.engagement_shares), 0) as total_engagement,
+                   s.score,
+                   MAX({time}) as last_seen
+                   FROM topics t
+                   LEFT JOIN content_topics ct ON ct.topic_id = t.id
+                   LEFT JOIN content c ON c.id = ct.content_id AND c.status != '{deleted}'
+                   LEFT JOIN topic_trend_scores s ON s.topic_id = t.id
+                   WHERE t.id IN ({placeholders})
+                   GROUP BY t.id"#,
+                time = CONTENT_TIME_SQL,
+                deleted = status::DELETED,
+                placeholders = placeholders
+            );
+            let mut stmt = conn.prepare(&sql)?;
+
+            let values = std::iter::once(&modifier).chain(chunk.iter());
+            let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+                Ok(TopicStats {
+                    topic_id: row.get(0)?,
+                    name: row.get(1)?,
+                    content_count: row.get(2)?,
+                    recent_count: row.get(3)?,
+                    total_engagement: row.get(4)?,
+                    trend_score: row.get(5)?,
+                    last_seen_at: row.get(6)?,
+                })
+            })?;
+
+            for row in rows {
+                stats.push(row?);
+            }
+        }
+        Ok(stats)
+    })?;
+
+    stats.sort_by_key(|s| topic_ids.iter().position(|id| *id == s.topic_id));
+    Ok(stats)
+}
+
+/// Creators ranked by how much of a topic's content they produced.
+#[tauri::command]
+pub fn get_topic_creators(topic_id: String, limit: Option<i64>) -> Result<Vec<TopicCreator>, TrendrError> {
+    let limit = limit.unwrap_or(10);
+
+    with_db(|conn| {
+        let topic_total: i64 = conn.query_row(
+            r#"SELECT COUNT(*) FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2"#,
+            params![&topic_id, status::DELETED],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT cr.id, cr.platform, cr.username, cr.display_name, cr.follower_count,
+               COUNT(*) as content_count,
+               COALESCE(SUM(c.engagement_likes + c.engagement_comments + c.engagement_shares), 0) as engagement
+               FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               JOIN creators cr ON cr.id = c.creator_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2
+               GROUP BY cr.id
+               ORDER BY content_count DESC, engagement DESC, cr.follower_count DESC
+               LIMIT ?3"#,
+        )?;
+
+        let rows = stmt.query_map(params![&topic_id, status::DELETED, limit], |row| {
+            let content_count: i64 = row.get(5)?;
+            Ok(TopicCreator {
+                creator_id: row.get(0)?,
+                platform: row.get(1)?,
+                username: row.get(2)?,
+                display_name: row.get(3)?,
+                follower_count: row.get(4)?,
+                content_count,
+                share: if topic_total > 0 { content_count as f64 / topic_total as f64 } else { 0.0 },
+                total_engagement: row.get(6)?,
+            })
+        })?;
+
+        let mut creators = Vec::new();
+        for row in rows {
+            creators.push(row?);
+        }
+        Ok(creators)
+    })
+}
+
+/// How a topic's content splits across platforms. Platforms with credentials configured
+/// are listed even when they have none of the topic's content.
+#[tauri::command]
+pub fn get_topic_platform_breakdown(topic_id: String) -> Result<Vec<PlatformShare>, TrendrError> {
+    let settings = settings::load_settings();
+    let configured = [
+        ("reddit", settings.reddit.is_some()),
+        ("x", settings.x.is_some()),
+        ("youtube", settings.youtube.is_some()),
+    ];
+
+    let counts: Vec<(String, i64)> = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT c.platform, COUNT(*)
+               FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2
+               GROUP BY c.platform"#,
+        )?;
+        let rows = stmt.query_map(params![&topic_id, status::DELETED], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut counts = Vec::new();
+        for row in rows {
+            counts.push(row?);
+        }
+        Ok(counts)
+    })?;
+
+    let total: i64 = counts.iter().map(|(_, count)| count).sum();
+    let mut breakdown: Vec<PlatformShare> = configured
+        .iter()
+        .filter(|(platform, is_configured)| *is_configured || counts.iter().any(|(p, _)| p == platform))
+        .map(|(platform, _)| platform.to_string())
+        .chain(
+            counts
+                .iter()
+                .map(|(platform, _)| platform.clone())
+                .filter(|platform| !configured.iter().any(|(p, _)| p == platform)),
+        )
+        .map(|platform| {
+            let count = counts
+                .iter()
+                .find(|(p, _)| *p == platform)
+                .map(|(_, count)| *count)
+                .unwrap_or(0);
+            PlatformShare {
+                platform,
+                count,
+                share: if total > 0 { count as f64 / total as f64 } else { 0.0 },
+            }
+        })
+        .collect();
+
+    breakdown.sort_by_key(|b| std::cmp::Reverse(b.count));
+    Ok(breakdown)
+}
+
+/// Content volume for a topic over the last `days` days, grouped into `"day"` or `"week"`
+/// buckets by published date (collected date when unknown). Buckets with no content are
+/// returned with a count of 0 so the series can be charted directly.
+#[tauri::command]
+pub fn get_topic_timeseries(topic_id: String, days: i64, bucket: String) -> Result<Vec<TimeseriesPoint>, TrendrError> {
+    let (bucket_sql, step_days) = match bucket.as_str() {
+        "day" => ("strftime('%Y-%m-%d', {time})", 1),
+        // Step back to the Monday on or before the date
+        "week" => ("strftime('%Y-%m-%d', {time}, '-6 days', 'weekday 1')", 7),
+        other => return Err(TrendrError::Other(format!("Unknown bucket: {} (expected \"day\" or \"week\")", other))),
+    };
+
+    let today = chrono::Utc::now().date_naive();
+    let first_day = today - chrono::Duration::days(days.clamp(1, 3650) - 1);
+    let first_bucket = if step_days == 7 {
+        first_day - chrono::Duration::days(first_day.weekday().num_days_from_monday() as i64)
+    } else {
+        first_day
+    };
+
+    let counts: HashMap<String, i64> = with_db(|conn| {
+        let sql = format!(
+            r#"SELECT {bucket} as bucket, COUNT(*)
+               FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2 AND date({time}) >= ?3
+               GROUP BY bucket"#,
+            bucket = bucket_sql.replace("{time}", CONTENT_TIME_SQL),
+            time = CONTENT_TIME_SQL
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params![&topic_id, status::DELETED, first_day.format("%Y-%m-%d").to_string()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+        )?;
+
+        let mut counts = HashMap::new();
+        for row in rows {
+            let (bucket, count) = row?;
+            counts.insert(bucket, count);
+        }
+        Ok(counts)
+    })?;
+
+    let mut series = Vec::new();
+    let mut day = first_bucket;
+    while day <= today {
+        let bucket = day.format("%Y-%m-%d").to_string();
+        let count = counts.get(&bucket).copied().unwrap_or(0);
+        series.push(TimeseriesPoint { bucket, count });
+        day += chrono::Duration::days(step_days);
+    }
+    Ok(series)
+}
+
+/// Width of each bin in `get_topic_confidence_distribution`
+const CONFIDENCE_BIN_WIDTH: f64 = 0.2;
+
+/// Histogram of the confidence scores on a topic's content links. A topic whose links
+/// pile up in the lowest bin usually has keywords that are too broad.
+#[tauri::command]
+pub fn get_topic_confidence_distribution(topic_id: String) -> Result<Vec<ConfidenceBin>, TrendrError> {
+    let bin_count = (1.0 / CONFIDENCE_BIN_WIDTH).round() as i64;
+
+    let counts: Vec<(i64, i64)> = with_db(|conn| {
+        // The epsilon keeps scores on a bin edge (0.6 / 0.2 = 2.999...) in the upper bin;
+        // scores of exactly 1.0 fall into the top bin rather than one past it
+        let mut stmt = conn.prepare(
+            r#"SELECT MIN(CAST(confidence / ?2 + 1e-9 AS INTEGER), ?3 - 1) as bin, COUNT(*)
+               FROM content_topics
+               WHERE topic_id = ?1
+               GROUP BY bin"#,
+        )?;
+        let rows = stmt.query_map(params![&topic_id, CONFIDENCE_BIN_WIDTH, bin_count], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
+        let mut counts = Vec::new();
+        for row in rows {
+            counts.push(row?);
+        }
+        Ok(counts)
+    })?;
+
+    Ok((0..bin_count)
+        .map(|bin| ConfidenceBin {
+            min: bin as f64 * CONFIDENCE_BIN_WIDTH,
+            max: (bin + 1) as f64 * CONFIDENCE_BIN_WIDTH,
+            count: counts
+                .iter()
+                .filter(|(b, _)| *b == bin)
+                .map(|(_, count)| count)
+                .sum(),
+        })
+        .collect())
+}
+
+/// Match counts and last-matched dates for every keyword of every topic, for finding
+/// dead or over-broad keywords.
+#[tauri::command]
+pub fn get_all_keyword_stats() -> Result<KeywordStats, TrendrError> {
+    topics::keyword_stats()
+}
+
+/// Frequent words in a topic's content that could be added as keywords.
+#[tauri::command]
+pub fn suggest_keywords(topic_id: String, limit: Option<usize>) -> Result<Vec<KeywordSuggestion>, TrendrError> {
+    topics::suggest_keywords(&topic_id, limit.unwrap_or(20))
+}
+
+/// Rank other topics by how much of their linked content overlaps with the given topic's.
+#[tauri::command]
+pub fn get_similar_topics(topic_id: String, limit: Option<i64>) -> Result<Vec<SimilarTopic>, TrendrError> {
+    let limit = limit.unwrap_or(10).max(0) as usize;
+
+    with_db(|conn| {
+        let target_size: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM content_topics WHERE topic_id = ?1",
+            params![&topic_id],
+            |row| row.get(0),
+        )?;
+
+        if target_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        // Intersections come from a self-join on content_id restricted to the target's
+        // content, so only topics that share at least one item are ever considered.
+        let mut stmt = conn.prepare(
+            r#"SELECT t.id, t.name, shared.shared_count,
+               (SELECT COUNT(*) FROM content_topics WHERE topic_id = t.id) as topic_size
+               FROM (
+                   SELECT other.topic_id, COUNT(*) as shared_count
+                   FROM content_topics target
+                   JOIN content_topics other ON target.content_id = other.content_id
+                   WHERE target.topic_id = ?1 AND other.topic_id != ?1
+                   GROUP BY other.topic_id
+               ) shared
+               JOIN topics t ON t.id = shared.topic_id"#,
+        )?;
+
+        let rows = stmt.query_map(params![&topic_id], |row| {
+            let shared: i64 = row.get(2)?;
+            let topic_size: i64 = row.get(3)?;
+            let union = (target_size + topic_size - shared) as f64;
+
+            Ok(SimilarTopic {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                shared_content: shared,
+                jaccard: if union > 0.0 { shared as f64 / union } else { 0.0 },
+                cosine: shared as f64 / ((target_size * topic_size) as f64).sqrt(),
+            })
+        })?;
+
+        let mut similar = Vec::new();
+        for row in rows {
+            similar.push(row?);
+        }
+
+        similar.sort_by(|a, b| {
+            b.jaccard
+                .partial_cmp(&a.jaccard)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.shared_content.cmp(&a.shared_content))
+        });
+        similar.truncate(limit);
+        Ok(similar)
+    })
+}
+
+/// Topic pairs that co-occur at least `min_frequency` times where neither is the other's
+/// parent: candidates for `set_topic_parent`. Read-only; nothing changes until the user
+/// confirms a link.
+#[tauri::command]
+pub fn suggest_hierarchy_links(min_frequency: Option<i64>) -> Result<Vec<HierarchyLinkSuggestion>, TrendrError> {
+    let min_frequency = min_frequency.unwrap_or(5).max(1);
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT a.id, a.name, b.id, b.name, co.frequency,
+               (SELECT COUNT(*) FROM content_topics x
+                JOIN content_topics y ON x.content_id = y.content_id
+                WHERE x.topic_id = a.id AND y.topic_id = b.id) as shared_count,
+               (SELECT COUNT(*) FROM content_topics WHERE topic_id = a.id) as a_size,
+               (SELECT COUNT(*) FROM content_topics WHERE topic_id = b.id) as b_size
+               FROM topic_cooccurrences co
+               JOIN topics a ON a.id = co.topic_a_id
+               JOIN topics b ON b.id = co.topic_b_id
+               WHERE co.frequency >= ?1
+               AND a.parent_topic_id IS NOT b.id AND b.parent_topic_id IS NOT a.id
+               ORDER BY co.frequency DESC"#,
+        )?;
+
+        let rows = stmt.query_map(params![min_frequency], |row| {
+            let a_id: String = row.get(0)?;
+            let b_id: String = row.get(2)?;
+            let shared: i64 = row.get(5)?;
+            let a_size: i64 = row.get(6)?;
+            let b_size: i64 = row.get(7)?;
+            let smaller = a_size.min(b_size);
+
+            Ok(HierarchyLinkSuggestion {
+                suggested_parent_id: if b_size > a_size { b_id.clone() } else { a_id.clone() },
+                topic_a_id: a_id,
+                topic_a_name: row.get(1)?,
+                topic_b_id: b_id,
+                topic_b_name: row.get(3)?,
+                frequency: row.get(4)?,
+                shared_content: shared,
+                overlap: if smaller > 0 { shared as f64 / smaller as f64 } else { 0.0 },
+            })
+        })?;
+
+        let mut suggestions = Vec::new();
+        for row in rows {
+            suggestions.push(row?);
+        }
+        Ok(suggestions)
+    })
+}
+
+/// Topic pairs extracted from the same items, most frequent first. With a `topic_id`,
+/// only pairs involving that topic are returned.
+#[tauri::command]
+pub fn get_cooccurrences(
+    topic_id: Option<String>,
+    min_frequency: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<TopicCooccurrence>, TrendrError> {
+    let min_frequency = min_frequency.unwrap_or(1).max(1);
+    let limit = limit.unwrap_or(50);
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT a.id, a.name, b.id, b.name, co.frequency, co.last_seen
+               FROM topic_cooccurrences co
+               JOIN topics a ON a.id = co.topic_a_id
+               JOIN topics b ON b.id = co.topic_b_id
+               WHERE co.frequency >= ?1
+               AND (?2 IS NULL OR co.topic_a_id = ?2 OR co.topic_b_id = ?2)
+               ORDER BY co.frequency DESC, co.last_seen DESC
+               LIMIT ?3"#,
+        )?;
+
+        let rows = stmt.query_map(params![min_frequency, &topic_id, limit], |row| {
+            Ok(TopicCooccurrence {
+                topic_a_id: row.get(0)?,
+                topic_a_name: row.get(1)?,
+                topic_b_id: row.get(2)?,
+                topic_b_name: row.get(3)?,
+                frequency: row.get(4)?,
+                last_seen: row.get(5)?,
+            })
+        })?;
+
+        let mut pairs = Vec::new();
+        for row in rows {
+            pairs.push(row?);
+        }
+        Ok(pairs)
+    })
+}
+
+/// Set or clear a topic's parent. Rejects links that would make a topic its own ancestor.
+#[tauri::command]
+pub fn set_topic_parent(topic_id: String, parent_topic_id: Option<String>) -> Result<(), TrendrError> {
+    if let Some(parent_id) = &parent_topic_id {
+        // Walk up from the new parent; reaching the topic itself would close a cycle
+        let closes_cycle = with_db(|conn| {
+            let mut current = Some(parent_id.clone());
+            while let Some(id) = current {
+                if id == topic_id {
+                    return Ok(true);
+                }
+                current = conn
+                    .query_row(
+                        "SELECT parent_topic_id FROM topics WHERE id = ?1",
+                        params![&id],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .flatten();
+            }
+            Ok(false)
+        })?;
+
+        if closes_cycle {
+            return Err(TrendrError::Other("A topic cannot be nested under itself or one of its subtopics".to_string()));
+        }
+    }
+
+    let updated = with_db(|conn| {
+        conn.execute(
+            "UPDATE topics SET parent_topic_id = ?1 WHERE id = ?2",
+            params![&parent_topic_id, &topic_id],
+        )
+    })?;
+
+    if updated == 0 {
+        return Err(TrendrError::Other(format!("Topic {} not found", topic_id)));
+    }
+    Ok(())
+}
+
+/// Trim aliases or exclusions, dropping empties and case-insensitive repeats while
+/// keeping their order and original casing.
+fn clean_aliases(aliases: &[String]) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::with_capacity(aliases.len());
+    for alias in aliases {
+        let alias = alias.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !alias.is_empty() && !cleaned.iter().any(|a| a.eq_ignore_ascii_case(&alias)) {
+            cleaned.push(alias);
+        }
+    }
+    cleaned
+}
+
+/// Error describing another topic that already uses this name or slug, if any
+fn topic_name_conflict(
+    conn: &rusqlite::Connection,
+    name: &str,
+    slug: &str,
+    exclude_id: Option<&str>,
+) -> Result<Option<String>, rusqlite::Error> {
+    let conflict: Option<(String, bool)> = conn
+        .query_row(
+            r#"SELECT name, name = ?1 COLLATE NOCASE FROM topics
+               WHERE (slug = ?2 OR name = ?1 COLLATE NOCASE) AND id IS NOT ?3
+               LIMIT 1"#,
+            params![name, slug, exclude_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    Ok(conflict.map(|(existing, same_name)| {
+        if same_name {
+            format!("A topic named '{}' already exists", existing)
+        } else {
+            format!("Topic '{}' already uses the slug '{}'", existing, slug)
+        }
+    }))
+}
+
+/// Create a user-defined topic. Keywords are bare strings or `{ term, weight }` objects
+/// and are normalized the same way seeded ones are; names and slugs must be unique.
+#[tauri::command]
+pub fn create_topic(
+    name: String,
+    keywords: Vec<Keyword>,
+    aliases: Option<Vec<String>>,
+    parent_topic_id: Option<String>,
+    exclusions: Option<Vec<String>>,
+) -> Result<Topic, TrendrError> {
+    let name = name.trim().to_string();
+    let slug = slugify(&name);
+    if slug.is_empty() {
+        return Err(TrendrError::Other("Topic name must contain at least one letter or number".to_string()));
+    }
+
+    let allow_single_char = settings::load_settings().extraction.allow_single_char_keywords;
+    let keywords = topics::normalize_keywords(&keywords, allow_single_char)?;
+    let aliases = clean_aliases(&aliases.unwrap_or_default());
+    let keywords_json = serde_json::to_string(&keywords)?;
+    let aliases_json = serde_json::to_string(&aliases)?;
+    let exclusions_json = serde_json::to_string(&clean_aliases(&exclusions.unwrap_or_default()))?;
+    let id = uuid::Uuid::new_v4().to_string();
+
+    let error = with_db(|conn| {
+        if let Some(conflict) = topic_name_conflict(conn, &name, &slug, None)? {
+            return Ok(Some(conflict));
+        }
+        if let Some(parent_id) = &parent_topic_id {
+            let parent_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM topics WHERE id = ?1)",
+                params![parent_id],
+                |row| row.get(0),
+            )?;
+            if !parent_exists {
+                return Ok(Some(format!("Parent topic {} not found", parent_id)));
+            }
+        }
+
+        conn.execute(
+            r#"INSERT INTO topics (id, name, slug, parent_topic_id, aliases, keywords, exclusions)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+            params![&id, &name, &slug, &parent_topic_id, &aliases_json, &keywords_json, &exclusions_json],
+        )?;
+        Ok(None)
+    })?;
+
+    if let Some(error) = error {
+        return Err(TrendrError::Other(error));
+    }
+    log::info!("Created topic {} ({})", name, id);
+    get_topic_details(id)
+}
+
+/// Rename a topic or replace its keyword, alias or exclusion lists; omitted fields are left as they
+/// are. Renaming regenerates the slug. Use `set_topic_parent` to move it in the hierarchy.
+#[tauri::command]
+pub fn update_topic(
+    topic_id: String,
+    name: Option<String>,
+    keywords: Option<Vec<Keyword>>,
+    aliases: Option<Vec<String>>,
+    exclusions: Option<Vec<String>>,
+) -> Result<Topic, TrendrError> {
+    let renamed = match name {
+        Some(name) => {
+            let name = name.trim().to_string();
+            let slug = slugify(&name);
+            if slug.is_empty() {
+                return Err(TrendrError::Other("Topic name must contain at least one letter or number".to_string()));
+            }
+            Some((name, slug))
+        }
+        None => None,
+    };
+
+    let keywords_json = match keywords {
+        Some(keywords) => {
+            let allow_single_char = settings::load_settings().extraction.allow_single_char_keywords;
+            let keywords = topics::normalize_keywords(&keywords, allow_single_char)?;
+            Some(serde_json::to_string(&keywords)?)
+        }
+        None => None,
+    };
+    let aliases_json = match aliases {
+        Some(aliases) => Some(serde_json::to_string(&clean_aliases(&aliases))?),
+        None => None,
+    };
+    let exclusions_json = match exclusions {
+        Some(exclusions) => Some(serde_json::to_string(&clean_aliases(&exclusions))?),
+        None => None,
+    };
+
+    let error = with_db_tx(|tx| {
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM topics WHERE id = ?1)",
+            params![&topic_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Ok(Some(format!("Topic {} not found", topic_id)));
+        }
+
+        if let Some((name, slug)) = &renamed {
+            if let Some(conflict) = topic_name_conflict(tx, name, slug, Some(&topic_id))? {
+                return Ok(Some(conflict));
+            }
+            tx.execute(
+                "UPDATE topics SET name = ?1, slug = ?2 WHERE id = ?3",
+                params![name, slug, &topic_id],
+            )?;
+        }
+        if let Some(keywords_json) = &keywords_json {
+            tx.execute("UPDATE topics SET keywords = ?1 WHERE id = ?2", params![keywords_json, &topic_id])?;
+        }
+        if let Some(aliases_json) = &aliases_json {
+            tx.execute("UPDATE topics SET aliases = ?1 WHERE id = ?2", params![aliases_json, &topic_id])?;
+        }
+        if let Some(exclusions_json) = &exclusions_json {
+            tx.execute("UPDATE topics SET exclusions = ?1 WHERE id = ?2", params![exclusions_json, &topic_id])?;
+        }
+        Ok(None)
+    })?;
+
+    if let Some(error) = error {
+        return Err(TrendrError::Other(error));
+    }
+    get_topic_details(topic_id)
+}
+
+/// Delete a topic. Its content links and cached trend score go with it (ON DELETE
+/// CASCADE); its subtopics move up to its own parent, and its alerts are kept without a
+/// topic. The content itself is not touched.
+#[tauri::command]
+pub fn delete_topic(topic_id: String) -> Result<(), TrendrError> {
+    let deleted = with_db_tx(|tx| {
+        tx.execute(
+            r#"UPDATE topics SET parent_topic_id = (SELECT parent_topic_id FROM topics WHERE id = ?1)
+               WHERE parent_topic_id = ?1"#,
+            params![&topic_id],
+        )?;
+        tx.execute(
+            "DELETE FROM topic_cooccurrences WHERE topic_a_id = ?1 OR topic_b_id = ?1",
+            params![&topic_id],
+        )?;
+        tx.execute("DELETE FROM topic_motivations WHERE topic_id = ?1", params![&topic_id])?;
+        tx.execute("UPDATE alerts SET topic_id = NULL WHERE topic_id = ?1", params![&topic_id])?;
+        tx.execute("DELETE FROM topics WHERE id = ?1", params![&topic_id])
+    })?;
+
+    if deleted == 0 {
+        return Err(TrendrError::Other(format!("Topic {} not found", topic_id)));
+    }
+    log::info!("Deleted topic {}", topic_id);
+    Ok(())
+}
+
+/// Rebuild every content item's topic links with the current topics and keywords, so
+/// newly added topics and keywords apply to content collected before them. Emits
+/// `reextract://progress` events as batches complete.
+#[tauri::command]
+pub async fn reextract_topics(app: tauri::AppHandle) -> Result<ReextractResult, TrendrError> {
+    let settings = settings::load_settings();
+    tauri::async_runtime::spawn_blocking(move || {
+        topics::reextract_all(&settings, |processed, total| {
+            let _ = app.emit(REEXTRACT_PROGRESS_EVENT, ReextractProgress { processed, total });
+        })
+    })
+    .await
+    .map_err(|e| TrendrError::Other(e.to_string()))?
+}
+
+/// Run the extraction pipeline on arbitrary text and report every step, without storing anything.
+#[tauri::command]
+pub fn analyze_text(text: String) -> Result<TextAnalysis, TrendrError> {
+    topics::analyze_text(&text)
+}
+
+// Content commands
+
+/// Most recently collected content, optionally narrowed to one platform, a range of
+/// publish dates (`from`/`to` as ISO dates, both inclusive; collection date when the
+/// publish date is unknown) and a minimum likes + comments + shares.
+#[tauri::command]
+pub fn get_content(
+    limit: Option<i64>,
+    offset: Option<i64>,
+    platform: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    min_engagement: Option<i64>,
+) -> Result<Vec<Content>, TrendrError> {
+    let limit = limit.unwrap_or(50);
+    let offset = offset.unwrap_or(0);
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            r#"SELECT {columns} FROM content c
+               WHERE (?3 IS NULL OR c.platform = ?3)
+               AND (?4 IS NULL OR date({time}) >= date(?4))
+               AND (?5 IS NULL OR date({time}) <= date(?5))
+               AND (?6 IS NULL OR c.engagement_likes + c.engagement_comments + c.engagement_shares >= ?6)
+               ORDER BY c.collected_at DESC
+               LIMIT ?1 OFFSET ?2"#,
+            columns = CONTENT_COLUMNS,
+            time = CONTENT_TIME_SQL
+        ))?;
+
+        let rows = stmt.query_map(
+            params![limit, offset, &platform, &from, &to, min_engagement],
+            content_from_row,
+        )?;
+
+        let mut content = Vec::new();
+        for row in rows {
+            content.push(row?);
+        }
+        Ok(content)
+    })
+}
+
+/// Write content to `path` as CSV (`format` "csv") or newline-delimited JSON ("json"),
+/// optionally limited to one platform and an inclusive `from`/`to` range of ISO dates.
+/// Returns the number of rows written.
+#[tauri::command]
+pub fn export_content(
+    format: String,
+    path: String,
+    platform: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<usize, TrendrError> {
+    let format = ExportFormat::parse(&format)
+        .ok_or_else(|| TrendrError::Other(format!("Unknown export format: {} (expected \"csv\" or \"json\")", format)))?;
+    let filter = ContentExportFilter { platform, from, to };
+    export::export_content(format, std::path::Path::new(&path), &filter)
+}
+
+/// Snapshot the whole SQLite database to `dest_path`, safe to run mid-collection.
+/// Returns the size of the copy in bytes.
+#[tauri::command]
+pub fn backup_database(dest_path: String) -> Result<u64, TrendrError> {
+    database::backup_database(std::path::Path::new(&dest_path))
+}
+
+/// Write all topics, creators, content and topic links to `path` as a JSON backup.
+/// Returns the number of rows written.
+#[tauri::command]
+pub fn export_backup(path: String) -> Result<usize, TrendrError> {
+    backup::export_backup(std::path::Path::new(&path))
+}
+
+/// Restore a backup written by `export_backup` alongside the existing data. Rows that
+/// already exist are left untouched and counted as skipped.
+#[tauri::command]
+pub fn import_backup(path: String) -> Result<ImportResult, TrendrError> {
+    backup::import_backup(std::path::Path::new(&path))
+}
+
+/// Content whose text matches every word of `query`, best matches first (bm25). Words are
+/// matched literally, so quotes and FTS operators in the query are treated as text.
+#[tauri::command]
+pub fn search_content(query: String, limit: Option<i64>) -> Result<Vec<Content>, TrendrError> {
+    let limit = limit.unwrap_or(50);
+    let match_expr = fts_match_expression(&query);
+    if match_expr.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    with_db(|conn| {
+        if !has_fulltext_index(conn)? {
+            return Ok(None);
+        }
+
+        let mut stmt = conn.prepare(&format!(
+            r#"SELECT {} FROM content_fts f
+               JOIN content c ON c.rowid = f.rowid
+               WHERE content_fts MATCH ?1
+               ORDER BY bm25(content_fts)
+               LIMIT ?2"#,
+            CONTENT_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(params![&match_expr, limit], content_from_row)?;
+
+        let mut content = Vec::new();
+        for row in rows {
+            content.push(row?);
+        }
+        Ok(Some(content))
+    })?
+    .ok_or_else(|| TrendrError::Other("Content search is unavailable: this SQLite build lacks FTS5".to_string()))
+}
+
+/// Quote each whitespace-separated word of a user query as an FTS5 string, so the
+/// words are ANDed together and none of them is parsed as query syntax
+fn fts_match_expression(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[tauri::command]
+pub fn get_content_by_topic(topic_id: String, limit: Option<i64>) -> Result<Vec<Content>, TrendrError> {
+    let limit = limit.unwrap_or(20);
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            r#"SELECT {} FROM content c
                JOIN content_topics ct ON c.id = ct.content_id
                WHERE ct.topic_id = ?1
                ORDER BY c.collected_at DESC
                LIMIT ?2"#,
-        )?;
+            CONTENT_COLUMNS
+        ))?;
 
-        let rows = stmt.query_map(params![&topic_id, limit], |row| {
-            Ok(Content {
-                id: row.get(0)?,
-                platform: row.get(1)?,
-                platform_id: row.get(2)?,
-                creator_id: row.get(3)?,
-                content_type: row.get(4)?,
-                text_content: row.get(5)?,
-                engagement_likes: row.get(6)?,
-                engagement_comments: row.get(7)?,
-                published_at: row.get(8)?,
-                collected_at: row.get(9)?,
+        let rows = stmt.query_map(params![&topic_id, limit], content_from_row)?;
+
+        let mut content = Vec::new();
+        for row in rows {
+            content.push(row?);
+        }
+        Ok(content)
+    })
+}
+
+/// A topic's most engaging content. `metric` "raw" (the default) ranks by likes +
+/// comments + shares; "rate" ranks by that total over the creator's follower count, so
+/// small accounts with an unusually engaged audience surface. Follower counts are only
+/// known for X and YouTube creators; under "rate", items without one follow the rest,
+/// ranked by raw engagement and flagged with `rankedByRaw`.
+#[tauri::command]
+pub fn get_top_content(
+    topic_id: String,
+    metric: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<TopContent>, TrendrError> {
+    let limit = limit.unwrap_or(20);
+    let by_rate = match metric.as_deref().unwrap_or("raw") {
+        "raw" => false,
+        "rate" => true,
+        other => return Err(TrendrError::Other(format!("Unknown metric: {} (expected \"raw\" or \"rate\")", other))),
+    };
+    let order_by = if by_rate {
+        "engagement_rate IS NULL, engagement_rate DESC, engagement DESC"
+    } else {
+        "engagement DESC, engagement_rate DESC"
+    };
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            r#"SELECT {columns},
+               c.engagement_likes + c.engagement_comments + COALESCE(c.engagement_shares, 0) AS engagement,
+               cr.follower_count,
+               CASE WHEN cr.follower_count > 0
+                   THEN CAST(c.engagement_likes + c.engagement_comments + COALESCE(c.engagement_shares, 0) AS REAL)
+                        / cr.follower_count
+               END AS engagement_rate
+               FROM content c
+               JOIN content_topics ct ON c.id = ct.content_id
+               LEFT JOIN creators cr ON cr.id = c.creator_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2
+               ORDER BY {order_by}
+               LIMIT ?3"#,
+            columns = CONTENT_COLUMNS,
+            order_by = order_by
+        ))?;
+
+        let rows = stmt.query_map(params![&topic_id, status::DELETED, limit], |row| {
+            let engagement_rate: Option<f64> = row.get(21)?;
+            Ok(TopContent {
+                content: content_from_row(row)?,
+                engagement: row.get(19)?,
+                follower_count: row.get(20)?,
+                engagement_rate,
+                ranked_by_raw: by_rate && engagement_rate.is_none(),
             })
         })?;
 
@@ -422,12 +2180,234 @@ pub fn get_content_by_topic(topic_id: String, limit: Option<i64>) -> Result<Vec<
     })
 }
 
+/// Delete content older than `older_than_days` and/or beyond the newest `max_rows`,
+/// optionally keeping anything still linked to a topic.
+#[tauri::command]
+pub fn prune_content(
+    older_than_days: Option<i64>,
+    keep_linked: Option<bool>,
+    max_rows: Option<i64>,
+) -> Result<PruneResult, TrendrError> {
+    retention::prune_content(older_than_days, max_rows, keep_linked.unwrap_or(false))
+}
+
+/// Bounds of the collected data, optionally for one platform, for seeding date pickers.
+/// Empty or unparseable publish dates are ignored; all bounds are None when there's no content.
+#[tauri::command]
+pub fn get_content_date_range(platform: Option<String>) -> Result<ContentDateRange, TrendrError> {
+    with_db(|conn| {
+        conn.query_row(
+            r#"SELECT MIN(datetime(published_at)), MAX(datetime(published_at)),
+               MIN(datetime(collected_at)), MAX(datetime(collected_at))
+               FROM content
+               WHERE ?1 IS NULL OR platform = ?1"#,
+            params![&platform],
+            |row| {
+                Ok(ContentDateRange {
+                    earliest_published_at: row.get(0)?,
+                    latest_published_at: row.get(1)?,
+                    earliest_collected_at: row.get(2)?,
+                    latest_collected_at: row.get(3)?,
+                })
+            },
+        )
+    })
+}
+
+/// Distinct content types in the corpus with their counts, for the UI type filter.
+#[tauri::command]
+pub fn get_content_types() -> Result<Vec<ContentTypeCount>, TrendrError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT content_type, COUNT(*) as count
+               FROM content
+               GROUP BY content_type
+               ORDER BY count DESC"#,
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let content_type: String = row.get(0)?;
+            if ContentType::parse(&content_type).is_none() {
+                log::warn!("Non-canonical content_type in database: {}", content_type);
+            }
+
+            Ok(ContentTypeCount {
+                content_type,
+                count: row.get(1)?,
+            })
+        })?;
+
+        let mut types = Vec::new();
+        for row in rows {
+            types.push(row?);
+        }
+        Ok(types)
+    })
+}
+
+struct UntaggedItem {
+    id: String,
+    platform: String,
+    content_type: String,
+    source: Option<String>,
+    text: String,
+}
+
+/// Run extraction over content that currently has no topic links (e.g. after adding
+/// keywords) without touching items that are already tagged.
+#[tauri::command]
+pub fn tag_untagged_content(limit: Option<i64>) -> Result<TagResult, TrendrError> {
+    let limit = limit.unwrap_or(500);
+
+    let untagged: Vec<UntaggedItem> = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT c.id, c.platform, c.content_type, c.source, c.text_content
+               FROM content c
+               WHERE c.text_content IS NOT NULL AND c.status != ?2
+               AND NOT EXISTS (SELECT 1 FROM content_topics ct WHERE ct.content_id = c.id)
+               ORDER BY c.collected_at DESC
+               LIMIT ?1"#,
+        )?;
+
+        let rows = stmt.query_map(params![limit, status::DELETED], |row| {
+            Ok(UntaggedItem {
+                id: row.get(0)?,
+                platform: row.get(1)?,
+                content_type: row.get(2)?,
+                source: row.get(3)?,
+                text: row.get(4)?,
+            })
+        })?;
+
+        let mut untagged = Vec::new();
+        for row in rows {
+            untagged.push(row?);
+        }
+        Ok(untagged)
+    })?;
+
+    // Extraction reads topics through with_db, so it has to run before the write lock is taken
+    let known_topics = topics::load_topics()?;
+    let settings = settings::load_settings();
+    let mut matches = Vec::new();
+    for item in &untagged {
+        let found = topics::extract_stored_topics(
+            &known_topics,
+            &settings,
+            &item.platform,
+            &item.content_type,
+            item.source.as_deref(),
+            &item.text,
+        )?;
+        if !found.is_empty() {
+            matches.push((&item.id, found));
+        }
+    }
+
+    let links_created: u32 = matches.iter().map(|(_, found)| found.len() as u32).sum();
+
+    with_db_tx(|tx| {
+        for (content_id, found) in &matches {
+            topics::link_topics(tx, content_id, found)?;
+        }
+        Ok(())
+    })?;
+
+    Ok(TagResult {
+        scanned: untagged.len() as u32,
+        tagged: matches.len() as u32,
+        links_created,
+    })
+}
+
+/// Detect named entities in content that hasn't been scanned for them yet.
+#[tauri::command]
+pub fn tag_content_entities(limit: Option<i64>) -> Result<EntityTagResult, TrendrError> {
+    entities::tag_content(limit.unwrap_or(500))
+}
+
+/// Entities mentioned by the most content in the last `days` days.
+#[tauri::command]
+pub fn get_trending_entities(days: Option<i64>, limit: Option<i64>) -> Result<Vec<TrendingEntity>, TrendrError> {
+    entities::get_trending_entities(days.unwrap_or(7), limit.unwrap_or(20))
+}
+
+/// Hashtags and cashtags used by the most content in the last `days` days.
+#[tauri::command]
+pub fn get_trending_tags(days: Option<i64>, limit: Option<i64>) -> Result<Vec<TrendingTag>, TrendrError> {
+    hashtags::get_trending_tags(days.unwrap_or(7), limit.unwrap_or(20))
+}
+
+// Creator commands
+/// Fold a duplicate creator into another, reassigning all of its content.
+#[tauri::command]
+pub fn merge_creators(source_id: String, target_id: String) -> Result<Creator, TrendrError> {
+    creators::merge_creators(&source_id, &target_id)
+}
+
+/// Creators with how much content each has, optionally for one platform. Sorted by
+/// content count by default; `sort_by` may also be "followers" or "name".
+#[tauri::command]
+pub fn get_creators(
+    platform: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    sort_by: Option<String>,
+) -> Result<Vec<CreatorSummary>, TrendrError> {
+    creators::list_creators(
+        platform.as_deref(),
+        sort_by.as_deref(),
+        limit.unwrap_or(50),
+        offset.unwrap_or(0),
+    )
+}
+
+/// A creator with their content count and most recent content.
+#[tauri::command]
+pub fn get_creator_details(id: String, content_limit: Option<i64>) -> Result<CreatorDetails, TrendrError> {
+    let creator = creators::get_creator_summary(&id)?.ok_or_else(|| TrendrError::Other(format!("Creator not found: {}", id)))?;
+
+    let recent_content = with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            r#"SELECT {} FROM content c
+               WHERE c.creator_id = ?1
+               ORDER BY COALESCE(datetime(c.published_at), datetime(c.collected_at)) DESC
+               LIMIT ?2"#,
+            CONTENT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![&id, content_limit.unwrap_or(20)], content_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>()
+    })?;
+
+    Ok(CreatorDetails { creator, recent_content })
+}
+
+/// All platform presences of a creator, grouped by normalized username.
+#[tauri::command]
+pub fn get_creator_group(username: String) -> Result<CreatorGroup, TrendrError> {
+    if !settings::load_settings().creator_grouping {
+        return Err(TrendrError::Other("Creator grouping is disabled in settings".to_string()));
+    }
+    creators::get_creator_group(&username)
+}
+
 // Dashboard commands
+
+/// All-time totals plus activity over the last `days` (default 7): content collected in
+/// that window and the topics with the most of it. `contentLast7Days` is always 7 days.
 #[tauri::command]
-pub fn get_dashboard_stats() -> Result<DashboardStats, String> {
+pub fn get_dashboard_stats(include_deleted: Option<bool>, days: Option<i64>) -> Result<DashboardStats, TrendrError> {
+    // Deleted content stays in the database for trend history but is hidden from counts by default
+    let excluded_status = if include_deleted.unwrap_or(false) { None } else { Some(status::DELETED) };
+    let window_days = days.unwrap_or(7).max(1);
+    let window = days_ago_modifier(window_days);
+
     with_db(|conn| {
-        let total_content: i64 =
-            conn.query_row("SELECT COUNT(*) FROM content", [], |row| row.get(0))?;
+        let total_content: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM content WHERE status IS NOT ?1 AND duplicate_of IS NULL",
+            params![excluded_status],
+            |row| row.get(0),
+        )?;
 
         let total_topics: i64 =
             conn.query_row("SELECT COUNT(*) FROM topics", [], |row| row.get(0))?;
@@ -436,8 +2416,16 @@ pub fn get_dashboard_stats() -> Result<DashboardStats, String> {
             conn.query_row("SELECT COUNT(*) FROM creators", [], |row| row.get(0))?;
 
         let content_last_7_days: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM content WHERE collected_at > datetime('now', '-7 days')",
-            [],
+            r#"SELECT COUNT(*) FROM content
+               WHERE collected_at > datetime('now', '-7 days') AND status IS NOT ?1 AND duplicate_of IS NULL"#,
+            params![excluded_status],
+            |row| row.get(0),
+        )?;
+
+        let content_in_window: i64 = conn.query_row(
+            r#"SELECT COUNT(*) FROM content
+               WHERE collected_at > datetime('now', ?2) AND status IS NOT ?1 AND duplicate_of IS NULL"#,
+            params![excluded_status, &window],
             |row| row.get(0),
         )?;
 
@@ -445,12 +2433,14 @@ pub fn get_dashboard_stats() -> Result<DashboardStats, String> {
             r#"SELECT t.name, COUNT(ct.content_id) as count
                FROM topics t
                JOIN content_topics ct ON t.id = ct.topic_id
+               JOIN content c ON c.id = ct.content_id
+               WHERE c.collected_at > datetime('now', ?2) AND c.status IS NOT ?1 AND c.duplicate_of IS NULL
                GROUP BY t.id
                ORDER BY count DESC
                LIMIT 10"#,
         )?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params![excluded_status, &window], |row| {
             Ok(TopicCount {
                 name: row.get(0)?,
                 count: row.get(1)?,
@@ -467,14 +2457,47 @@ pub fn get_dashboard_stats() -> Result<DashboardStats, String> {
             total_topics,
             total_creators,
             content_last_7_days,
+            window_days,
+            content_in_window,
             top_topics,
         })
     })
 }
 
+/// Topics whose average sentiment over the last `days` moved away from their trailing average.
+#[tauri::command]
+pub fn get_sentiment_shifts(days: Option<i64>, threshold: Option<f64>) -> Result<Vec<SentimentShift>, TrendrError> {
+    sentiment::detect_shifts(
+        days.unwrap_or(SENTIMENT_SHIFT_DAYS),
+        threshold.unwrap_or(SENTIMENT_SHIFT_THRESHOLD),
+    )
+}
+
+/// Freeze current per-topic stats under `label` for later comparison.
+#[tauri::command]
+pub fn pin_snapshot(label: String) -> Result<SnapshotSummary, TrendrError> {
+    snapshots::pin_snapshot(&label)
+}
+
+#[tauri::command]
+pub fn list_snapshots() -> Result<Vec<SnapshotSummary>, TrendrError> {
+    snapshots::list_snapshots()
+}
+
+/// Per-topic changes since the snapshot pinned as `label`.
+#[tauri::command]
+pub fn compare_snapshot(label: String) -> Result<SnapshotComparison, TrendrError> {
+    snapshots::compare_snapshot(&label)
+}
+
+#[tauri::command]
+pub fn delete_snapshot(label: String) -> Result<bool, TrendrError> {
+    snapshots::delete_snapshot(&label)
+}
+
 // Alerts commands
 #[tauri::command]
-pub fn get_alerts(limit: Option<i64>) -> Result<Vec<Alert>, String> {
+pub fn get_alerts(limit: Option<i64>) -> Result<Vec<Alert>, TrendrError> {
     let limit = limit.unwrap_or(50);
 
     with_db(|conn| {
@@ -505,8 +2528,26 @@ pub fn get_alerts(limit: Option<i64>) -> Result<Vec<Alert>, String> {
     })
 }
 
+/// Delete read alerts older than the configured retention now, rather than waiting for
+/// the next collection run. Returns how many were removed.
 #[tauri::command]
-pub fn mark_alert_read(alert_id: String) -> Result<(), String> {
+pub fn cleanup_alerts() -> Result<usize, TrendrError> {
+    alerts::cleanup_read_alerts(settings::load_settings().alert_retention_days)
+}
+
+/// Write recent alerts to an RSS 2.0 file at `path`, or at the configured feed path when
+/// none is given. Returns how many alerts were written.
+#[tauri::command]
+pub fn export_alerts_rss(path: Option<String>, limit: Option<i64>) -> Result<usize, TrendrError> {
+    let path = path
+        .or_else(|| settings::load_settings().alerts_rss_path)
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| TrendrError::NotConfigured("No RSS feed path given or configured".to_string()))?;
+    alerts::export_rss(std::path::Path::new(&path), limit.unwrap_or(100))
+}
+
+#[tauri::command]
+pub fn mark_alert_read(alert_id: String) -> Result<(), TrendrError> {
     with_db(|conn| {
         conn.execute(
             "UPDATE alerts SET read = 1 WHERE id = ?1",
@@ -515,3 +2556,478 @@ pub fn mark_alert_read(alert_id: String) -> Result<(), String> {
         Ok(())
     })
 }
+
+/// Mark every unread alert as read. Returns how many were updated.
+#[tauri::command]
+pub fn mark_all_alerts_read() -> Result<usize, TrendrError> {
+    with_db(|conn| conn.execute("UPDATE alerts SET read = 1 WHERE read = 0", []))
+}
+
+/// Number of unread alerts, for the notification badge.
+#[tauri::command]
+pub fn get_unread_alert_count() -> Result<i64, TrendrError> {
+    with_db(|conn| conn.query_row("SELECT COUNT(*) FROM alerts WHERE read = 0", [], |row| row.get(0)))
+}
+
+/// Delete one alert. Returns false if it didn't exist.
+#[tauri::command]
+pub fn delete_alert(alert_id: String) -> Result<bool, TrendrError> {
+    with_db(|conn| {
+        let deleted = conn.execute("DELETE FROM alerts WHERE id = ?1", params![&alert_id])?;
+        Ok(deleted > 0)
+    })
+}
+
+/// Delete every read alert regardless of age. Unread alerts and notes are kept.
+/// Returns how many were removed.
+#[tauri::command]
+pub fn clear_read_alerts() -> Result<usize, TrendrError> {
+    with_db(|conn| {
+        conn.execute(
+            "DELETE FROM alerts WHERE read = 1 AND alert_type != ?1",
+            params![alerts::NOTE_ALERT_TYPE],
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform_run(posts_collected: u32, topics_extracted: u32, cancelled: bool) -> PlatformRun {
+        Some(Ok(CollectionResult {
+            posts_collected,
+            topics_extracted,
+            outcomes: Vec::new(),
+            rate_limited: false,
+            skipped_sources: Vec::new(),
+            cancelled,
+        }))
+    }
+
+    #[test]
+    fn fts_expression_quotes_each_word() {
+        assert_eq!(fts_match_expression("rust  async"), r#""rust" "async""#);
+        assert_eq!(fts_match_expression(r#"say "hi""#), r#""say" """hi""""#);
+        assert_eq!(fts_match_expression("   "), "");
+    }
+
+    #[test]
+    fn fts_expression_keeps_query_syntax_literal() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"CREATE VIRTUAL TABLE docs USING fts5(body);
+               INSERT INTO docs (body) VALUES ('rust NOT go'), ('rust and go'), ('c++ tips');"#,
+        )
+        .unwrap();
+        let count = |query: &str| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM docs WHERE docs MATCH ?1",
+                params![fts_match_expression(query)],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+
+        // NOT is a word to look for, not an operator
+        assert_eq!(count("rust NOT go"), 1);
+        assert_eq!(count("rust go"), 2);
+        // Would be a syntax error unquoted
+        assert_eq!(count("c++"), 1);
+    }
+
+    #[test]
+    fn top_content_ranks_by_raw_engagement_or_by_rate() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug, keywords) VALUES ('rust', 'Rust', 'rust', '[]');
+                   INSERT INTO creators (id, platform, platform_id, username, follower_count) VALUES
+                       ('big', 'x', 'u1', 'big', 1000000),
+                       ('small', 'x', 'u2', 'small', 100),
+                       ('redditor', 'reddit', 'u3', 'redditor', NULL);
+                   INSERT INTO content (id, platform, platform_id, creator_id, content_type,
+                                        engagement_likes, engagement_comments, engagement_shares) VALUES
+                       ('viral', 'x', 't1', 'big', 'post', 4000, 500, 500),
+                       ('niche', 'x', 't2', 'small', 'post', 40, 5, 5),
+                       ('thread', 'reddit', 'r1', 'redditor', 'post', 700, 100, 0);
+                   INSERT INTO content (id, platform, platform_id, creator_id, content_type, engagement_likes, status)
+                   VALUES ('gone', 'x', 't3', 'small', 'post', 99999, 'deleted');
+                   INSERT INTO content_topics (content_id, topic_id, confidence)
+                   VALUES ('viral', 'rust', 1.0), ('niche', 'rust', 1.0), ('thread', 'rust', 1.0), ('gone', 'rust', 1.0);"#,
+            )
+        })
+        .unwrap();
+        let ranked = |metric: Option<&str>| -> Vec<(String, i64, bool)> {
+            get_top_content("rust".to_string(), metric.map(str::to_string), None)
+                .unwrap()
+                .into_iter()
+                .map(|top| (top.content.id, top.engagement, top.ranked_by_raw))
+                .collect()
+        };
+
+        let by_raw = vec![
+            ("viral".to_string(), 5000, false),
+            ("thread".to_string(), 800, false),
+            ("niche".to_string(), 50, false),
+        ];
+        assert_eq!(ranked(None), by_raw);
+        assert_eq!(ranked(Some("raw")), by_raw);
+
+        // 50 / 100 followers beats 5000 / 1M; Reddit has no follower counts so comes last
+        assert_eq!(
+            ranked(Some("rate")),
+            vec![
+                ("niche".to_string(), 50, false),
+                ("viral".to_string(), 5000, false),
+                ("thread".to_string(), 800, true),
+            ]
+        );
+
+        assert!(get_top_content("rust".to_string(), Some("views".to_string()), None).is_err());
+    }
+
+    #[test]
+    fn multi_platform_run_totals_platforms_and_post_processes_once() {
+        let _db = crate::database::test_database();
+        let settings = AppSettings::default();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let before = AFTER_COLLECTION_CALLS.load(Ordering::Relaxed);
+        let result = runtime.block_on(join_platforms(
+            async { platform_run(3, 5, false) },
+            async { platform_run(4, 1, true) },
+            async { Some(Err(TrendrError::Auth("API key rejected".to_string()))) },
+            &settings,
+        ));
+        assert_eq!(AFTER_COLLECTION_CALLS.load(Ordering::Relaxed) - before, 1);
+
+        assert_eq!(result.posts_collected, 7);
+        assert_eq!(result.topics_extracted, 6);
+        assert!(result.cancelled);
+        assert_eq!(result.reddit.map(|r| r.posts_collected), Some(3));
+        assert_eq!(result.x.map(|r| r.posts_collected), Some(4));
+        assert!(result.youtube.is_none());
+        assert_eq!(result.errors, vec!["youtube: API key rejected".to_string()]);
+
+        let before = AFTER_COLLECTION_CALLS.load(Ordering::Relaxed);
+        let result = runtime.block_on(join_platforms(
+            async { None },
+            async { platform_run(2, 2, false) },
+            async { None },
+            &settings,
+        ));
+        assert_eq!(AFTER_COLLECTION_CALLS.load(Ordering::Relaxed) - before, 1);
+        assert_eq!((result.posts_collected, result.topics_extracted), (2, 2));
+        assert!(!result.cancelled && result.errors.is_empty());
+    }
+
+    #[test]
+    fn topic_lifecycle_reparents_children_and_keeps_alerts() {
+        let _db = crate::database::test_database();
+        let plain = |terms: &[&str]| -> Vec<Keyword> {
+            terms.iter().map(|t| Keyword::Plain(t.to_string())).collect()
+        };
+
+        let languages = create_topic("Languages".to_string(), plain(&["language"]), None, None, None).unwrap();
+        let rust = create_topic(
+            " Rust ".to_string(),
+            plain(&["Rust"]),
+            Some(vec!["ferris".to_string()]),
+            None,
+            None,
+        )
+        .unwrap();
+        let cargo = create_topic("Cargo".to_string(), plain(&["cargo"]), None, Some(rust.id.clone()), None).unwrap();
+        assert_eq!((rust.name.as_str(), rust.slug.as_str()), ("Rust", "rust"));
+        assert_eq!(cargo.parent_topic_id.as_deref(), Some(rust.id.as_str()));
+
+        let updated = update_topic(rust.id.clone(), None, Some(plain(&["rust", "Rustlang"])), None, None).unwrap();
+        assert_eq!(updated.keywords, plain(&["rust", "rustlang"]));
+        assert_eq!(updated.aliases, vec!["ferris".to_string()]);
+        set_topic_parent(rust.id.clone(), Some(languages.id.clone())).unwrap();
+        assert_eq!(get_topic_details(rust.id.clone()).unwrap().parent_topic_id, Some(languages.id.clone()));
+
+        with_db(|conn| {
+            conn.execute(
+                r#"INSERT INTO topic_cooccurrences (topic_a_id, topic_b_id, frequency)
+                   VALUES (?1, ?2, 3), (?2, ?3, 1), (?1, ?3, 2)"#,
+                params![&cargo.id, &rust.id, &languages.id],
+            )?;
+            conn.execute(
+                "INSERT INTO alerts (id, alert_type, topic_id, message) VALUES ('a1', 'trend_spike', ?1, 'Rust spiked')",
+                params![&rust.id],
+            )
+        })
+        .unwrap();
+
+        delete_topic(rust.id.clone()).unwrap();
+
+        assert!(get_topic_details(rust.id.clone()).is_err());
+        assert_eq!(get_topic_details(cargo.id.clone()).unwrap().parent_topic_id, Some(languages.id.clone()));
+        let (cooccurrences, alert_topic): (i64, Option<String>) = with_db(|conn| {
+            Ok((
+                conn.query_row("SELECT COUNT(*) FROM topic_cooccurrences", [], |row| row.get(0))?,
+                conn.query_row("SELECT topic_id FROM alerts WHERE id = 'a1'", [], |row| row.get(0))?,
+            ))
+        })
+        .unwrap();
+        assert_eq!(cooccurrences, 1);
+        assert_eq!(alert_topic, None);
+
+        assert!(delete_topic(rust.id).is_err());
+    }
+
+    #[test]
+    fn creators_sort_by_content_followers_or_name_within_a_platform() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO creators (id, platform, platform_id, username, follower_count) VALUES
+                       ('ann', 'x', 'u1', 'ann', 500),
+                       ('bob', 'x', 'u2', 'Bob', NULL),
+                       ('cat', 'reddit', 'u3', 'cat', 10000),
+                       ('dan', 'x', 'u4', 'dan', 100);
+                   INSERT INTO content (id, platform, platform_id, creator_id, content_type, published_at) VALUES
+                       ('a1', 'x', 't1', 'ann', 'post', '2024-03-01 10:00:00'),
+                       ('a2', 'x', 't2', 'ann', 'post', '2024-03-03 10:00:00'),
+                       ('b1', 'x', 't3', 'bob', 'post', '2024-03-01 10:00:00'),
+                       ('b2', 'x', 't4', 'bob', 'post', '2024-03-02 10:00:00'),
+                       ('c1', 'reddit', 'r1', 'cat', 'post', '2024-03-01 10:00:00');
+                   INSERT INTO content (id, platform, platform_id, creator_id, content_type, published_at, status)
+                   VALUES ('a3', 'x', 't5', 'ann', 'post', '2024-02-01 10:00:00', 'deleted');"#,
+            )
+        })
+        .unwrap();
+        let creators = |platform: Option<&str>, sort_by: Option<&str>, limit: Option<i64>, offset: Option<i64>| {
+            get_creators(platform.map(str::to_string), limit, offset, sort_by.map(str::to_string))
+                .unwrap()
+                .into_iter()
+                .map(|summary| (summary.creator.id, summary.content_count))
+                .collect::<Vec<_>>()
+        };
+        let ids = |ranked: Vec<(String, i64)>| ranked.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+
+        // Deleted content doesn't count; ties go to the creator with a known, larger following
+        assert_eq!(
+            creators(None, None, None, None),
+            vec![("ann".to_string(), 2), ("bob".to_string(), 2), ("cat".to_string(), 1), ("dan".to_string(), 0)]
+        );
+        assert_eq!(ids(creators(None, Some("followers"), None, None)), ["cat", "ann", "dan", "bob"]);
+        assert_eq!(ids(creators(None, Some("name"), None, None)), ["ann", "bob", "cat", "dan"]);
+        assert_eq!(ids(creators(Some("x"), Some("name"), None, None)), ["ann", "bob", "dan"]);
+        assert_eq!(ids(creators(Some("x"), None, Some(1), Some(1))), ["bob"]);
+        assert!(creators(Some("youtube"), None, None, None).is_empty());
+
+        let details = get_creator_details("ann".to_string(), Some(2)).unwrap();
+        assert_eq!(details.creator.content_count, 2);
+        let recent: Vec<&str> = details.recent_content.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(recent, ["a2", "a1"]);
+        assert!(get_creator_details("nobody".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn cooccurrences_filter_by_topic_and_frequency_and_carry_names() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug)
+                   VALUES ('go', 'Go', 'go'), ('rust', 'Rust', 'rust'), ('zig', 'Zig', 'zig');
+                   INSERT INTO topic_cooccurrences (topic_a_id, topic_b_id, frequency, last_seen) VALUES
+                       ('go', 'rust', 5, '2024-03-01 10:00:00'),
+                       ('rust', 'zig', 2, '2024-03-02 10:00:00'),
+                       ('go', 'zig', 2, '2024-03-03 10:00:00');"#,
+            )
+        })
+        .unwrap();
+        let pairs = |topic_id: Option<&str>, min_frequency: Option<i64>, limit: Option<i64>| {
+            get_cooccurrences(topic_id.map(str::to_string), min_frequency, limit)
+                .unwrap()
+                .into_iter()
+                .map(|pair| format!("{}+{} x{}", pair.topic_a_name, pair.topic_b_name, pair.frequency))
+                .collect::<Vec<_>>()
+        };
+
+        // Most frequent first, then most recently seen
+        assert_eq!(pairs(None, None, None), ["Go+Rust x5", "Go+Zig x2", "Rust+Zig x2"]);
+        assert_eq!(pairs(None, Some(3), None), ["Go+Rust x5"]);
+        assert_eq!(pairs(Some("zig"), None, None), ["Go+Zig x2", "Rust+Zig x2"]);
+        assert_eq!(pairs(Some("rust"), Some(3), None), ["Go+Rust x5"]);
+        assert_eq!(pairs(None, Some(0), Some(1)), ["Go+Rust x5"]);
+        assert!(pairs(Some("missing"), None, None).is_empty());
+
+        let first = get_cooccurrences(None, None, Some(1)).unwrap().remove(0);
+        assert_eq!((first.topic_a_id.as_str(), first.topic_b_id.as_str()), ("go", "rust"));
+        assert_eq!(first.last_seen.as_deref(), Some("2024-03-01 10:00:00"));
+    }
+
+    #[test]
+    fn timeseries_fills_empty_days_and_groups_weeks_from_monday() {
+        let _db = crate::database::test_database();
+        let today = chrono::Utc::now().date_naive();
+        let day = |days_ago: i64| today - chrono::Duration::days(days_ago);
+        with_db(|conn| {
+            conn.execute_batch(
+                "INSERT INTO topics (id, name, slug) VALUES ('rust', 'Rust', 'rust'), ('go', 'Go', 'go');",
+            )?;
+            let items = [
+                ("t1", 0, "rust", "active"),
+                ("t2", 0, "rust", "active"),
+                ("t3", 2, "rust", "active"),
+                ("t4", 10, "rust", "active"),
+                ("t5", 40, "rust", "active"),
+                ("t6", 0, "rust", "deleted"),
+                ("t7", 1, "go", "active"),
+            ];
+            for (id, days_ago, topic_id, item_status) in items {
+                conn.execute(
+                    r#"INSERT INTO content (id, platform, platform_id, content_type, published_at, status)
+                       VALUES (?1, 'x', ?1, 'post', ?2, ?3)"#,
+                    params![id, format!("{} 12:00:00", day(days_ago)), item_status],
+                )?;
+                conn.execute(
+                    "INSERT INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, 1.0)",
+                    params![id, topic_id],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        let series = |days: i64, bucket: &str| -> Vec<(String, i64)> {
+            get_topic_timeseries("rust".to_string(), days, bucket.to_string())
+                .unwrap()
+                .into_iter()
+                .map(|point| (point.bucket, point.count))
+                .collect()
+        };
+
+        // Oldest first, one point per day, including the day with nothing
+        assert_eq!(
+            series(3, "day"),
+            vec![(day(2).to_string(), 1), (day(1).to_string(), 0), (day(0).to_string(), 2)]
+        );
+        assert_eq!(series(1, "day"), vec![(day(0).to_string(), 2)]);
+
+        // Weeks start on the Monday on or before the first day, and the 40-day-old item is out
+        let monday = |date: chrono::NaiveDate| {
+            date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+        };
+        let mut expected = Vec::new();
+        let mut week = monday(day(13));
+        while week <= today {
+            let count = [0, 0, 2, 10].iter().filter(|&&days_ago| monday(day(days_ago)) == week).count();
+            expected.push((week.to_string(), count as i64));
+            week += chrono::Duration::days(7);
+        }
+        assert_eq!(series(14, "week"), expected);
+
+        assert!(get_topic_timeseries("rust".to_string(), 7, "month".to_string()).is_err());
+    }
+
+    #[test]
+    fn content_filters_by_platform_dates_and_engagement() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO content (id, platform, platform_id, content_type, published_at, collected_at,
+                                        engagement_likes, engagement_comments, engagement_shares) VALUES
+                       ('r1', 'reddit', 'p1', 'post', '2024-03-01 10:00:00', '2024-03-05 10:00:00', 10, 5, 0),
+                       ('x1', 'x', 't1', 'post', '2024-03-02 23:59:00', '2024-03-04 10:00:00', 90, 5, 5),
+                       ('x2', 'x', 't2', 'post', NULL, '2024-03-03 10:00:00', 1, 0, 0),
+                       ('y1', 'youtube', 'v1', 'video', '2024-02-28 10:00:00', '2024-03-02 10:00:00', 50, 0, 0);"#,
+            )
+        })
+        .unwrap();
+        let ids = |limit: Option<i64>,
+                   offset: Option<i64>,
+                   platform: Option<&str>,
+                   from: Option<&str>,
+                   to: Option<&str>,
+                   min_engagement: Option<i64>| {
+            get_content(
+                limit,
+                offset,
+                platform.map(str::to_string),
+                from.map(str::to_string),
+                to.map(str::to_string),
+                min_engagement,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect::<Vec<_>>()
+        };
+
+        // Newest collected first
+        assert_eq!(ids(None, None, None, None, None, None), ["r1", "x1", "x2", "y1"]);
+        assert_eq!(ids(Some(2), Some(1), None, None, None, None), ["x1", "x2"]);
+        assert_eq!(ids(None, None, Some("x"), None, None, None), ["x1", "x2"]);
+
+        // Both ends are inclusive; content with no publish date goes by when it was collected
+        assert_eq!(ids(None, None, None, Some("2024-03-01"), Some("2024-03-02"), None), ["r1", "x1"]);
+        assert_eq!(ids(None, None, None, Some("2024-03-03"), Some("2024-03-03"), None), ["x2"]);
+        assert_eq!(ids(None, None, None, None, Some("2024-02-28"), None), ["y1"]);
+
+        // Likes, comments and shares together
+        assert_eq!(ids(None, None, None, None, None, Some(50)), ["x1", "y1"]);
+        assert_eq!(ids(None, None, Some("x"), Some("2024-03-01"), None, Some(50)), ["x1"]);
+        assert!(ids(None, None, Some("reddit"), None, None, Some(16)).is_empty());
+    }
+
+    #[test]
+    fn dashboard_counts_activity_within_the_requested_window() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute(
+                "INSERT INTO topics (id, name, slug) VALUES ('rust', 'Rust', 'rust'), ('go', 'Go', 'go')",
+                [],
+            )?;
+            let items = [
+                ("c1", "-12 hours", "rust", "active", None),
+                ("c2", "-3 days", "go", "active", None),
+                ("c3", "-2 days", "go", "active", None),
+                ("c4", "-20 days", "rust", "active", None),
+                ("c5", "-20 days", "rust", "active", None),
+                ("c6", "-12 hours", "rust", "deleted", None),
+                ("c7", "-12 hours", "rust", "active", Some("c1")),
+            ];
+            for (id, age, topic_id, item_status, duplicate_of) in items {
+                conn.execute(
+                    r#"INSERT INTO content (id, platform, platform_id, content_type, collected_at, status, duplicate_of)
+                       VALUES (?1, 'x', ?1, 'post', datetime('now', ?2), ?3, ?4)"#,
+                    params![id, age, item_status, duplicate_of],
+                )?;
+                conn.execute(
+                    "INSERT INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, 1.0)",
+                    params![id, topic_id],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        let top = |stats: &DashboardStats| -> Vec<(String, i64)> {
+            stats.top_topics.iter().map(|t| (t.name.clone(), t.count)).collect()
+        };
+
+        // Deleted content and reposts are left out of every count
+        let week = get_dashboard_stats(None, None).unwrap();
+        assert_eq!((week.total_content, week.total_topics, week.total_creators), (5, 2, 0));
+        assert_eq!((week.window_days, week.content_in_window, week.content_last_7_days), (7, 3, 3));
+        assert_eq!(top(&week), vec![("Go".to_string(), 2), ("Rust".to_string(), 1)]);
+
+        let month = get_dashboard_stats(None, Some(30)).unwrap();
+        assert_eq!((month.window_days, month.content_in_window, month.content_last_7_days), (30, 5, 3));
+        assert_eq!(top(&month), vec![("Rust".to_string(), 3), ("Go".to_string(), 2)]);
+
+        // The window is at least a day
+        let day = get_dashboard_stats(None, Some(0)).unwrap();
+        assert_eq!((day.window_days, day.content_in_window), (1, 1));
+        assert_eq!(top(&day), vec![("Rust".to_string(), 1)]);
+
+        let with_deleted = get_dashboard_stats(Some(true), None).unwrap();
+        assert_eq!((with_deleted.total_content, with_deleted.content_in_window), (6, 4));
+        let mut tied = top(&with_deleted);
+        tied.sort();
+        assert_eq!(tied, vec![("Go".to_string(), 2), ("Rust".to_string(), 2)]);
+    }
+}
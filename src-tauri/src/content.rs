@@ -0,0 +1,182 @@
+use crate::settings::AppSettings;
+use crate::topics::ExtractedTopic;
+use serde::Serialize;
+
+/// Canonical values stored in `content.content_type`. Every source maps its items onto
+/// this set so type filters behave the same regardless of platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentType {
+    /// Long-form video (YouTube)
+    Video,
+    /// Top-level post (Reddit submission, X tweet)
+    Post,
+    /// Reply attached to another item (Reddit comment)
+    Comment,
+    /// Written article (RSS and blog sources)
+    Article,
+    /// Link aggregator story (Hacker News)
+    Story,
+}
+
+impl ContentType {
+    pub const ALL: [ContentType; 5] = [
+        ContentType::Video,
+        ContentType::Post,
+        ContentType::Comment,
+        ContentType::Article,
+        ContentType::Story,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentType::Video => "video",
+            ContentType::Post => "post",
+            ContentType::Comment => "comment",
+            ContentType::Article => "article",
+            ContentType::Story => "story",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<ContentType> {
+        ContentType::ALL.into_iter().find(|t| t.as_str() == value)
+    }
+}
+
+/// Values stored in `content.status`. Rows are never removed when content disappears
+/// upstream, so trend history stays intact; they are just marked.
+pub mod status {
+    pub const ACTIVE: &str = "active";
+    /// No longer returned by the platform API
+    pub const DELETED: &str = "deleted";
+    /// Taken down by platform moderators
+    #[allow(dead_code)]
+    pub const REMOVED: &str = "removed";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemStatus {
+    Stored,
+    Duplicate,
+    Filtered,
+    Error,
+}
+
+/// What a platform's item processing did with one fetched item.
+pub enum Processed {
+    /// Stored, with this many topics linked
+    Stored(u32),
+    /// Already in the database
+    Duplicate,
+    /// Matched no topics and `storeUntaggedContent` is off, so it was not stored
+    Untagged,
+}
+
+/// Outcome reason recorded for content dropped by `drop_untagged`
+pub const UNTAGGED_REASON: &str = "Matched no topics";
+
+/// Whether an item with these extracted topics should be dropped before it is stored.
+pub fn drop_untagged(topics: &[ExtractedTopic], settings: &AppSettings) -> bool {
+    !settings.store_untagged_content && !topics.iter().any(|topic| topic.confidence > 0.0)
+}
+
+/// Keep at most `cap` of the items fetched for one source, logging when any are cut.
+pub fn cap_items<T>(items: &mut Vec<T>, cap: u32, platform: &str, source: &str) {
+    let cap = cap.max(1) as usize;
+    if items.len() > cap {
+        log::info!(
+            "{} '{}': capped at {} items, skipping {} more",
+            platform,
+            source,
+            cap,
+            items.len() - cap
+        );
+        items.truncate(cap);
+    }
+}
+
+/// What happened to one fetched item during a collection run.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemOutcome {
+    pub platform: &'static str,
+    #[serde(rename = "platformId")]
+    pub platform_id: String,
+    pub status: ItemStatus,
+    #[serde(rename = "topicsFound")]
+    pub topics_found: u32,
+    pub reason: Option<String>,
+}
+
+impl ItemOutcome {
+    pub fn stored(platform: &'static str, platform_id: &str, topics_found: u32) -> Self {
+        Self::new(platform, platform_id, ItemStatus::Stored, topics_found, None)
+    }
+
+    pub fn duplicate(platform: &'static str, platform_id: &str) -> Self {
+        Self::new(platform, platform_id, ItemStatus::Duplicate, 0, None)
+    }
+
+    pub fn filtered(platform: &'static str, platform_id: &str, reason: String) -> Self {
+        Self::new(platform, platform_id, ItemStatus::Filtered, 0, Some(reason))
+    }
+
+    pub fn failed(platform: &'static str, platform_id: &str, reason: String) -> Self {
+        Self::new(platform, platform_id, ItemStatus::Error, 0, Some(reason))
+    }
+
+    fn new(
+        platform: &'static str,
+        platform_id: &str,
+        status: ItemStatus,
+        topics_found: u32,
+        reason: Option<String>,
+    ) -> Self {
+        ItemOutcome {
+            platform,
+            platform_id: platform_id.to_string(),
+            status,
+            topics_found,
+            reason,
+        }
+    }
+}
+
+/// How far a collection run has got, reported after each subreddit or query finishes.
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionProgress {
+    pub platform: &'static str,
+    /// Sources finished so far, including ones that failed
+    pub current: u32,
+    pub total: u32,
+    /// Items stored so far this run
+    #[serde(rename = "postsCollected")]
+    pub posts_collected: u32,
+}
+
+impl CollectionProgress {
+    pub fn new(platform: &'static str, current: usize, total: usize, posts_collected: u32) -> Self {
+        CollectionProgress {
+            platform,
+            current: current as u32,
+            total: total as u32,
+            posts_collected,
+        }
+    }
+}
+
+/// Cut `text` to at most `max_chars` characters, on a char boundary.
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((byte_index, _)) => &text[..byte_index],
+        None => text,
+    }
+}
+
+/// Shorten `body` so that "title\n\nbody" fits in `max_chars`, keeping the whole title.
+/// Returns the (possibly shortened) body and whether anything was cut.
+pub fn truncate_body<'a>(title: &str, body: &'a str, max_chars: usize) -> (&'a str, bool) {
+    let budget = max_chars.saturating_sub(title.chars().count() + 2);
+    let truncated = truncate_chars(body, budget);
+    (truncated, truncated.len() < body.len())
+}
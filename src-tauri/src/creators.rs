@@ -0,0 +1,237 @@
+use crate::content::status;
+use crate::database::with_db;
+use crate::error::TrendrError;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+/// How many of a creator's most frequent topics are kept in `primary_topics`
+const PRIMARY_TOPIC_COUNT: i64 = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Creator {
+    pub id: String,
+    pub platform: String,
+    #[serde(rename = "platformId")]
+    pub platform_id: String,
+    pub username: String,
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+    #[serde(rename = "followerCount")]
+    pub follower_count: Option<i64>,
+    /// Topic ids, most frequent first
+    #[serde(rename = "primaryTopics")]
+    pub primary_topics: Vec<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+/// The same person's accounts across platforms, matched by normalized handle. This is a
+/// derived view; the underlying creators are left untouched.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatorGroup {
+    pub handle: String,
+    pub platforms: Vec<String>,
+    #[serde(rename = "totalFollowers")]
+    pub total_followers: i64,
+    pub creators: Vec<Creator>,
+}
+
+/// A creator with how much of their content has been collected
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatorSummary {
+    #[serde(flatten)]
+    pub creator: Creator,
+    /// Collected content not marked deleted
+    #[serde(rename = "contentCount")]
+    pub content_count: i64,
+}
+
+const CREATOR_COLUMNS: &str =
+    "id, platform, platform_id, username, display_name, follower_count, primary_topics, created_at, updated_at";
+
+/// SQL form of `normalize_handle` applied to the username column
+const NORMALIZED_USERNAME_SQL: &str =
+    "REPLACE(REPLACE(REPLACE(REPLACE(LOWER(LTRIM(username, '@')), '_', ''), '-', ''), '.', ''), ' ', '')";
+
+fn creator_from_row(row: &rusqlite::Row) -> Result<Creator, rusqlite::Error> {
+    let primary_topics_json: Option<String> = row.get(6)?;
+    let primary_topics: Vec<String> = primary_topics_json
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    Ok(Creator {
+        id: row.get(0)?,
+        platform: row.get(1)?,
+        platform_id: row.get(2)?,
+        username: row.get(3)?,
+        display_name: row.get(4)?,
+        follower_count: row.get(5)?,
+        primary_topics,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// Creators with their content counts, optionally for one platform. Sorted by content
+/// count unless `sort_by` is "followers" or "name".
+pub fn list_creators(
+    platform: Option<&str>,
+    sort_by: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<CreatorSummary>, TrendrError> {
+    let order_by = match sort_by {
+        Some("followers") => "follower_count IS NULL, follower_count DESC, content_count DESC",
+        Some("name") => "username COLLATE NOCASE ASC",
+        _ => "content_count DESC, follower_count IS NULL, follower_count DESC",
+    };
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            r#"SELECT {},
+               (SELECT COUNT(*) FROM content c WHERE c.creator_id = creators.id AND c.status != ?1)
+               AS content_count
+               FROM creators
+               WHERE ?2 IS NULL OR platform = ?2
+               ORDER BY {}
+               LIMIT ?3 OFFSET ?4"#,
+            CREATOR_COLUMNS, order_by
+        ))?;
+        let rows = stmt.query_map(params![status::DELETED, platform, limit, offset], |row| {
+            Ok(CreatorSummary {
+                creator: creator_from_row(row)?,
+                content_count: row.get(9)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// One creator with their content count, if they exist.
+pub fn get_creator_summary(creator_id: &str) -> Result<Option<CreatorSummary>, TrendrError> {
+    with_db(|conn| {
+        let Some(creator) = get_creator(conn, creator_id)? else {
+            return Ok(None);
+        };
+        let content_count = conn.query_row(
+            "SELECT COUNT(*) FROM content WHERE creator_id = ?1 AND status != ?2",
+            params![creator_id, status::DELETED],
+            |row| row.get(0),
+        )?;
+        Ok(Some(CreatorSummary { creator, content_count }))
+    })
+}
+
+pub fn get_creator(conn: &Connection, creator_id: &str) -> Result<Option<Creator>, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT {} FROM creators WHERE id = ?1", CREATOR_COLUMNS),
+        params![creator_id],
+        creator_from_row,
+    )
+    .optional()
+}
+
+/// Lowercase a handle and drop "@"/"u/" prefixes and separator characters, so "@Jane_Doe",
+/// "u/jane-doe" and "Jane.Doe" all normalize to "janedoe".
+pub fn normalize_handle(username: &str) -> String {
+    let lowered = username.trim().to_lowercase();
+    let handle = lowered
+        .strip_prefix("u/")
+        .or_else(|| lowered.strip_prefix("/u/"))
+        .unwrap_or(&lowered)
+        .trim_start_matches('@');
+
+    handle
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | '.' | ' '))
+        .collect()
+}
+
+/// Every creator, on any platform, whose username normalizes to the same handle as `username`.
+pub fn get_creator_group(username: &str) -> Result<CreatorGroup, TrendrError> {
+    let handle = normalize_handle(username);
+    if handle.is_empty() {
+        return Err(TrendrError::Other("Username is empty".to_string()));
+    }
+
+    let creators = with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM creators WHERE {} = ?1 ORDER BY platform, follower_count DESC",
+            CREATOR_COLUMNS, NORMALIZED_USERNAME_SQL
+        ))?;
+        let rows = stmt.query_map(params![&handle], creator_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>()
+    })?;
+
+    let mut platforms: Vec<String> = creators.iter().map(|c| c.platform.clone()).collect();
+    platforms.dedup();
+
+    Ok(CreatorGroup {
+        handle,
+        platforms,
+        total_followers: creators.iter().filter_map(|c| c.follower_count).sum(),
+        creators,
+    })
+}
+
+/// Move all of `source_id`'s content onto `target_id` and delete the source creator.
+/// Display name and follower count are filled from the source only where the target
+/// has none. Returns the updated target.
+pub fn merge_creators(source_id: &str, target_id: &str) -> Result<Creator, TrendrError> {
+    if source_id == target_id {
+        return Err(TrendrError::Other("Cannot merge a creator into itself".to_string()));
+    }
+
+    let merged = with_db(|conn| {
+        let tx = conn.unchecked_transaction()?;
+
+        if get_creator(&tx, source_id)?.is_none() || get_creator(&tx, target_id)?.is_none() {
+            return Ok(None);
+        }
+
+        tx.execute(
+            "UPDATE content SET creator_id = ?1 WHERE creator_id = ?2",
+            params![target_id, source_id],
+        )?;
+        tx.execute(
+            r#"UPDATE creators SET
+               display_name = COALESCE(display_name, (SELECT display_name FROM creators WHERE id = ?2)),
+               follower_count = COALESCE(follower_count, (SELECT follower_count FROM creators WHERE id = ?2)),
+               updated_at = CURRENT_TIMESTAMP
+               WHERE id = ?1"#,
+            params![target_id, source_id],
+        )?;
+        tx.execute("DELETE FROM creators WHERE id = ?1", params![source_id])?;
+        update_primary_topics(&tx, target_id)?;
+
+        let merged = get_creator(&tx, target_id)?;
+        tx.commit()?;
+        Ok(merged)
+    })?;
+
+    merged.ok_or_else(|| TrendrError::Other("Creator not found".to_string()))
+}
+
+/// Recompute a creator's most frequent topics from their linked content.
+pub fn update_primary_topics(conn: &Connection, creator_id: &str) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        r#"SELECT ct.topic_id
+           FROM content c
+           JOIN content_topics ct ON ct.content_id = c.id
+           WHERE c.creator_id = ?1
+           GROUP BY ct.topic_id
+           ORDER BY COUNT(*) DESC
+           LIMIT ?2"#,
+    )?;
+    let topic_ids = stmt
+        .query_map(params![creator_id, PRIMARY_TOPIC_COUNT], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    conn.execute(
+        "UPDATE creators SET primary_topics = ?1 WHERE id = ?2",
+        params![serde_json::to_string(&topic_ids).unwrap_or_default(), creator_id],
+    )?;
+    Ok(())
+}
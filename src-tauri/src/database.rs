@@ -1,43 +1,151 @@
-use rusqlite::{Connection, params};
-use std::sync::Mutex;
-use std::path::PathBuf;
+use once_cell::sync::OnceCell;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, DatabaseName, Transaction, params};
+use std::fs;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
-use directories::ProjectDirs;
+use crate::error::TrendrError;
+use crate::paths;
+use crate::topics;
 
-pub static DATABASE: once_cell::sync::Lazy<Mutex<Option<Connection>>> =
-    once_cell::sync::Lazy::new(|| Mutex::new(None));
+/// Connection pool shared by every `with_db` call. Each pooled connection is configured by
+/// `configure_connection` when it is opened.
+pub static DATABASE: OnceCell<Pool<SqliteConnectionManager>> = OnceCell::new();
 
+/// The database runs in WAL mode, so `trendr.db-wal` and `trendr.db-shm` sit next to it
+/// while it is open. Copy all three (or checkpoint first) when moving the database, or
+/// use `backup_database`.
 fn get_db_path() -> PathBuf {
-    if let Some(proj_dirs) = ProjectDirs::from("com", "trendr", "Trendr") {
-        let data_dir = proj_dirs.data_dir();
-        std::fs::create_dir_all(data_dir).ok();
-        data_dir.join("trendr.db")
-    } else {
-        PathBuf::from("trendr.db")
-    }
+    paths::data_dir().join("trendr.db")
 }
 
 pub fn init_database(_app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let db_path = get_db_path();
-    let conn = Connection::open(&db_path)?;
-
-    create_tables(&conn)?;
-    seed_default_topics(&conn)?;
+    let pool = open_pool(&db_path)?;
 
-    let mut db = DATABASE.lock().unwrap();
-    *db = Some(conn);
+    if DATABASE.set(pool).is_err() {
+        return Err("Database already initialized".into());
+    }
 
     log::info!("Database initialized at {:?}", db_path);
     Ok(())
 }
 
-pub fn with_db<F, T>(f: F) -> Result<T, String>
+/// Open a pool on the database at `db_path`, creating or upgrading its schema
+fn open_pool(db_path: &Path) -> Result<Pool<SqliteConnectionManager>, Box<dyn std::error::Error>> {
+    let manager = SqliteConnectionManager::file(db_path).with_init(configure_connection);
+    let pool = Pool::new(manager)?;
+
+    {
+        let conn = pool.get()?;
+        create_tables(&conn)?;
+        migrate(&conn)?;
+        create_fulltext_index(&conn)?;
+        seed_default_topics(&conn)?;
+    }
+
+    Ok(pool)
+}
+
+/// Point `DATABASE` at a scratch file for tests, on first use, and empty every table.
+/// Tests share the one pool, so the returned guard keeps other database tests out until
+/// it is dropped.
+#[cfg(test)]
+pub fn test_database() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    DATABASE.get_or_init(|| {
+        let path = std::env::temp_dir().join(format!("trendr-test-{}.db", uuid::Uuid::new_v4()));
+        open_pool(&path).expect("failed to open the test database")
+    });
+    with_db(|conn| {
+        let tables: Vec<String> = conn
+            .prepare(
+                r#"SELECT name FROM sqlite_master
+                   WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE 'content_fts%'"#,
+            )?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        let deletes: String = tables.iter().map(|table| format!("DELETE FROM {};", table)).collect();
+        conn.execute_batch(&format!("PRAGMA foreign_keys = OFF; {} PRAGMA foreign_keys = ON;", deletes))
+    })
+    .expect("failed to empty the test database");
+
+    guard
+}
+
+/// SQLite only enforces foreign keys (and so ON DELETE CASCADE) when enabled per
+/// connection. WAL lets the UI keep reading while a collection run writes, and NORMAL
+/// sync is safe under WAL.
+fn configure_connection(conn: &mut Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        r#"
+        PRAGMA foreign_keys = ON;
+        PRAGMA journal_mode = WAL;
+        PRAGMA synchronous = NORMAL;
+        "#,
+    )
+}
+
+pub fn with_db<F, T>(f: F) -> Result<T, TrendrError>
 where
     F: FnOnce(&Connection) -> Result<T, rusqlite::Error>,
 {
-    let db = DATABASE.lock().map_err(|e| e.to_string())?;
-    let conn = db.as_ref().ok_or("Database not initialized")?;
-    f(conn).map_err(|e| e.to_string())
+    let pool = DATABASE
+        .get()
+        .ok_or_else(|| TrendrError::Database("Database not initialized".to_string()))?;
+    let conn = pool.get().map_err(|e| TrendrError::Database(e.to_string()))?;
+    Ok(f(&conn)?)
+}
+
+/// Like `with_db`, but runs `f` inside a transaction that commits when it returns Ok and
+/// rolls back on any error, so its writes land together or not at all.
+pub fn with_db_tx<F, T>(f: F) -> Result<T, TrendrError>
+where
+    F: FnOnce(&Transaction) -> Result<T, rusqlite::Error>,
+{
+    with_db(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        let value = f(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    })
+}
+
+/// Copy the live database to `dest` with SQLite's online backup API, which takes a
+/// consistent snapshot (including anything still in the WAL) even while a collection run
+/// is writing. The copy is switched out of WAL mode so it is a single self-contained
+/// file, and must pass `PRAGMA quick_check`. Returns its size in bytes.
+pub fn backup_database(dest: &Path) -> Result<u64, TrendrError> {
+    if let (Ok(dest), Ok(live)) = (dest.canonicalize(), get_db_path().canonicalize()) {
+        if dest == live {
+            return Err(TrendrError::Other("Can't back up the database onto itself".to_string()));
+        }
+    }
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    with_db(|conn| conn.backup(DatabaseName::Main, dest, None))?;
+
+    let check: String = {
+        let copy = Connection::open(dest)?;
+        copy.query_row("PRAGMA journal_mode = DELETE", [], |_| Ok(()))
+            .and_then(|_| copy.query_row("PRAGMA quick_check", [], |row| row.get(0)))?
+    };
+    if check != "ok" {
+        return Err(TrendrError::Database(format!(
+            "Backup at {} failed its integrity check: {}",
+            dest.display(),
+            check
+        )));
+    }
+
+    let bytes = fs::metadata(dest)?.len();
+    log::info!("Backed up database to {} ({} bytes)", dest.display(), bytes);
+    Ok(bytes)
 }
 
 fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
@@ -114,17 +222,234 @@ fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         );
 
+        CREATE TABLE IF NOT EXISTS topic_trend_scores (
+            topic_id TEXT PRIMARY KEY REFERENCES topics(id) ON DELETE CASCADE,
+            score REAL NOT NULL,
+            volume REAL NOT NULL,
+            momentum REAL NOT NULL,
+            recency REAL NOT NULL,
+            engagement REAL NOT NULL,
+            computed_at DATETIME NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS collection_cursors (
+            platform TEXT NOT NULL,
+            source TEXT NOT NULL,
+            cursor TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (platform, source)
+        );
+
+        CREATE TABLE IF NOT EXISTS source_watermarks (
+            platform TEXT NOT NULL,
+            source TEXT NOT NULL,
+            newest_at TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (platform, source)
+        );
+
+        CREATE TABLE IF NOT EXISTS collection_progress (
+            platform TEXT NOT NULL,
+            source TEXT NOT NULL,
+            completed_at DATETIME NOT NULL,
+            PRIMARY KEY (platform, source)
+        );
+
+        CREATE TABLE IF NOT EXISTS collection_rotation (
+            platform TEXT PRIMARY KEY,
+            next_offset INTEGER NOT NULL DEFAULT 0,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS pinned_snapshots (
+            label TEXT PRIMARY KEY,
+            data TEXT NOT NULL,
+            created_at DATETIME NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS entities (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            normalized TEXT NOT NULL UNIQUE,
+            first_seen_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS content_entities (
+            content_id TEXT REFERENCES content(id) ON DELETE CASCADE,
+            entity_id TEXT REFERENCES entities(id),
+            mention_count INTEGER NOT NULL DEFAULT 1,
+            PRIMARY KEY (content_id, entity_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS collection_health (
+            platform TEXT PRIMARY KEY,
+            consecutive_empty_runs INTEGER NOT NULL DEFAULT 0,
+            last_collected_at DATETIME
+        );
+
         CREATE INDEX IF NOT EXISTS idx_content_platform ON content(platform, published_at);
         CREATE INDEX IF NOT EXISTS idx_content_creator ON content(creator_id, published_at);
         CREATE INDEX IF NOT EXISTS idx_content_collected ON content(collected_at);
         CREATE INDEX IF NOT EXISTS idx_content_topics_topic ON content_topics(topic_id);
+        CREATE INDEX IF NOT EXISTS idx_content_entities_entity ON content_entities(entity_id);
         CREATE INDEX IF NOT EXISTS idx_alerts_created ON alerts(created_at);
     "#)?;
 
     Ok(())
 }
 
-fn slugify(text: &str) -> String {
+/// Schema changes since the tables in `create_tables`, keyed on `PRAGMA user_version`.
+/// Each runs once, on databases whose version is below its own. Append new entries with
+/// the next version; never edit or reorder ones that have shipped.
+const MIGRATIONS: &[(u32, &str)] = &[
+    // Refresh times, media, sentiment, deletion status, entity tagging, auto-created
+    // topics and repost detection
+    (
+        1,
+        r#"ALTER TABLE content ADD COLUMN last_refreshed_at DATETIME;
+        ALTER TABLE content ADD COLUMN media_url TEXT;
+        ALTER TABLE content ADD COLUMN thumbnail_url TEXT;
+        ALTER TABLE content ADD COLUMN sentiment REAL;
+        ALTER TABLE content ADD COLUMN status TEXT NOT NULL DEFAULT 'active';
+        ALTER TABLE content ADD COLUMN entities_extracted_at DATETIME;
+        ALTER TABLE topics ADD COLUMN auto_created_from TEXT;
+        ALTER TABLE content ADD COLUMN canonical_url TEXT;
+        ALTER TABLE content ADD COLUMN duplicate_of TEXT;"#,
+    ),
+    (2, "CREATE INDEX IF NOT EXISTS idx_content_canonical_url ON content(canonical_url);"),
+    // Link back to the original item. Reddit permalinks weren't kept before this, so older
+    // posts get the short /comments/ form, which redirects to the same place.
+    (
+        3,
+        r#"ALTER TABLE content ADD COLUMN url TEXT;
+        UPDATE content SET url = CASE platform
+            WHEN 'reddit' THEN 'https://reddit.com/comments/' || platform_id
+            WHEN 'x' THEN 'https://twitter.com/i/web/status/' || platform_id
+            WHEN 'youtube' THEN 'https://youtube.com/watch?v=' || platform_id
+        END;"#,
+    ),
+    // Video length, so Shorts and long-form videos can be told apart
+    (4, "ALTER TABLE content ADD COLUMN duration_seconds INTEGER;"),
+    // Language the platform detected for the text (X only), as a BCP 47 code
+    (5, "ALTER TABLE content ADD COLUMN language TEXT;"),
+    // Terms that stop a topic from being linked (JSON string array, like aliases)
+    (6, "ALTER TABLE topics ADD COLUMN exclusions TEXT;"),
+    // Hashtags and cashtags used by each content item, for tag frequency over time
+    (
+        7,
+        r#"CREATE TABLE IF NOT EXISTS content_tags (
+            content_id TEXT NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+            tag TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            PRIMARY KEY (content_id, kind, tag)
+        );
+        CREATE INDEX IF NOT EXISTS idx_content_tags_tag ON content_tags(kind, tag);"#,
+    ),
+    // Subreddit or search query each item was collected from, so re-extraction can apply
+    // the same source weight. Reddit permalinks name the subreddit; other sources before
+    // this are unknown.
+    (
+        8,
+        r#"ALTER TABLE content ADD COLUMN source TEXT;
+        UPDATE content SET source = substr(url, 22, instr(substr(url, 22), '/') - 1)
+        WHERE platform = 'reddit' AND url LIKE 'https://reddit.com/r/%/%';"#,
+    ),
+    // Drop entity mentions along with their content, like topic links. SQLite can't alter
+    // a foreign key, so the table is rebuilt; mentions of already-deleted content go.
+    (
+        9,
+        r#"CREATE TABLE content_entities_new (
+            content_id TEXT REFERENCES content(id) ON DELETE CASCADE,
+            entity_id TEXT REFERENCES entities(id),
+            mention_count INTEGER NOT NULL DEFAULT 1,
+            PRIMARY KEY (content_id, entity_id)
+        );
+        INSERT INTO content_entities_new (content_id, entity_id, mention_count)
+            SELECT content_id, entity_id, mention_count FROM content_entities
+            WHERE content_id IN (SELECT id FROM content);
+        DROP TABLE content_entities;
+        ALTER TABLE content_entities_new RENAME TO content_entities;
+        CREATE INDEX IF NOT EXISTS idx_content_entities_entity ON content_entities(entity_id);"#,
+    ),
+];
+
+/// Version of the newest migration, which every initialized database is at. Backups
+/// record it so they are only restored into the same schema.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].0;
+
+/// Apply pending migrations in order inside one transaction, bumping `user_version` after
+/// each, so a failure leaves the database at its previous version.
+pub fn migrate(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let current: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let pending: Vec<&(u32, &str)> = MIGRATIONS.iter().filter(|(version, _)| *version > current).collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for (version, sql) in pending {
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version)?;
+        log::info!("Applied schema migration {}", version);
+    }
+    tx.commit()
+}
+
+/// Full-text index over `content.text_content`, stored by reference to the content row's
+/// rowid and kept in sync by triggers. Optional: SQLite builds without FTS5 skip it and
+/// `search_content` reports search as unavailable.
+fn create_fulltext_index(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let fts5: bool = conn.query_row("SELECT sqlite_compileoption_used('ENABLE_FTS5')", [], |row| row.get(0))?;
+    if !fts5 {
+        log::warn!("SQLite was built without FTS5; content search is disabled");
+        return Ok(());
+    }
+
+    let existed = has_fulltext_index(conn)?;
+    conn.execute_batch(
+        r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS content_fts USING fts5(
+            text_content,
+            content = 'content',
+            content_rowid = 'rowid'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS content_fts_insert AFTER INSERT ON content BEGIN
+            INSERT INTO content_fts(rowid, text_content) VALUES (new.rowid, new.text_content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS content_fts_delete AFTER DELETE ON content BEGIN
+            INSERT INTO content_fts(content_fts, rowid, text_content)
+            VALUES ('delete', old.rowid, old.text_content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS content_fts_update AFTER UPDATE OF text_content ON content BEGIN
+            INSERT INTO content_fts(content_fts, rowid, text_content)
+            VALUES ('delete', old.rowid, old.text_content);
+            INSERT INTO content_fts(rowid, text_content) VALUES (new.rowid, new.text_content);
+        END;
+        "#,
+    )?;
+
+    // Index content stored before the table existed
+    if !existed {
+        conn.execute("INSERT INTO content_fts(content_fts) VALUES ('rebuild')", [])?;
+        log::info!("Built full-text index over existing content");
+    }
+
+    Ok(())
+}
+
+/// Whether the `content_fts` search index exists in this database
+pub fn has_fulltext_index(conn: &Connection) -> Result<bool, rusqlite::Error> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'content_fts')",
+        [],
+        |row| row.get(0),
+    )
+}
+
+pub fn slugify(text: &str) -> String {
     text.to_lowercase()
         .chars()
         .map(|c| if c.is_alphanumeric() { c } else { '-' })
@@ -156,6 +481,9 @@ fn seed_default_topics(conn: &Connection) -> Result<(), rusqlite::Error> {
         for (name, keywords) in topics {
             let id = uuid::Uuid::new_v4().to_string();
             let slug = slugify(name);
+            let keywords: Vec<topics::Keyword> =
+                keywords.iter().map(|k| topics::Keyword::Plain(k.to_string())).collect();
+            let keywords = topics::normalize_keywords(&keywords, false).unwrap_or(keywords);
             let keywords_json = serde_json::to_string(&keywords).unwrap_or_default();
 
             conn.execute(
@@ -169,3 +497,196 @@ fn seed_default_topics(conn: &Connection) -> Result<(), rusqlite::Error> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        let names = stmt.query_map([], |row| row.get(1)).unwrap();
+        names.collect::<Result<_, _>>().unwrap()
+    }
+
+    fn user_version(conn: &Connection) -> u32 {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn migrations_bring_a_new_database_to_the_current_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        assert_eq!(user_version(&conn), 0);
+
+        migrate(&conn).unwrap();
+
+        assert_eq!(user_version(&conn), SCHEMA_VERSION);
+        let content_columns = columns(&conn, "content");
+        for column in ["status", "canonical_url", "duplicate_of", "url", "duration_seconds", "language"] {
+            assert!(content_columns.iter().any(|c| c == column), "content.{} missing", column);
+        }
+        let topic_columns = columns(&conn, "topics");
+        for column in ["auto_created_from", "exclusions"] {
+            assert!(topic_columns.iter().any(|c| c == column), "topics.{} missing", column);
+        }
+        assert_eq!(columns(&conn, "content_tags"), ["content_id", "tag", "kind"]);
+    }
+
+    #[test]
+    fn migrations_only_run_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        migrate(&conn).unwrap();
+        // A second run would fail on the duplicate ALTER TABLE columns
+        migrate(&conn).unwrap();
+        assert_eq!(user_version(&conn), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn migrations_resume_from_the_stored_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        conn.execute_batch(MIGRATIONS[0].1).unwrap();
+        conn.execute_batch(MIGRATIONS[1].1).unwrap();
+        conn.pragma_update(None, "user_version", 2).unwrap();
+
+        migrate(&conn).unwrap();
+
+        assert_eq!(user_version(&conn), SCHEMA_VERSION);
+        assert!(columns(&conn, "content").iter().any(|c| c == "duration_seconds"));
+    }
+
+    #[test]
+    fn deleting_content_cascades_to_its_topic_links() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        configure_connection(&mut conn).unwrap();
+        create_tables(&conn).unwrap();
+        conn.execute_batch(
+            r#"INSERT INTO topics (id, name, slug) VALUES ('t1', 'Rust', 'rust');
+               INSERT INTO content (id, platform, platform_id, content_type) VALUES ('c1', 'reddit', 'p1', 'post');
+               INSERT INTO content_topics (content_id, topic_id, confidence) VALUES ('c1', 't1', 0.5);"#,
+        )
+        .unwrap();
+
+        conn.execute("DELETE FROM content WHERE id = 'c1'", []).unwrap();
+
+        let links: i64 = conn
+            .query_row("SELECT COUNT(*) FROM content_topics", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(links, 0);
+        let topics: i64 = conn.query_row("SELECT COUNT(*) FROM topics", [], |row| row.get(0)).unwrap();
+        assert_eq!(topics, 1);
+    }
+
+    #[test]
+    fn deleting_content_cascades_to_its_entities_after_migrating() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        configure_connection(&mut conn).unwrap();
+        create_tables(&conn).unwrap();
+        // The table as databases before migration 9 have it
+        conn.execute_batch(
+            r#"DROP TABLE content_entities;
+               CREATE TABLE content_entities (
+                   content_id TEXT REFERENCES content(id),
+                   entity_id TEXT REFERENCES entities(id),
+                   mention_count INTEGER NOT NULL DEFAULT 1,
+                   PRIMARY KEY (content_id, entity_id)
+               );
+               INSERT INTO entities (id, name, normalized) VALUES ('e1', 'Ferris', 'ferris');
+               INSERT INTO content (id, platform, platform_id, content_type) VALUES
+                   ('c1', 'reddit', 'p1', 'post'),
+                   ('c2', 'reddit', 'p2', 'post');
+               INSERT INTO content_entities (content_id, entity_id, mention_count)
+               VALUES ('c1', 'e1', 2), ('c2', 'e1', 1);"#,
+        )
+        .unwrap();
+
+        migrate(&conn).unwrap();
+        conn.execute("DELETE FROM content WHERE id = 'c1'", []).unwrap();
+
+        let mentions: Vec<(String, i64)> = conn
+            .prepare("SELECT content_id, mention_count FROM content_entities")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(mentions, vec![("c2".to_string(), 1)]);
+        let entities: i64 = conn.query_row("SELECT COUNT(*) FROM entities", [], |row| row.get(0)).unwrap();
+        assert_eq!(entities, 1);
+    }
+
+    #[test]
+    fn backup_copy_has_the_same_rows() {
+        let _db = test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug, keywords) VALUES ('rust', 'Rust', 'rust', '[]'), ('go', 'Go', 'go', '[]');
+                   INSERT INTO creators (id, platform, platform_id, username) VALUES ('ferris', 'x', 'u1', 'ferris');
+                   INSERT INTO content (id, platform, platform_id, creator_id, content_type, text_content) VALUES
+                       ('c1', 'x', 't1', 'ferris', 'post', 'rust 2.0'),
+                       ('c2', 'x', 't2', 'ferris', 'post', 'go 2.0'),
+                       ('c3', 'reddit', 'r1', NULL, 'post', 'rust and go');
+                   INSERT INTO content_topics (content_id, topic_id, confidence)
+                   VALUES ('c1', 'rust', 1.0), ('c2', 'go', 1.0), ('c3', 'rust', 0.5), ('c3', 'go', 0.5);"#,
+            )
+        })
+        .unwrap();
+
+        let dest = std::env::temp_dir()
+            .join(format!("trendr-backup-{}", uuid::Uuid::new_v4()))
+            .join("trendr.db");
+        let bytes = backup_database(&dest).unwrap();
+        assert_eq!(bytes, fs::metadata(&dest).unwrap().len());
+
+        let tables = ["topics", "creators", "content", "content_topics"];
+        let counts = |conn: &Connection| -> Vec<i64> {
+            tables
+                .iter()
+                .map(|table| {
+                    conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                        .unwrap()
+                })
+                .collect()
+        };
+        let live = with_db(|conn| Ok(counts(conn))).unwrap();
+        let copy = Connection::open_with_flags(&dest, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+        assert_eq!(live, vec![2, 1, 3, 4]);
+        assert_eq!(counts(&copy), live);
+
+        let journal_mode: String = copy.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(journal_mode, "delete");
+        drop(copy);
+        fs::remove_dir_all(dest.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn concurrent_writers_share_the_pool_without_locking_errors() {
+        let _db = test_database();
+        const THREADS: usize = 8;
+        const INSERTS: usize = 25;
+
+        let writers: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                std::thread::spawn(move || {
+                    for i in 0..INSERTS {
+                        with_db(|conn| {
+                            conn.execute(
+                                "INSERT INTO content (id, platform, platform_id, content_type) VALUES (?1, 'x', ?1, 'post')",
+                                params![format!("{}-{}", thread, i)],
+                            )
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let rows: i64 = with_db(|conn| conn.query_row("SELECT COUNT(*) FROM content", [], |row| row.get(0))).unwrap();
+        assert_eq!(rows, (THREADS * INSERTS) as i64);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::content::status;
+use crate::database::with_db;
+use crate::error::TrendrError;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Longer runs of capitalized words are usually Title Case headlines, not names
+const MAX_PHRASE_WORDS: usize = 4;
+
+/// Capitalized words that commonly open a sentence or phrase but never name anything
+const STOPWORDS: &[&str] = &[
+    "a", "also", "an", "and", "any", "are", "as", "at", "but", "can", "do", "does", "edit",
+    "for", "he", "hey", "hi", "how", "i", "i'm", "if", "imo", "in", "is", "it", "it's", "just",
+    "lol", "my", "no", "not", "of", "on", "or", "our", "she", "so", "thanks", "that", "the",
+    "there", "these", "they", "this", "those", "to", "today", "tldr", "update", "was", "we",
+    "what", "when", "where", "who", "why", "will", "with", "yes", "you", "your",
+];
+
+#[derive(Debug, Clone)]
+pub struct EntityMention {
+    pub name: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendingEntity {
+    pub id: String,
+    pub name: String,
+    /// Total mentions in the window
+    pub mentions: i64,
+    /// Distinct content items mentioning the entity in the window
+    #[serde(rename = "contentCount")]
+    pub content_count: i64,
+    /// Mentions in the window of the same length just before
+    #[serde(rename = "previousMentions")]
+    pub previous_mentions: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntityTagResult {
+    scanned: u32,
+    tagged: u32,
+    #[serde(rename = "entitiesLinked")]
+    entities_linked: u32,
+}
+
+/// Pull candidate named entities out of free text: runs of capitalized words such as
+/// "Elon Musk" or "Federal Reserve", plus acronyms like "NVDA". A lone capitalized word
+/// that opens a sentence is skipped since it is usually just sentence case.
+pub fn extract_entities(text: &str) -> Vec<EntityMention> {
+    let mut counts: HashMap<String, EntityMention> = HashMap::new();
+    let mut phrase: Vec<&str> = Vec::new();
+    let mut phrase_at_sentence_start = false;
+    let mut sentence_start = true;
+
+    for raw in text.split_whitespace() {
+        let word = raw.trim_matches(|c: char| !c.is_alphanumeric());
+        let word = word
+            .strip_suffix("'s")
+            .or_else(|| word.strip_suffix("\u{2019}s"))
+            .unwrap_or(word);
+
+        if word.is_empty() {
+            flush_phrase(&mut phrase, phrase_at_sentence_start, &mut counts);
+            continue;
+        }
+
+        let is_stopword = STOPWORDS.contains(&word.to_lowercase().as_str());
+        if is_capitalized(word) && !(phrase.is_empty() && is_stopword) {
+            if phrase.is_empty() {
+                phrase_at_sentence_start = sentence_start;
+            }
+            phrase.push(word);
+        } else {
+            flush_phrase(&mut phrase, phrase_at_sentence_start, &mut counts);
+        }
+
+        // Punctuation after a word ends the phrase, so "Apple, Google" is two entities
+        if raw.ends_with(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '\u{2019}')) {
+            flush_phrase(&mut phrase, phrase_at_sentence_start, &mut counts);
+        }
+        sentence_start = raw
+            .trim_end_matches(['"', '\'', ')', '\u{201d}'])
+            .ends_with(['.', '!', '?']);
+    }
+    flush_phrase(&mut phrase, phrase_at_sentence_start, &mut counts);
+
+    counts.into_values().collect()
+}
+
+fn flush_phrase(phrase: &mut Vec<&str>, at_sentence_start: bool, counts: &mut HashMap<String, EntityMention>) {
+    if phrase.is_empty() {
+        return;
+    }
+
+    let sentence_case = phrase.len() == 1 && at_sentence_start && !is_acronym(phrase[0]);
+    if phrase.len() <= MAX_PHRASE_WORDS && !sentence_case {
+        let name = phrase.join(" ");
+        counts
+            .entry(name.to_lowercase())
+            .or_insert_with(|| EntityMention { name, count: 0 })
+            .count += 1;
+    }
+    phrase.clear();
+}
+
+fn is_capitalized(word: &str) -> bool {
+    word.chars().count() >= 2 && word.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+fn is_acronym(word: &str) -> bool {
+    word.chars().count() >= 2
+        && word.chars().any(|c| c.is_alphabetic())
+        && word.chars().all(|c| c.is_uppercase() || c.is_ascii_digit())
+}
+
+/// Extract entities from up to `limit` content items that haven't been scanned yet and
+/// record their mention counts.
+pub fn tag_content(limit: i64) -> Result<EntityTagResult, TrendrError> {
+    with_db(|conn| {
+        let pending: Vec<(String, String)> = {
+            let mut stmt = conn.prepare(
+                r#"SELECT id, text_content FROM content
+                   WHERE entities_extracted_at IS NULL AND text_content IS NOT NULL
+                   ORDER BY collected_at DESC
+                   LIMIT ?1"#,
+            )?;
+            let rows = stmt.query_map(params![limit], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        let mut tagged = 0u32;
+        let mut entities_linked = 0u32;
+
+        for (content_id, text) in &pending {
+            let mentions = extract_entities(text);
+            for mention in &mentions {
+                let entity_id = get_or_create_entity(&tx, &mention.name)?;
+                tx.execute(
+                    r#"INSERT INTO content_entities (content_id, entity_id, mention_count)
+                       VALUES (?1, ?2, ?3)
+                       ON CONFLICT(content_id, entity_id) DO UPDATE SET mention_count = excluded.mention_count"#,
+                    params![content_id, entity_id, mention.count],
+                )?;
+            }
+
+            tx.execute(
+                "UPDATE content SET entities_extracted_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                params![content_id],
+            )?;
+
+            if !mentions.is_empty() {
+                tagged += 1;
+                entities_linked += mentions.len() as u32;
+            }
+        }
+        tx.commit()?;
+
+        Ok(EntityTagResult {
+            scanned: pending.len() as u32,
+            tagged,
+            entities_linked,
+        })
+    })
+}
+
+fn get_or_create_entity(conn: &Connection, name: &str) -> Result<String, rusqlite::Error> {
+    let normalized = name.to_lowercase();
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM entities WHERE normalized = ?1",
+            params![normalized],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO entities (id, name, normalized) VALUES (?1, ?2, ?3)",
+        params![id, name, normalized],
+    )?;
+    Ok(id)
+}
+
+/// Entities mentioned by the most distinct content items in the last `days`, with the
+/// previous window's mentions for comparison.
+pub fn get_trending_entities(days: i64, limit: i64) -> Result<Vec<TrendingEntity>, TrendrError> {
+    let days = days.max(1);
+    let window = format!("-{} days", days);
+    let previous_window = format!("-{} days", days * 2);
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"WITH mentions AS (
+                   SELECT ce.entity_id, ce.content_id, ce.mention_count,
+                          COALESCE(datetime(c.published_at), datetime(c.collected_at)) AS seen_at
+                   FROM content_entities ce
+                   JOIN content c ON c.id = ce.content_id
+                   WHERE c.status != ?4
+               )
+               SELECT e.id, e.name,
+                      SUM(CASE WHEN m.seen_at > datetime('now', ?1) THEN m.mention_count ELSE 0 END) AS mentions,
+                      COUNT(DISTINCT CASE WHEN m.seen_at > datetime('now', ?1) THEN m.content_id END) AS content_count,
+                      SUM(CASE WHEN m.seen_at <= datetime('now', ?1) THEN m.mention_count ELSE 0 END) AS previous_mentions
+               FROM entities e
+               JOIN mentions m ON m.entity_id = e.id
+               WHERE m.seen_at > datetime('now', ?2)
+               GROUP BY e.id
+               HAVING mentions > 0
+               ORDER BY content_count DESC, mentions DESC
+               LIMIT ?3"#,
+        )?;
+
+        let rows = stmt.query_map(params![window, previous_window, limit, status::DELETED], |row| {
+            Ok(TrendingEntity {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                mentions: row.get(2)?,
+                content_count: row.get(3)?,
+                previous_mentions: row.get(4)?,
+            })
+        })?;
+
+        rows.collect()
+    })
+}
@@ -0,0 +1,191 @@
+use crate::rate_limit::FetchError;
+use reqwest::StatusCode;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::time::Duration;
+
+/// An error categorized so the frontend can tell what went wrong (bad credentials, a
+/// rate limit, a database failure...) without parsing the message. Serializes as
+/// `{ kind, message, retryAfterSecs }`, where `kind` is the camelCase variant name and
+/// `retryAfterSecs` is only set for rate limits that said when they reset.
+#[derive(Debug, thiserror::Error)]
+pub enum TrendrError {
+    /// Credentials are missing from the request, invalid, or were rejected
+    #[error("{0}")]
+    Auth(String),
+    /// The platform refused the request for rate or quota reasons
+    #[error("Rate limited{}", resets_in(.retry_after))]
+    RateLimited { retry_after: Option<Duration> },
+    /// No usable response: connection failures, timeouts and server errors
+    #[error("{0}")]
+    Network(String),
+    #[error("Database error: {0}")]
+    Database(String),
+    /// Credentials or sources the operation needs haven't been set up in Settings
+    #[error("{0}")]
+    NotConfigured(String),
+    /// A response or file couldn't be read
+    #[error("{0}")]
+    Parse(String),
+    /// Anything not yet categorized, with its message as-is
+    #[error("{0}")]
+    Other(String),
+}
+
+fn resets_in(retry_after: &Option<Duration>) -> String {
+    retry_after
+        .map(|wait| format!(" (resets in {}s)", wait.as_secs()))
+        .unwrap_or_default()
+}
+
+impl TrendrError {
+    /// Categorize an unsuccessful HTTP status: 401 and 403 are credential problems, 429
+    /// a rate limit and 5xx a server failure. Other statuses keep `message` uncategorized.
+    pub fn from_status(status: StatusCode, message: String) -> Self {
+        match status.as_u16() {
+            401 | 403 => TrendrError::Auth(message),
+            429 => TrendrError::RateLimited { retry_after: None },
+            500..=599 => TrendrError::Network(message),
+            _ => TrendrError::Other(message),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TrendrError::Auth(_) => "auth",
+            TrendrError::RateLimited { .. } => "rateLimited",
+            TrendrError::Network(_) => "network",
+            TrendrError::Database(_) => "database",
+            TrendrError::NotConfigured(_) => "notConfigured",
+            TrendrError::Parse(_) => "parse",
+            TrendrError::Other(_) => "other",
+        }
+    }
+}
+
+impl Serialize for TrendrError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let retry_after = match self {
+            TrendrError::RateLimited { retry_after } => retry_after.map(|wait| wait.as_secs()),
+            _ => None,
+        };
+
+        let mut state = serializer.serialize_struct("TrendrError", 3)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("retryAfterSecs", &retry_after)?;
+        state.end()
+    }
+}
+
+impl From<rusqlite::Error> for TrendrError {
+    fn from(error: rusqlite::Error) -> Self {
+        TrendrError::Database(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for TrendrError {
+    fn from(error: reqwest::Error) -> Self {
+        match error.status() {
+            Some(status) => TrendrError::from_status(status, error.to_string()),
+            None if error.is_decode() => TrendrError::Parse(error.to_string()),
+            None => TrendrError::Network(format!("Request failed: {}", error)),
+        }
+    }
+}
+
+impl From<serde_json::Error> for TrendrError {
+    fn from(error: serde_json::Error) -> Self {
+        TrendrError::Parse(error.to_string())
+    }
+}
+
+/// File reads and writes (exports, backups, feeds) fail with the OS message as-is
+impl From<std::io::Error> for TrendrError {
+    fn from(error: std::io::Error) -> Self {
+        TrendrError::Other(error.to_string())
+    }
+}
+
+impl From<FetchError> for TrendrError {
+    fn from(error: FetchError) -> Self {
+        match error {
+            FetchError::RateLimited { retry_after } => TrendrError::RateLimited { retry_after },
+            FetchError::Unauthorized => TrendrError::Auth("Credentials were rejected".to_string()),
+            FetchError::Transient { message, .. } => TrendrError::Network(message),
+            FetchError::Failed(message) => TrendrError::Other(message),
+        }
+    }
+}
+
+impl From<TrendrError> for FetchError {
+    fn from(error: TrendrError) -> Self {
+        match error {
+            TrendrError::RateLimited { retry_after } => FetchError::RateLimited { retry_after },
+            TrendrError::Auth(_) => FetchError::Unauthorized,
+            TrendrError::Network(message) => FetchError::Transient {
+                message,
+                retry_after: None,
+            },
+            other => FetchError::Failed(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categorized(status: u16) -> TrendrError {
+        TrendrError::from_status(StatusCode::from_u16(status).unwrap(), format!("HTTP {}", status))
+    }
+
+    #[test]
+    fn rejected_credentials_are_auth_errors() {
+        for status in [401, 403] {
+            let error = categorized(status);
+            assert!(matches!(&error, TrendrError::Auth(message) if *message == format!("HTTP {}", status)));
+            assert_eq!(error.kind(), "auth");
+        }
+    }
+
+    #[test]
+    fn too_many_requests_is_a_rate_limit_without_a_reset_time() {
+        let error = categorized(429);
+        assert!(matches!(error, TrendrError::RateLimited { retry_after: None }));
+        assert_eq!(error.kind(), "rateLimited");
+        assert_eq!(error.to_string(), "Rate limited");
+    }
+
+    #[test]
+    fn server_errors_are_network_errors() {
+        for status in [500, 502, 503, 599] {
+            let error = categorized(status);
+            assert!(matches!(&error, TrendrError::Network(message) if *message == format!("HTTP {}", status)));
+            assert_eq!(error.kind(), "network");
+        }
+    }
+
+    #[test]
+    fn other_statuses_keep_their_message_uncategorized() {
+        for status in [400, 404, 409, 418] {
+            let error = categorized(status);
+            assert!(matches!(&error, TrendrError::Other(message) if *message == format!("HTTP {}", status)));
+            assert_eq!(error.kind(), "other");
+        }
+    }
+
+    #[test]
+    fn serializes_kind_message_and_reset_time() {
+        let json = serde_json::to_value(categorized(403)).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "auth", "message": "HTTP 403", "retryAfterSecs": null }));
+
+        let limited = TrendrError::RateLimited {
+            retry_after: Some(Duration::from_secs(90)),
+        };
+        assert_eq!(
+            serde_json::to_value(limited).unwrap(),
+            serde_json::json!({ "kind": "rateLimited", "message": "Rate limited (resets in 90s)", "retryAfterSecs": 90 })
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::database::with_db;
+use crate::error::TrendrError;
+use rusqlite::{params, Row, Rows};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<ExportFormat> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" | "ndjson" | "jsonl" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Narrow an export the same way `get_content` filters: one platform, and publish dates
+/// (collection date when unknown) between `from` and `to` inclusive, as ISO dates
+#[derive(Debug, Clone, Default)]
+pub struct ContentExportFilter {
+    pub platform: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Columns written by an export, in output order. Names match the `content` table.
+const EXPORT_COLUMNS: [&str; 16] = [
+    "id",
+    "platform",
+    "platform_id",
+    "creator_id",
+    "content_type",
+    "text_content",
+    "engagement_likes",
+    "engagement_comments",
+    "engagement_shares",
+    "engagement_views",
+    "published_at",
+    "collected_at",
+    "url",
+    "status",
+    "duplicate_of",
+    "duration_seconds",
+];
+
+#[derive(Debug, Serialize)]
+struct ExportRow {
+    id: String,
+    platform: String,
+    platform_id: String,
+    creator_id: Option<String>,
+    content_type: String,
+    text_content: Option<String>,
+    engagement_likes: i64,
+    engagement_comments: i64,
+    engagement_shares: i64,
+    engagement_views: Option<i64>,
+    published_at: Option<String>,
+    collected_at: String,
+    url: Option<String>,
+    status: String,
+    duplicate_of: Option<String>,
+    duration_seconds: Option<i64>,
+}
+
+impl ExportRow {
+    /// Values in `EXPORT_COLUMNS` order; NULLs become empty fields
+    fn csv_fields(&self) -> [String; 16] {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let number = |value: Option<i64>| value.map(|n| n.to_string()).unwrap_or_default();
+        [
+            self.id.clone(),
+            self.platform.clone(),
+            self.platform_id.clone(),
+            text(&self.creator_id),
+            self.content_type.clone(),
+            text(&self.text_content),
+            self.engagement_likes.to_string(),
+            self.engagement_comments.to_string(),
+            self.engagement_shares.to_string(),
+            number(self.engagement_views),
+            text(&self.published_at),
+            self.collected_at.clone(),
+            text(&self.url),
+            self.status.clone(),
+            text(&self.duplicate_of),
+            number(self.duration_seconds),
+        ]
+    }
+}
+
+/// Write matching content to `path`, oldest first, streaming rows straight from the
+/// database to disk. Returns the number of rows written.
+pub fn export_content(format: ExportFormat, path: &Path, filter: &ContentExportFilter) -> Result<usize, TrendrError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    let written = with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            r#"SELECT {} FROM content
+               WHERE (?1 IS NULL OR platform = ?1)
+               AND (?2 IS NULL OR date(COALESCE(datetime(published_at), datetime(collected_at))) >= date(?2))
+               AND (?3 IS NULL OR date(COALESCE(datetime(published_at), datetime(collected_at))) <= date(?3))
+               ORDER BY collected_at ASC"#,
+            EXPORT_COLUMNS.join(", ")
+        ))?;
+        let mut rows = stmt.query(params![&filter.platform, &filter.from, &filter.to])?;
+        Ok(write_rows(&mut rows, format, &mut writer))
+    })??;
+
+    writer.flush()?;
+    Ok(written)
+}
+
+fn export_row(row: &Row) -> Result<ExportRow, rusqlite::Error> {
+    Ok(ExportRow {
+        id: row.get(0)?,
+        platform: row.get(1)?,
+        platform_id: row.get(2)?,
+        creator_id: row.get(3)?,
+        content_type: row.get(4)?,
+        text_content: row.get(5)?,
+        engagement_likes: row.get(6)?,
+        engagement_comments: row.get(7)?,
+        engagement_shares: row.get(8)?,
+        engagement_views: row.get(9)?,
+        published_at: row.get(10)?,
+        collected_at: row.get(11)?,
+        url: row.get(12)?,
+        status: row.get(13)?,
+        duplicate_of: row.get(14)?,
+        duration_seconds: row.get(15)?,
+    })
+}
+
+fn write_rows(rows: &mut Rows, format: ExportFormat, writer: &mut impl Write) -> Result<usize, TrendrError> {
+    if format == ExportFormat::Csv {
+        writeln!(writer, "{}", EXPORT_COLUMNS.join(","))?;
+    }
+
+    let mut written = 0;
+    while let Some(row) = rows.next()? {
+        let row = export_row(row)?;
+
+        let line = match format {
+            ExportFormat::Csv => row
+                .csv_fields()
+                .iter()
+                .map(|field| escape_csv(field))
+                .collect::<Vec<_>>()
+                .join(","),
+            ExportFormat::Json => serde_json::to_string(&row)?,
+        };
+        writeln!(writer, "{}", line)?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break, doubling inner quotes
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_fields_are_left_alone() {
+        assert_eq!(escape_csv("rust"), "rust");
+        assert_eq!(escape_csv(""), "");
+        assert_eq!(escape_csv("it's fine; really"), "it's fine; really");
+    }
+
+    #[test]
+    fn fields_with_separators_are_quoted() {
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("line one\nline two"), "\"line one\nline two\"");
+        assert_eq!(escape_csv("carriage\rreturn"), "\"carriage\rreturn\"");
+        assert_eq!(escape_csv(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(escape_csv("\""), "\"\"\"\"");
+    }
+}
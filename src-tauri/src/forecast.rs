@@ -0,0 +1,156 @@
+use crate::content::status;
+use crate::database::with_db;
+use crate::error::TrendrError;
+use chrono::{Duration, NaiveDate, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Days of history the trend line is fitted to
+const HISTORY_DAYS: i64 = 28;
+
+/// z-score for a ~95% band around the projection
+const BAND_Z: f64 = 1.96;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyCount {
+    pub date: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForecastPoint {
+    pub date: String,
+    pub projected: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Projected daily mention counts for a topic.
+///
+/// Model: an ordinary least-squares line y = intercept + slope × day fitted to the last
+/// 28 daily counts (days without content count as 0). Each future day is the line's value,
+/// floored at 0, with a band of ±1.96 × the residual standard deviation. Totals sum the
+/// projected days. This is a rough "at the current rate" estimate, not a seasonal model.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicForecast {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    /// Change in daily mentions per day according to the fitted line
+    #[serde(rename = "slopePerDay")]
+    pub slope_per_day: f64,
+    pub history: Vec<DailyCount>,
+    pub points: Vec<ForecastPoint>,
+    #[serde(rename = "projectedTotal")]
+    pub projected_total: f64,
+    #[serde(rename = "projectedTotalLower")]
+    pub projected_total_lower: f64,
+    #[serde(rename = "projectedTotalUpper")]
+    pub projected_total_upper: f64,
+}
+
+pub fn forecast_topic(topic_id: &str, horizon_days: i64) -> Result<TopicForecast, TrendrError> {
+    let horizon_days = horizon_days.clamp(1, 90);
+    let today = Utc::now().date_naive();
+    let first_day = today - Duration::days(HISTORY_DAYS - 1);
+
+    let (topic_name, counts) = with_db(|conn| {
+        let topic_name: Option<String> = conn
+            .query_row("SELECT name FROM topics WHERE id = ?1", params![topic_id], |row| row.get(0))
+            .optional()?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT date(COALESCE(datetime(c.published_at), datetime(c.collected_at))) as day, COUNT(*)
+               FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2 AND day >= ?3
+               GROUP BY day"#,
+        )?;
+        let rows = stmt.query_map(
+            params![topic_id, status::DELETED, first_day.format("%Y-%m-%d").to_string()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+        )?;
+
+        let mut counts = HashMap::new();
+        for row in rows {
+            let (day, count) = row?;
+            counts.insert(day, count);
+        }
+        Ok((topic_name, counts))
+    })?;
+
+    let topic_name = topic_name.ok_or_else(|| TrendrError::Other(format!("Topic not found: {}", topic_id)))?;
+
+    let history: Vec<DailyCount> = (0..HISTORY_DAYS)
+        .map(|offset| {
+            let date = format_date(first_day + Duration::days(offset));
+            let count = counts.get(&date).copied().unwrap_or(0);
+            DailyCount { date, count }
+        })
+        .collect();
+
+    let values: Vec<f64> = history.iter().map(|d| d.count as f64).collect();
+    let (intercept, slope, residual_sd) = fit_line(&values);
+    let margin = BAND_Z * residual_sd;
+
+    let points: Vec<ForecastPoint> = (1..=horizon_days)
+        .map(|ahead| {
+            let x = (HISTORY_DAYS - 1 + ahead) as f64;
+            let projected = (intercept + slope * x).max(0.0);
+            ForecastPoint {
+                date: format_date(today + Duration::days(ahead)),
+                projected,
+                lower: (projected - margin).max(0.0),
+                upper: projected + margin,
+            }
+        })
+        .collect();
+
+    Ok(TopicForecast {
+        topic_id: topic_id.to_string(),
+        topic_name,
+        slope_per_day: slope,
+        projected_total: points.iter().map(|p| p.projected).sum(),
+        projected_total_lower: points.iter().map(|p| p.lower).sum(),
+        projected_total_upper: points.iter().map(|p| p.upper).sum(),
+        history,
+        points,
+    })
+}
+
+/// Least-squares fit over x = 0..n, returning (intercept, slope, residual standard deviation)
+fn fit_line(values: &[f64]) -> (f64, f64, f64) {
+    let n = values.len() as f64;
+    if values.len() < 2 {
+        return (values.first().copied().unwrap_or(0.0), 0.0, 0.0);
+    }
+
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    for (i, y) in values.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        covariance += dx * (y - mean_y);
+        variance_x += dx * dx;
+    }
+
+    let slope = covariance / variance_x;
+    let intercept = mean_y - slope * mean_x;
+
+    let squared_residuals: f64 = values
+        .iter()
+        .enumerate()
+        .map(|(i, y)| (y - (intercept + slope * i as f64)).powi(2))
+        .sum();
+    let residual_sd = (squared_residuals / (n - 2.0).max(1.0)).sqrt();
+
+    (intercept, slope, residual_sd)
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
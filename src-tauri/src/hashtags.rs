@@ -0,0 +1,234 @@
+use crate::content::status;
+use crate::database::{slugify, with_db};
+use crate::error::TrendrError;
+use crate::settings::AutoCreateTopicsSettings;
+use crate::topics::{normalize_keywords, parse_keywords, Keyword};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Values of `content_tags.kind`
+pub mod tag_kind {
+    pub const HASHTAG: &str = "hashtag";
+    pub const CASHTAG: &str = "cashtag";
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoCreatedTopic {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    pub name: String,
+    pub hashtag: String,
+    /// Distinct content items that used the hashtag in the window
+    pub mentions: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendingTag {
+    /// Hashtags lowercased, cashtags uppercased, both without their prefix
+    pub tag: String,
+    pub kind: String,
+    /// Distinct content items using the tag in the window
+    #[serde(rename = "contentCount")]
+    pub content_count: i64,
+    /// Content items using it in the window of the same length just before
+    #[serde(rename = "previousCount")]
+    pub previous_count: i64,
+}
+
+/// Distinct hashtags in `text`, lowercased and without the leading '#'. Tags must
+/// contain a letter so "#1" or "#2024" aren't treated as topics.
+pub fn extract_hashtags(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut tags = Vec::new();
+
+    for (i, _) in text.match_indices('#') {
+        // Skip '#' inside words and URLs (e.g. "C#", "page#section")
+        if text[..i].chars().next_back().is_some_and(|c| c.is_alphanumeric() || c == '/') {
+            continue;
+        }
+
+        let tag: String = text[i + 1..]
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        let length = tag.chars().count();
+        if !(2..=50).contains(&length) || !tag.chars().any(|c| c.is_alphabetic()) {
+            continue;
+        }
+
+        let tag = tag.to_lowercase();
+        if seen.insert(tag.clone()) {
+            tags.push(tag);
+        }
+    }
+
+    tags
+}
+
+/// Distinct cashtags in `text`, uppercased and without the leading '$'. Tickers are one
+/// to six letters, so amounts like "$100" or "$5k" aren't picked up.
+pub fn extract_cashtags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+
+    for (i, _) in text.match_indices('$') {
+        if text[..i].chars().next_back().is_some_and(|c| c.is_alphanumeric()) {
+            continue;
+        }
+
+        let rest = &text[i + 1..];
+        let ticker: String = rest.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+        let ends_cleanly = !rest[ticker.len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if !(1..=6).contains(&ticker.len()) || !ends_cleanly {
+            continue;
+        }
+
+        let ticker = ticker.to_uppercase();
+        if !tags.contains(&ticker) {
+            tags.push(ticker);
+        }
+    }
+
+    tags
+}
+
+/// Save the hashtags and cashtags in a content item's text, for `get_trending_tags`
+pub fn record_tags(conn: &Connection, content_id: &str, text: &str) -> Result<(), rusqlite::Error> {
+    let hashtags = extract_hashtags(text).into_iter().map(|tag| (tag, tag_kind::HASHTAG));
+    let cashtags = extract_cashtags(text).into_iter().map(|tag| (tag, tag_kind::CASHTAG));
+    for (tag, kind) in hashtags.chain(cashtags) {
+        conn.execute(
+            "INSERT OR IGNORE INTO content_tags (content_id, tag, kind) VALUES (?1, ?2, ?3)",
+            params![content_id, tag, kind],
+        )?;
+    }
+    Ok(())
+}
+
+/// Hashtags and cashtags used by the most distinct content items in the last `days`, with
+/// the count for the window just before for comparison.
+pub fn get_trending_tags(days: i64, limit: i64) -> Result<Vec<TrendingTag>, TrendrError> {
+    let days = days.max(1);
+    let window = format!("-{} days", days);
+    let previous_window = format!("-{} days", days * 2);
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT t.tag, t.kind,
+                      COUNT(CASE WHEN COALESCE(datetime(c.published_at), datetime(c.collected_at)) > datetime('now', ?1) THEN 1 END) AS content_count,
+                      COUNT(CASE WHEN COALESCE(datetime(c.published_at), datetime(c.collected_at)) <= datetime('now', ?1) THEN 1 END) AS previous_count
+               FROM content_tags t
+               JOIN content c ON c.id = t.content_id
+               WHERE c.status != ?4
+               AND COALESCE(datetime(c.published_at), datetime(c.collected_at)) > datetime('now', ?2)
+               GROUP BY t.kind, t.tag
+               HAVING content_count > 0
+               ORDER BY content_count DESC, t.tag ASC
+               LIMIT ?3"#,
+        )?;
+
+        let rows = stmt.query_map(params![window, previous_window, limit, status::DELETED], |row| {
+            Ok(TrendingTag {
+                tag: row.get(0)?,
+                kind: row.get(1)?,
+                content_count: row.get(2)?,
+                previous_count: row.get(3)?,
+            })
+        })?;
+
+        rows.collect()
+    })
+}
+
+/// Turn hashtags used by at least `min_mentions` content items in the last `window_hours`
+/// into topics seeded with the hashtag as their keyword. At most `max_per_day` topics are
+/// created in any 24 hours. Hashtags that already name a topic or one of its keywords are
+/// skipped. Created topics record the hashtag in `auto_created_from` for later review.
+pub fn auto_create_topics(config: &AutoCreateTopicsSettings) -> Result<Vec<AutoCreatedTopic>, TrendrError> {
+    if !config.enabled || config.max_per_day == 0 {
+        return Ok(Vec::new());
+    }
+
+    with_db(|conn| {
+        let created_today: i64 = conn.query_row(
+            r#"SELECT COUNT(*) FROM topics
+               WHERE auto_created_from IS NOT NULL AND created_at > datetime('now', '-1 day')"#,
+            [],
+            |row| row.get(0),
+        )?;
+        let remaining = (config.max_per_day as i64 - created_today).max(0) as usize;
+        if remaining == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut counts: HashMap<String, i64> = HashMap::new();
+        {
+            let mut stmt = conn.prepare(
+                r#"SELECT text_content FROM content
+                   WHERE text_content LIKE '%#%' AND status != ?1
+                   AND COALESCE(datetime(published_at), datetime(collected_at)) > datetime('now', ?2)"#,
+            )?;
+            let rows = stmt.query_map(
+                params![status::DELETED, format!("-{} hours", config.window_hours)],
+                |row| row.get::<_, String>(0),
+            )?;
+            for text in rows {
+                for tag in extract_hashtags(&text?) {
+                    *counts.entry(tag).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut known = HashSet::new();
+        {
+            let mut stmt = conn.prepare("SELECT id, slug, keywords FROM topics")?;
+            let rows = stmt.query_map([], |row| {
+                let id: String = row.get(0)?;
+                let slug: String = row.get(1)?;
+                let keywords_json: Option<String> = row.get(2)?;
+                Ok((slug, parse_keywords(keywords_json.as_deref(), &id)))
+            })?;
+            for row in rows {
+                let (slug, keywords) = row?;
+                known.insert(slug);
+                known.extend(keywords.iter().map(|k| k.term().to_lowercase()));
+            }
+        }
+
+        let mut candidates: Vec<(String, i64)> = counts
+            .into_iter()
+            .filter(|(tag, count)| {
+                *count >= config.min_mentions as i64 && !known.contains(tag) && !known.contains(&slugify(tag))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let tx = conn.unchecked_transaction()?;
+        let mut created = Vec::new();
+        for (hashtag, mentions) in candidates.into_iter().take(remaining) {
+            let topic_id = uuid::Uuid::new_v4().to_string();
+            let name = format!("#{}", hashtag);
+            let keywords = normalize_keywords(&[Keyword::Plain(hashtag.clone())], false).unwrap_or_default();
+            let keywords_json = serde_json::to_string(&keywords).unwrap_or_default();
+
+            tx.execute(
+                r#"INSERT INTO topics (id, name, slug, keywords, auto_created_from)
+                   VALUES (?1, ?2, ?3, ?4, ?5)"#,
+                params![topic_id, name, slugify(&hashtag), keywords_json, hashtag],
+            )?;
+
+            created.push(AutoCreatedTopic {
+                topic_id,
+                name,
+                hashtag,
+                mentions,
+            });
+        }
+        tx.commit()?;
+
+        Ok(created)
+    })
+}
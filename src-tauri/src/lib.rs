@@ -1,12 +1,40 @@
+mod paths;
+mod log_stream;
 mod database;
+mod error;
+mod content;
+mod urls;
 mod settings;
+mod rate_limit;
+mod watermarks;
+mod progress;
 mod reddit;
 mod x;
 mod youtube;
 mod topics;
+mod preprocess;
+mod refresh;
+mod scheduler;
+mod trends;
+mod forecast;
+mod authenticity;
+mod snapshots;
+mod alerts;
+mod creators;
+mod sentiment;
+mod entities;
+mod hashtags;
+mod export;
+mod backup;
+mod retention;
 mod commands;
 
 use tauri::Manager;
+use tauri_plugin_log::{Target, TargetKind};
+
+fn persistent_level(metadata: &log::Metadata) -> bool {
+    metadata.level() <= log::Level::Info
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -17,13 +45,25 @@ pub fn run() {
             let app_handle = app.handle().clone();
             database::init_database(&app_handle)?;
 
+            // File (and console in debug builds) stay at info; the frontend stream has its
+            // own adjustable level, so the global filter lets debug records through to it.
+            let mut targets = vec![
+                Target::new(TargetKind::LogDir { file_name: None }).filter(persistent_level),
+                Target::new(TargetKind::Dispatch(log_stream::dispatch())),
+            ];
             if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
+                targets.push(Target::new(TargetKind::Stdout).filter(persistent_level));
             }
+
+            log_stream::init(app.handle());
+            app.handle().plugin(
+                tauri_plugin_log::Builder::default()
+                    .level(log::LevelFilter::Debug)
+                    .targets(targets)
+                    .build(),
+            )?;
+
+            scheduler::spawn(app.handle().clone())?;
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -33,23 +73,85 @@ pub fn run() {
             commands::test_reddit_connection,
             commands::test_x_connection,
             commands::test_youtube_connection,
+            // Logging commands
+            commands::get_log_path,
+            commands::set_log_stream_level,
             // Collection commands
             commands::run_collection,
             commands::run_x_collection,
             commands::run_youtube_collection,
+            commands::run_all_collection,
             commands::get_collection_status,
+            commands::cancel_collection,
+            commands::refresh_engagement,
+            commands::get_scheduler_status,
+            commands::start_scheduler,
+            commands::stop_scheduler,
+            commands::reset_cursor,
             // Topics commands
             commands::get_topics,
             commands::get_topic_details,
             commands::search_topics,
+            commands::get_similar_topics,
+            commands::suggest_hierarchy_links,
+            commands::get_cooccurrences,
+            commands::set_topic_parent,
+            commands::create_topic,
+            commands::update_topic,
+            commands::delete_topic,
+            commands::reextract_topics,
+            commands::preview_topic_slug,
+            commands::analyze_text,
+            commands::get_active_topics,
+            commands::get_topics_stats,
+            commands::get_topic_creators,
+            commands::get_topic_platform_breakdown,
+            commands::get_topic_confidence_distribution,
+            commands::get_topic_timeseries,
+            commands::get_all_keyword_stats,
+            commands::suggest_keywords,
+            commands::compute_trend_scores,
+            commands::get_trend_scores,
+            commands::simulate_trend_detection,
+            commands::get_topic_forecast,
+            commands::get_trend_authenticity,
             // Content commands
             commands::get_content,
+            commands::search_content,
+            commands::export_content,
+            commands::backup_database,
+            commands::export_backup,
+            commands::import_backup,
+            commands::prune_content,
             commands::get_content_by_topic,
+            commands::get_top_content,
+            commands::get_content_types,
+            commands::get_content_date_range,
+            commands::tag_untagged_content,
+            commands::tag_content_entities,
+            commands::get_trending_entities,
+            commands::get_trending_tags,
+            // Creator commands
+            commands::merge_creators,
+            commands::get_creator_group,
+            commands::get_creators,
+            commands::get_creator_details,
             // Dashboard commands
             commands::get_dashboard_stats,
+            commands::get_sentiment_shifts,
+            commands::pin_snapshot,
+            commands::list_snapshots,
+            commands::compare_snapshot,
+            commands::delete_snapshot,
             // Alerts commands
             commands::get_alerts,
             commands::mark_alert_read,
+            commands::mark_all_alerts_read,
+            commands::get_unread_alert_count,
+            commands::delete_alert,
+            commands::clear_read_alerts,
+            commands::export_alerts_rss,
+            commands::cleanup_alerts,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,71 @@
+use crate::error::TrendrError;
+use log::LevelFilter;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_log::fern;
+
+/// Event each forwarded log record is emitted on
+pub const LOG_EVENT: &str = "log://line";
+
+const LEVELS: [LevelFilter; 6] = [
+    LevelFilter::Off,
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+static STREAM_LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Info as usize);
+
+#[derive(Debug, Clone, Serialize)]
+struct LogLine {
+    level: String,
+    target: String,
+    message: String,
+    timestamp: String,
+}
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Set the most verbose level forwarded to the frontend ("off", "error", "warn", "info",
+/// "debug" or "trace"). File and console logging are unaffected.
+pub fn set_level(level: &str) -> Result<(), TrendrError> {
+    let filter: LevelFilter = level
+        .parse()
+        .map_err(|_| TrendrError::Other(format!("Unknown log level: {}", level)))?;
+    STREAM_LEVEL.store(filter as usize, Ordering::Relaxed);
+    Ok(())
+}
+
+fn stream_level() -> LevelFilter {
+    LEVELS
+        .get(STREAM_LEVEL.load(Ordering::Relaxed))
+        .copied()
+        .unwrap_or(LevelFilter::Info)
+}
+
+/// Log target that emits this crate's records to the frontend as `log://line` events.
+/// Records from dependencies (HTTP clients, Tauri itself) are not forwarded.
+pub fn dispatch() -> fern::Dispatch {
+    fern::Dispatch::new()
+        .filter(|metadata| {
+            metadata.target().starts_with(env!("CARGO_CRATE_NAME")) && metadata.level() <= stream_level()
+        })
+        .chain(fern::Output::call(|record| {
+            if let Some(app) = APP.get() {
+                let line = LogLine {
+                    level: record.level().to_string(),
+                    target: record.target().to_string(),
+                    message: record.args().to_string(),
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                };
+                let _ = app.emit(LOG_EVENT, line);
+            }
+        }))
+}
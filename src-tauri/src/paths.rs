@@ -0,0 +1,60 @@
+use directories::ProjectDirs;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+static FALLBACK_WARNING: Once = Once::new();
+
+/// Directory holding the database and other app data.
+pub fn data_dir() -> PathBuf {
+    resolve(ProjectDirs::from("com", "trendr", "Trendr").as_ref(), ProjectDirs::data_dir)
+}
+
+/// Directory holding settings.json.
+pub fn config_dir() -> PathBuf {
+    resolve(ProjectDirs::from("com", "trendr", "Trendr").as_ref(), ProjectDirs::config_dir)
+}
+
+fn resolve(project_dirs: Option<&ProjectDirs>, pick: fn(&ProjectDirs) -> &Path) -> PathBuf {
+    let dir = match project_dirs {
+        Some(dirs) => pick(dirs).to_path_buf(),
+        None => fallback_dir(),
+    };
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Used for both data and config when the platform has no standard app directories,
+/// so the database and settings always end up side by side. Prefers a folder next to
+/// the executable and falls back to the system temp dir.
+fn fallback_dir() -> PathBuf {
+    let base = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_else(std::env::temp_dir);
+    let dir = base.join("trendr-data");
+
+    FALLBACK_WARNING.call_once(|| {
+        log::warn!(
+            "Standard app directories unavailable; storing data and settings in {:?}",
+            dir
+        );
+    });
+
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_project_dirs_put_data_and_config_together() {
+        let data = resolve(None, ProjectDirs::data_dir);
+        let config = resolve(None, ProjectDirs::config_dir);
+
+        assert_eq!(data, config);
+        assert!(data.ends_with("trendr-data"));
+        assert!(data.is_absolute());
+        assert!(data.is_dir());
+    }
+}
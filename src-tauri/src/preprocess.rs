@@ -0,0 +1,134 @@
+use crate::settings::TextCleaners;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static URL: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:https?://|www\.)\S+").unwrap());
+static MENTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"\B@\w+").unwrap());
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+static MD_IMAGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+static MD_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+static MD_LINE_PREFIX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*(?:#{1,6}\s+|>+\s?|[-*+]\s+)").unwrap());
+static MD_EMPHASIS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*{1,3}|~~|`+|\^").unwrap());
+
+/// Run the enabled cleaners over text before topic extraction. Stored text is not
+/// changed; this only affects what keywords are matched against. Markdown and HTML
+/// are stripped before URLs so link targets are exposed and removed as well.
+pub fn clean(text: &str, cleaners: &TextCleaners) -> String {
+    let mut cleaned = text.to_string();
+
+    if cleaners.strip_html {
+        cleaned = strip_html(&cleaned);
+    }
+    if cleaners.strip_markdown {
+        cleaned = strip_markdown(&cleaned);
+    }
+    if cleaners.strip_urls {
+        cleaned = URL.replace_all(&cleaned, " ").into_owned();
+    }
+    if cleaners.strip_mentions {
+        cleaned = MENTION.replace_all(&cleaned, " ").into_owned();
+    }
+
+    cleaned
+}
+
+/// Keep link and image text, drop their targets, heading/quote/list markers and
+/// emphasis characters. Underscores are left alone since they appear inside words.
+fn strip_markdown(text: &str) -> String {
+    let text = MD_IMAGE.replace_all(text, "$1");
+    let text = MD_LINK.replace_all(&text, "$1");
+    let text = MD_LINE_PREFIX.replace_all(&text, "");
+    let text = MD_EMPHASIS.replace_all(&text, "");
+    decode_entities(&text)
+}
+
+/// Drop tags and decode the common entities
+fn strip_html(text: &str) -> String {
+    let text = HTML_TAG.replace_all(text, " ");
+    decode_entities(&text)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Clean with only the given cleaners on, collapsing the spaces they leave behind
+    fn cleaned(text: &str, cleaners: &TextCleaners) -> String {
+        clean(text, cleaners).split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn nothing_enabled_leaves_text_alone() {
+        let text = "**Bold** <b>tag</b> @user https://t.co/abc";
+        assert_eq!(clean(text, &TextCleaners::default()), text);
+    }
+
+    #[test]
+    fn markdown_keeps_link_text_and_drops_syntax() {
+        let cleaners = TextCleaners {
+            strip_markdown: true,
+            ..TextCleaners::default()
+        };
+        assert_eq!(
+            cleaned("## Rust 2024\n> **Big** news, see [the post](https://blog.rust-lang.org) ![logo](logo.png)", &cleaners),
+            "Rust 2024 Big news, see the post logo"
+        );
+        assert_eq!(cleaned("- `cargo` &amp; snake_case", &cleaners), "cargo & snake_case");
+    }
+
+    #[test]
+    fn html_drops_tags_and_decodes_entities() {
+        let cleaners = TextCleaners {
+            strip_html: true,
+            ..TextCleaners::default()
+        };
+        assert_eq!(
+            cleaned("<p>Fish&nbsp;&amp;&nbsp;chips</p><br/>it&#39;s <a href=\"x\">good</a>", &cleaners),
+            "Fish & chips it's good"
+        );
+    }
+
+    #[test]
+    fn mentions_are_removed_but_emails_are_not() {
+        let cleaners = TextCleaners {
+            strip_mentions: true,
+            ..TextCleaners::default()
+        };
+        assert_eq!(
+            cleaned("@alice @bob_2 thanks, mail me at me@example.com", &cleaners),
+            "thanks, mail me at me@example.com"
+        );
+    }
+
+    #[test]
+    fn urls_are_removed() {
+        let cleaners = TextCleaners {
+            strip_urls: true,
+            ..TextCleaners::default()
+        };
+        assert_eq!(
+            cleaned("Read https://t.co/AbC123 or www.example.com/page?x=1 HTTP://SHOUT.COM now", &cleaners),
+            "Read or now"
+        );
+    }
+
+    #[test]
+    fn markdown_link_targets_go_along_with_urls() {
+        let cleaners = TextCleaners {
+            strip_markdown: true,
+            strip_urls: true,
+            ..TextCleaners::default()
+        };
+        assert_eq!(cleaned("[Docs](https://docs.rs) and https://crates.io", &cleaners), "Docs and");
+    }
+}
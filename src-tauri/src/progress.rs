@@ -0,0 +1,124 @@
+use crate::database::with_db;
+use crate::error::TrendrError;
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashSet;
+
+/// The slice of `sources` this run should cover when at most `max_per_run` are collected
+/// per run (0 for no limit), starting from the platform's persisted rotation offset and
+/// wrapping around the end of the list.
+pub fn window(platform: &str, sources: &[String], max_per_run: u32) -> Result<Vec<String>, TrendrError> {
+    let max = max_per_run as usize;
+    if max == 0 || sources.len() <= max {
+        return Ok(sources.to_vec());
+    }
+
+    let offset = rotation_offset(platform)? % sources.len();
+    Ok(sources.iter().cycle().skip(offset).take(max).cloned().collect())
+}
+
+/// Where the platform's next capped run starts in its source list.
+pub fn rotation_offset(platform: &str) -> Result<usize, TrendrError> {
+    let offset: Option<i64> = with_db(|conn| {
+        conn.query_row(
+            "SELECT next_offset FROM collection_rotation WHERE platform = ?1",
+            params![platform],
+            |row| row.get(0),
+        )
+        .optional()
+    })?;
+    Ok(offset.unwrap_or(0).max(0) as usize)
+}
+
+/// Sources not yet completed in the current collection cycle, in configured order.
+/// Progress older than `cycle_minutes` belongs to an earlier cycle and is discarded, as is
+/// progress that already covers every source, so a fresh cycle starts from the top.
+pub fn pending(platform: &str, sources: &[String], cycle_minutes: u32) -> Result<Vec<String>, TrendrError> {
+    with_db(|conn| {
+        conn.execute(
+            "DELETE FROM collection_progress WHERE platform = ?1 AND completed_at < datetime('now', ?2)",
+            params![platform, format!("-{} minutes", cycle_minutes.max(1))],
+        )
+    })?;
+    let done = completed(platform)?;
+
+    let remaining: Vec<String> = sources.iter().filter(|s| !done.contains(*s)).cloned().collect();
+    if remaining.is_empty() {
+        clear(platform)?;
+        return Ok(sources.to_vec());
+    }
+
+    if remaining.len() < sources.len() {
+        log::info!(
+            "Resuming {} collection: {} of {} sources already completed this cycle",
+            platform,
+            sources.len() - remaining.len(),
+            sources.len()
+        );
+    }
+    Ok(remaining)
+}
+
+/// Record that a source finished within the current cycle. Failures are logged, since
+/// losing progress only means the source is fetched again.
+pub fn mark_done(platform: &str, source: &str) {
+    let result = with_db(|conn| {
+        conn.execute(
+            r#"INSERT INTO collection_progress (platform, source, completed_at)
+               VALUES (?1, ?2, CURRENT_TIMESTAMP)
+               ON CONFLICT(platform, source) DO UPDATE SET completed_at = CURRENT_TIMESTAMP"#,
+            params![platform, source],
+        )?;
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        log::warn!("Failed to record progress for {} '{}': {}", platform, source, e);
+    }
+}
+
+/// Clear progress once every source in this run's `window` has completed, and move the
+/// rotation on to the next window of `all_sources`. Returns whether the cycle finished;
+/// sources skipped or failed this run stay pending for the next attempt.
+pub fn finish_cycle(platform: &str, window: &[String], all_sources: &[String]) -> Result<bool, TrendrError> {
+    let done = completed(platform)?;
+
+    if window.iter().any(|source| !done.contains(source)) {
+        return Ok(false);
+    }
+
+    clear(platform)?;
+    if window.len() < all_sources.len() {
+        let next_offset = (rotation_offset(platform)? + window.len()) % all_sources.len();
+        with_db(|conn| {
+            conn.execute(
+                r#"INSERT INTO collection_rotation (platform, next_offset, updated_at)
+                   VALUES (?1, ?2, CURRENT_TIMESTAMP)
+                   ON CONFLICT(platform) DO UPDATE SET
+                   next_offset = excluded.next_offset, updated_at = CURRENT_TIMESTAMP"#,
+                params![platform, next_offset as i64],
+            )?;
+            Ok(())
+        })?;
+    }
+    Ok(true)
+}
+
+fn completed(platform: &str) -> Result<HashSet<String>, TrendrError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT source FROM collection_progress WHERE platform = ?1")?;
+        let rows = stmt.query_map(params![platform], |row| row.get(0))?;
+
+        let mut done = HashSet::new();
+        for row in rows {
+            done.insert(row?);
+        }
+        Ok(done)
+    })
+}
+
+fn clear(platform: &str) -> Result<(), TrendrError> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM collection_progress WHERE platform = ?1", params![platform])?;
+        Ok(())
+    })
+}
@@ -0,0 +1,268 @@
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+/// Retries after a transient failure, on top of the first attempt
+const TRANSIENT_RETRIES: u32 = 3;
+
+/// Delay before the first retry; it doubles for each one after
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+
+/// Longest a server-sent Retry-After is honored for before giving up on the request
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Spaces requests evenly so a burst of concurrent callers never exceeds the configured
+/// per-minute rate. Cloning shares the same schedule.
+#[derive(Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Arc<Mutex<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(requests: u32) -> Self {
+        let requests = requests.max(1);
+        RateLimiter {
+            interval: Duration::from_secs(60) / requests,
+            next_slot: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Wait until the next request slot is free and claim it
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// A failed API request, keeping rate limiting apart from other failures so a collection
+/// loop can stop instead of sending every remaining query into the same limit.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The platform refused the request for rate or quota reasons. `retry_after` is how
+    /// long until the limit resets, when the platform says.
+    RateLimited { retry_after: Option<Duration> },
+    /// The platform rejected the access token or API key (HTTP 401, or a YouTube 403
+    /// that isn't about quota)
+    Unauthorized,
+    /// A network error or server-side (5xx) failure that may succeed if retried.
+    /// `retry_after` is set when the server said how long to wait.
+    Transient { message: String, retry_after: Option<Duration> },
+    Failed(String),
+}
+
+impl FetchError {
+    /// A request that never got a response
+    pub fn network(error: reqwest::Error) -> Self {
+        FetchError::Transient {
+            message: format!("Request failed: {}", error),
+            retry_after: None,
+        }
+    }
+
+    /// A 5xx response, keeping any Retry-After it carried
+    pub fn server(response: &reqwest::Response, message: String) -> Self {
+        FetchError::Transient {
+            message,
+            retry_after: seconds_header(response.headers(), "retry-after"),
+        }
+    }
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::RateLimited { retry_after: Some(wait) } => {
+                write!(f, "Rate limited (resets in {}s)", wait.as_secs())
+            }
+            FetchError::RateLimited { retry_after: None } => write!(f, "Rate limited"),
+            FetchError::Unauthorized => write!(f, "Unauthorized"),
+            FetchError::Transient { message, .. } => write!(f, "{}", message),
+            FetchError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for FetchError {
+    fn from(message: String) -> Self {
+        FetchError::Failed(message)
+    }
+}
+
+/// Run `fetch`, retrying transient failures up to 3 times with jittered exponential
+/// backoff (about 0.5s, 1s, 2s), or after the server's Retry-After when it sent one.
+/// Rate limits, rejected credentials and other failures are returned right away for
+/// the caller to handle.
+pub async fn retry_with_backoff<T, F, Fut>(mut fetch: F) -> Result<T, FetchError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match fetch().await {
+            Err(FetchError::Transient { message, retry_after })
+                if attempt < TRANSIENT_RETRIES && !retry_after.is_some_and(|wait| wait > MAX_RETRY_AFTER) =>
+            {
+                let delay = retry_after.unwrap_or_else(|| backoff_delay(attempt));
+                log::info!("{}; retrying in {}ms", message, delay.as_millis());
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Exponential delay for a retry attempt plus up to 50% jitter, so concurrent fetches
+/// that failed together don't all retry at the same moment
+fn backoff_delay(attempt: u32) -> Duration {
+    let base = BACKOFF_BASE * 2u32.pow(attempt);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    base + base.mul_f64(f64::from(nanos % 1000) / 2000.0)
+}
+
+/// Sleep until a rate limit resets when that is known and no longer than `max_wait`.
+/// Returns whether it waited, i.e. whether retrying makes sense.
+pub async fn wait_for_reset(retry_after: Option<Duration>, max_wait: Duration) -> bool {
+    match retry_after {
+        Some(wait) if wait <= max_wait => {
+            log::info!("Rate limited, waiting {}s for the limit to reset", wait.as_secs());
+            tokio::time::sleep(wait).await;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Parse a header holding a number of seconds (Retry-After, Reddit's x-ratelimit-reset)
+pub fn seconds_header(headers: &reqwest::header::HeaderMap, name: &str) -> Option<Duration> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(|secs| Duration::from_secs(secs.ceil() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A transient failure whose Retry-After keeps the retry delay short
+    fn transient(attempt: u32) -> FetchError {
+        FetchError::Transient {
+            message: format!("attempt {} failed", attempt),
+            retry_after: Some(Duration::from_millis(1)),
+        }
+    }
+
+    fn run<T>(fetch: impl Future<Output = Result<T, FetchError>>) -> Result<T, FetchError> {
+        tokio::runtime::Runtime::new().unwrap().block_on(fetch)
+    }
+
+    #[test]
+    fn transient_failures_are_retried_until_one_succeeds() {
+        let mut attempts = 0;
+        let result = run(retry_with_backoff(|| {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt < 3 {
+                    Err(transient(attempt))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        }));
+
+        assert!(matches!(result, Ok(3)));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn rejected_credentials_are_not_retried() {
+        let mut attempts = 0;
+        let result: Result<(), _> = run(retry_with_backoff(|| {
+            attempts += 1;
+            async { Err(FetchError::Unauthorized) }
+        }));
+
+        assert!(matches!(result, Err(FetchError::Unauthorized)));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn the_last_failure_is_returned_once_retries_run_out() {
+        let mut attempts = 0;
+        let result: Result<(), _> = run(retry_with_backoff(|| {
+            attempts += 1;
+            let attempt = attempts;
+            async move { Err(transient(attempt)) }
+        }));
+
+        assert_eq!(attempts, TRANSIENT_RETRIES + 1);
+        assert!(matches!(result, Err(FetchError::Transient { message, .. }) if message == "attempt 4 failed"));
+    }
+
+    #[test]
+    fn a_retry_after_beyond_the_cap_is_not_waited_out() {
+        let mut attempts = 0;
+        let result: Result<(), _> = run(retry_with_backoff(|| {
+            attempts += 1;
+            async {
+                Err(FetchError::Transient {
+                    message: "overloaded".to_string(),
+                    retry_after: Some(MAX_RETRY_AFTER + Duration::from_secs(1)),
+                })
+            }
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn seconds_headers_round_up_and_reject_nonsense() {
+        let headers = headers(&[
+            ("retry-after", "120"),
+            ("x-ratelimit-reset", " 1.2 "),
+            ("negative", "-5"),
+            ("date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+        ]);
+
+        assert_eq!(seconds_header(&headers, "retry-after"), Some(Duration::from_secs(120)));
+        assert_eq!(seconds_header(&headers, "x-ratelimit-reset"), Some(Duration::from_secs(2)));
+        assert_eq!(seconds_header(&headers, "negative"), None);
+        assert_eq!(seconds_header(&headers, "date"), None);
+        assert_eq!(seconds_header(&headers, "missing"), None);
+    }
+
+    #[test]
+    fn waits_for_a_reset_only_when_it_is_known_and_soon_enough() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let max_wait = Duration::from_secs(1);
+
+        assert!(runtime.block_on(wait_for_reset(Some(Duration::from_millis(1)), max_wait)));
+        assert!(!runtime.block_on(wait_for_reset(Some(Duration::from_secs(2)), max_wait)));
+        assert!(!runtime.block_on(wait_for_reset(None, max_wait)));
+    }
+}
@@ -1,15 +1,97 @@
-use crate::settings::RedditCredentials;
-use crate::database::with_db;
-use crate::topics::extract_topics;
+use crate::settings::{AppSettings, RedditCredentials, RedditFetchSettings};
+use crate::content::{
+    cap_items, drop_untagged, truncate_body, CollectionProgress, ContentType, ItemOutcome, ItemStatus,
+    Processed, UNTAGGED_REASON,
+};
+use crate::database::{with_db, with_db_tx};
+use crate::error::TrendrError;
+use crate::hashtags;
+use crate::progress;
+use crate::rate_limit::{self, FetchError, RateLimiter};
+use crate::sentiment;
+use crate::topics::{self, extract_item_topics, ItemText, TopicData};
+use crate::urls;
+use crate::watermarks;
 use serde::{Deserialize, Serialize};
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// Subreddit listings Reddit serves. Only "new" is ordered newest-first, which is what
+/// the only-newer-content cutoff relies on.
+const LISTING_SORTS: [&str; 5] = ["hot", "new", "top", "rising", "controversial"];
+
+/// Time windows accepted by the listings that take one
+const LISTING_TIMES: [&str; 6] = ["hour", "day", "week", "month", "year", "all"];
+
+/// Most posts Reddit returns in one listing page
+const LISTING_PAGE_SIZE: u32 = 100;
+
+/// Refresh a cached access token this long before Reddit says it expires
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Which subreddit listing a run reads, resolved from `redditFetch.sort` / `.time`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Listing {
+    sort: &'static str,
+    /// Only set for the "top" and "controversial" listings
+    time: Option<&'static str>,
+}
+
+impl Listing {
+    /// Falls back to hot / day, with a warning, for values Reddit doesn't accept
+    fn from_settings(fetch: &RedditFetchSettings) -> Listing {
+        let sort = LISTING_SORTS
+            .into_iter()
+            .find(|sort| sort.eq_ignore_ascii_case(fetch.sort.trim()))
+            .unwrap_or_else(|| {
+                log::warn!("Unknown Reddit listing sort '{}', using hot", fetch.sort);
+                "hot"
+            });
+
+        let time = matches!(sort, "top" | "controversial").then(|| {
+            LISTING_TIMES
+                .into_iter()
+                .find(|time| time.eq_ignore_ascii_case(fetch.time.trim()))
+                .unwrap_or_else(|| {
+                    log::warn!("Unknown Reddit listing time window '{}', using day", fetch.time);
+                    "day"
+                })
+        });
+
+        Listing { sort, time }
+    }
+
+    fn url(&self, subreddit: &str, limit: u32, after: Option<&str>) -> String {
+        let mut url = format!("https://oauth.reddit.com/r/{}/{}?limit={}", subreddit, self.sort, limit);
+        if let Some(time) = self.time {
+            url.push_str(&format!("&t={}", time));
+        }
+        if let Some(after) = after {
+            url.push_str(&format!("&after={}", urlencoding::encode(after)));
+        }
+        url
+    }
+}
+
+struct CachedToken {
+    credentials: RedditCredentials,
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// Access token reused across runs until shortly before it expires
+static TOKEN_CACHE: once_cell::sync::Lazy<Mutex<Option<CachedToken>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
 
 #[derive(Debug, Deserialize)]
 struct TokenResponse {
     access_token: String,
     #[allow(dead_code)]
     token_type: String,
-    #[allow(dead_code)]
     expires_in: u64,
 }
 
@@ -21,6 +103,8 @@ struct RedditListing {
 #[derive(Debug, Deserialize)]
 struct RedditListingData {
     children: Vec<RedditChild>,
+    /// Cursor for the next page; None on the last one
+    after: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,32 +122,228 @@ struct RedditPostData {
     score: i64,
     num_comments: i64,
     created_utc: f64,
-    #[allow(dead_code)]
+    /// Path of the post on reddit.com, starting with /r/
     permalink: String,
+    /// Link target for link posts; points back at the post itself for self posts
+    url: Option<String>,
+    #[serde(default)]
+    is_self: bool,
+    thumbnail: Option<String>,
+    preview: Option<RedditPreview>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedditPreview {
+    images: Vec<RedditPreviewImage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedditPreviewImage {
+    source: RedditImageSource,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedditImageSource {
+    url: String,
+}
+
+/// One listing in a comments response: the first holds the post, the second its comments
+#[derive(Debug, Deserialize)]
+struct RedditCommentListing {
+    data: RedditCommentListingData,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedditCommentListingData {
+    children: Vec<RedditThing>,
+}
+
+/// A listing entry of any kind; only comments ("t1") are parsed further. The rest are the
+/// post itself and "more" stubs for comments that weren't loaded.
+#[derive(Debug, Deserialize)]
+struct RedditThing {
+    kind: String,
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedditCommentData {
+    id: String,
+    author: String,
+    body: String,
+    score: i64,
+    created_utc: f64,
+    /// Path of the comment on reddit.com, starting with /r/
+    permalink: String,
+    /// Pinned by a moderator, usually a rules reminder
+    #[serde(default)]
+    stickied: bool,
+}
+
+impl RedditCommentData {
+    /// Posts and comments are numbered separately, so comments are stored under their
+    /// "t1_" fullname to keep them from colliding with a post that has the same id
+    fn platform_id(&self) -> String {
+        format!("t1_{}", self.id)
+    }
+
+    fn comment_url(&self) -> String {
+        format!("https://reddit.com{}", self.permalink)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.author == "[deleted]" || self.body == "[deleted]" || self.body == "[removed]"
+    }
+}
+
+impl RedditPostData {
+    fn post_url(&self) -> String {
+        format!("https://reddit.com{}", self.permalink)
+    }
+
+    fn media_url(&self) -> Option<String> {
+        if self.is_self {
+            return None;
+        }
+        self.url.clone()
+    }
+
+    /// Prefer the full-size preview; fall back to the listing thumbnail, which is a
+    /// placeholder keyword like "self" or "default" when there is no image.
+    fn thumbnail_url(&self) -> Option<String> {
+        let preview = self
+            .preview
+            .as_ref()
+            .and_then(|p| p.images.first())
+            .map(|image| image.source.url.replace("&amp;", "&"));
+
+        preview.or_else(|| {
+            self.thumbnail
+                .clone()
+                .filter(|t| t.starts_with("http"))
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct CollectionResult {
+    /// Posts stored, plus comments when comment collection is on
     pub posts_collected: u32,
     pub topics_extracted: u32,
+    pub outcomes: Vec<ItemOutcome>,
+    pub rate_limited: bool,
+    pub skipped_sources: Vec<String>,
+    /// Whether the run was cancelled before every source was collected
+    pub cancelled: bool,
 }
 
-pub async fn test_connection(credentials: &RedditCredentials) -> Result<bool, String> {
+pub async fn test_connection(credentials: &RedditCredentials) -> Result<bool, TrendrError> {
+    let client = reqwest::Client::new();
+    // App-only tokens have no account behind them, so check a public listing instead
+    let check_url = if credentials.is_app_only() {
+        "https://oauth.reddit.com/r/all/hot?limit=1"
+    } else {
+        "https://oauth.reddit.com/api/v1/me"
+    };
+
     let token = get_access_token(credentials).await?;
+    let mut status = fetch_status(&client, &token, check_url).await?;
 
-    let client = reqwest::Client::new();
+    if status == 401 {
+        invalidate_token(&token);
+        let token = get_access_token(credentials).await?;
+        status = fetch_status(&client, &token, check_url).await?;
+    }
+
+    Ok(status.is_success())
+}
+
+async fn fetch_status(client: &reqwest::Client, token: &str, url: &str) -> Result<reqwest::StatusCode, TrendrError> {
     let response = client
-        .get("https://oauth.reddit.com/api/v1/me")
+        .get(url)
         .header("Authorization", format!("Bearer {}", token))
         .header("User-Agent", "Trendr/1.0.0")
         .send()
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
+
+    Ok(response.status())
+}
+
+/// Access token for these credentials, reusing the cached one while it is still valid.
+async fn get_access_token(credentials: &RedditCredentials) -> Result<String, TrendrError> {
+    access_token_with(credentials, || request_access_token(credentials)).await
+}
+
+/// `get_access_token` with the token request passed in, so it only runs on a cache miss
+async fn access_token_with<R, F>(credentials: &RedditCredentials, request: R) -> Result<String, TrendrError>
+where
+    R: FnOnce() -> F,
+    F: Future<Output = Result<TokenResponse, TrendrError>>,
+{
+    if let Some(token) = cached_token(credentials) {
+        return Ok(token);
+    }
+
+    let token_response = request().await?;
+    let lifetime = Duration::from_secs(token_response.expires_in).saturating_sub(TOKEN_EXPIRY_MARGIN);
+    if let Ok(mut cache) = TOKEN_CACHE.lock() {
+        *cache = Some(CachedToken {
+            credentials: credentials.clone(),
+            access_token: token_response.access_token.clone(),
+            expires_at: Instant::now() + lifetime,
+        });
+    }
+
+    Ok(token_response.access_token)
+}
 
-    Ok(response.status().is_success())
+fn cached_token(credentials: &RedditCredentials) -> Option<String> {
+    let cache = TOKEN_CACHE.lock().ok()?;
+    cache
+        .as_ref()
+        .filter(|cached| cached.credentials == *credentials && Instant::now() < cached.expires_at)
+        .map(|cached| cached.access_token.clone())
 }
 
-async fn get_access_token(credentials: &RedditCredentials) -> Result<String, String> {
+/// Drop the cached token if it is still `stale`, so the next lookup requests a new one.
+/// A token another task already replaced is left alone.
+fn invalidate_token(stale: &str) {
+    if let Ok(mut cache) = TOKEN_CACHE.lock() {
+        if cache.as_ref().is_some_and(|cached| cached.access_token == stale) {
+            *cache = None;
+        }
+    }
+}
+
+/// Form body for the token request. With a Reddit account configured this is the
+/// password grant; without one, an application-only grant: client_credentials for apps
+/// with a secret, installed_client for installed apps, which have none.
+fn token_request_body(credentials: &RedditCredentials) -> Result<String, TrendrError> {
+    if credentials.is_app_only() {
+        return Ok(if credentials.client_secret.is_empty() {
+            format!(
+                "grant_type={}&device_id=DO_NOT_TRACK_THIS_DEVICE",
+                urlencoding::encode("https://oauth.reddit.com/grants/installed_client")
+            )
+        } else {
+            "grant_type=client_credentials".to_string()
+        });
+    }
+
+    if credentials.username.trim().is_empty() || credentials.password.is_empty() {
+        return Err(TrendrError::NotConfigured(
+            "Set both a Reddit username and password, or leave both blank for app-only access".to_string(),
+        ));
+    }
+
+    Ok(format!(
+        "grant_type=password&username={}&password={}",
+        urlencoding::encode(&credentials.username),
+        urlencoding::encode(&credentials.password)
+    ))
+}
+
+async fn request_access_token(credentials: &RedditCredentials) -> Result<TokenResponse, TrendrError> {
     let client = reqwest::Client::new();
     let auth = base64::Engine::encode(
         &base64::engine::general_purpose::STANDARD,
@@ -75,72 +355,289 @@ async fn get_access_token(credentials: &RedditCredentials) -> Result<String, Str
         .header("Authorization", format!("Basic {}", auth))
         .header("Content-Type", "application/x-www-form-urlencoded")
         .header("User-Agent", "Trendr/1.0.0")
-        .body(format!(
-            "grant_type=password&username={}&password={}",
-            credentials.username, credentials.password
-        ))
+        .body(token_request_body(credentials)?)
         .send()
         .await
-        .map_err(|e| format!("Failed to get token: {}", e))?;
+        .map_err(|e| TrendrError::Network(format!("Failed to get token: {}", e)))?;
 
-    if !response.status().is_success() {
-        return Err(format!("Token request failed: {}", response.status()));
+    let status = response.status();
+    if !status.is_success() {
+        return Err(TrendrError::from_status(status, format!("Token request failed: {}", status)));
     }
 
     let token_response: TokenResponse = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse token response: {}", e))?;
+        .map_err(|e| TrendrError::Parse(format!("Failed to parse token response: {}", e)))?;
 
-    Ok(token_response.access_token)
+    Ok(token_response)
 }
 
 pub async fn collect(
     credentials: &RedditCredentials,
     subreddits: &[String],
-) -> Result<CollectionResult, String> {
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(CollectionProgress),
+) -> Result<CollectionResult, TrendrError> {
     let token = get_access_token(credentials).await?;
     let client = reqwest::Client::new();
 
+    // Fetches run concurrently up to the configured limit; results are processed one
+    // subreddit at a time, in order, so database writes stay serialized.
+    let permits = Arc::new(Semaphore::new(settings.reddit_fetch.concurrency.max(1) as usize));
+    let limiter = RateLimiter::per_minute(settings.reddit_fetch.requests_per_minute);
+    // Set once Reddit rate limits us past the wait bound; fetches not yet started are skipped
+    let halted = Arc::new(AtomicBool::new(false));
+    let max_wait = tokio::time::Duration::from_secs(settings.rate_limit_max_wait_secs);
+    let max_items = settings.max_items_per_query.reddit;
+    let listing = Listing::from_settings(&settings.reddit_fetch);
+    let fetches: Vec<_> = subreddits
+        .iter()
+        .map(|subreddit| {
+            let client = client.clone();
+            let credentials = credentials.clone();
+            let mut token = token.clone();
+            let subreddit = subreddit.clone();
+            let permits = Arc::clone(&permits);
+            let limiter = limiter.clone();
+            let halted = Arc::clone(&halted);
+            tokio::spawn(async move {
+                let _permit = permits
+                    .acquire_owned()
+                    .await
+                    .map_err(|e| FetchError::Failed(e.to_string()))?;
+                if halted.load(Ordering::Relaxed) {
+                    return Err(FetchError::RateLimited { retry_after: None });
+                }
+
+                limiter.acquire().await;
+                let mut fetched = fetch_subreddit_posts(&client, &token, &subreddit, listing, max_items, &limiter).await;
+                if matches!(fetched, Err(FetchError::Unauthorized)) {
+                    // The token was revoked or expired early; get a fresh one and retry once
+                    invalidate_token(&token);
+                    token = get_access_token(&credentials).await?;
+                    limiter.acquire().await;
+                    fetched = fetch_subreddit_posts(&client, &token, &subreddit, listing, max_items, &limiter).await;
+                }
+                if let Err(FetchError::RateLimited { retry_after }) = &fetched {
+                    if !halted.load(Ordering::Relaxed) && rate_limit::wait_for_reset(*retry_after, max_wait).await {
+                        limiter.acquire().await;
+                        fetched = fetch_subreddit_posts(&client, &token, &subreddit, listing, max_items, &limiter).await;
+                    }
+                }
+
+                if matches!(fetched, Err(FetchError::RateLimited { .. })) {
+                    halted.store(true, Ordering::Relaxed);
+                }
+                fetched
+            })
+        })
+        .collect();
+
+    // Loaded once for the whole run rather than once per post
+    let known_topics = topics::load_topics()?;
+
     let mut total_posts = 0u32;
     let mut total_topics = 0u32;
+    let mut outcomes = Vec::new();
+    let mut skipped_sources = Vec::new();
+    let mut total_untagged = 0u32;
+    let mut total_comments = 0u32;
+    let mut cancelled = false;
+    // Cleared when Reddit rate limits a comments request, leaving the budget for posts
+    let mut collect_comments = settings.reddit_fetch.collect_comments;
+
+    for (source_index, (subreddit, fetch)) in subreddits.iter().zip(fetches).enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            log::info!(
+                "Reddit collection cancelled, {} subreddits not collected",
+                subreddits.len() - source_index
+            );
+            // Fetches that haven't started yet are skipped
+            halted.store(true, Ordering::Relaxed);
+            cancelled = true;
+            break;
+        }
+
+        let fetched = fetch
+            .await
+            .unwrap_or_else(|e| Err(FetchError::Failed(e.to_string())));
+        let cutoff = watermarks::cutoff(settings, "reddit", subreddit, listing.sort == "new");
+        let mut newest_seen = cutoff;
 
-    for subreddit in subreddits {
-        match fetch_subreddit_posts(&client, &token, subreddit).await {
-            Ok(posts) => {
-                for post in posts {
-                    match process_post(&post).await {
-                        Ok(topics_found) => {
+        match fetched {
+            Ok(mut posts) => {
+                cap_items(&mut posts, max_items, "reddit", subreddit);
+                let post_count = posts.len();
+                for (index, post) in posts.into_iter().enumerate() {
+                    let created_at = chrono::DateTime::from_timestamp(post.created_utc as i64, 0);
+                    if let (Some(cutoff), Some(created_at)) = (cutoff, created_at) {
+                        if created_at <= cutoff {
+                            log::info!(
+                                "r/{}: reached previously collected posts, skipping {} older",
+                                subreddit,
+                                post_count - index
+                            );
+                            break;
+                        }
+                    }
+                    newest_seen = newest_seen.max(created_at);
+
+                    match process_post(&post, subreddit, &known_topics, settings).await {
+                        Ok(Processed::Stored(topics_found)) => {
                             total_posts += 1;
                             total_topics += topics_found;
+                            outcomes.push(ItemOutcome::stored("reddit", &post.id, topics_found));
+
+                            if collect_comments && post.num_comments > 0 {
+                                let limit = settings.reddit_fetch.comments_per_post.max(1);
+                                match fetch_top_comments(&client, credentials, &limiter, &post, limit).await {
+                                    Ok(comments) => {
+                                        for outcome in store_comments(&comments, subreddit, &known_topics, settings) {
+                                            match outcome.status {
+                                                ItemStatus::Stored => {
+                                                    total_posts += 1;
+                                                    total_comments += 1;
+                                                    total_topics += outcome.topics_found;
+                                                }
+                                                ItemStatus::Filtered => total_untagged += 1,
+                                                _ => {}
+                                            }
+                                            outcomes.push(outcome);
+                                        }
+                                    }
+                                    Err(FetchError::RateLimited { .. }) => {
+                                        log::warn!("Reddit rate limited comment fetches, skipping comments for the rest of this run");
+                                        collect_comments = false;
+                                    }
+                                    Err(e) => log::warn!("Failed to fetch comments on post {}: {}", post.id, e),
+                                }
+                            }
+                        }
+                        Ok(Processed::Duplicate) => outcomes.push(ItemOutcome::duplicate("reddit", &post.id)),
+                        Ok(Processed::Untagged) => {
+                            total_untagged += 1;
+                            outcomes.push(ItemOutcome::filtered("reddit", &post.id, UNTAGGED_REASON.to_string()));
                         }
                         Err(e) => {
                             log::warn!("Failed to process post {}: {}", post.id, e);
+                            outcomes.push(ItemOutcome::failed("reddit", &post.id, e.to_string()));
                         }
                     }
                 }
+                progress::mark_done("reddit", subreddit);
+            }
+            Err(FetchError::RateLimited { .. }) => skipped_sources.push(subreddit.clone()),
+            // Still rejected after a fresh token, so the rest would be too
+            Err(FetchError::Unauthorized) => {
+                halted.store(true, Ordering::Relaxed);
+                return Err(TrendrError::Auth(
+                    "Reddit rejected the credentials. Please check your client id and secret.".to_string(),
+                ));
             }
             Err(e) => {
                 log::error!("Failed to fetch r/{}: {}", subreddit, e);
             }
         }
 
-        // Rate limiting
-        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        if settings.only_newer_content {
+            if let Some(newest) = newest_seen.filter(|&newest| Some(newest) != cutoff) {
+                if let Err(e) = watermarks::advance("reddit", subreddit, newest) {
+                    log::warn!("Failed to record watermark for r/{}: {}", subreddit, e);
+                }
+            }
+        }
+
+        on_progress(CollectionProgress::new("reddit", source_index + 1, subreddits.len(), total_posts));
+    }
+
+    if total_comments > 0 {
+        log::info!("Stored {} Reddit comments", total_comments);
+    }
+    if total_untagged > 0 {
+        log::info!("Dropped {} Reddit posts and comments that matched no topics", total_untagged);
+    }
+
+    let rate_limited = !skipped_sources.is_empty();
+    if rate_limited {
+        log::warn!("Reddit rate limited, {} subreddits skipped", skipped_sources.len());
     }
 
     Ok(CollectionResult {
         posts_collected: total_posts,
         topics_extracted: total_topics,
+        outcomes,
+        rate_limited,
+        skipped_sources,
+        cancelled,
     })
 }
 
+/// Fetch up to `max_items` posts, following the listing's `after` cursor across pages.
+/// The caller has already taken a rate limiter slot for the first page. If a later page
+/// fails, the posts from earlier pages are kept.
 async fn fetch_subreddit_posts(
     client: &reqwest::Client,
     token: &str,
     subreddit: &str,
-) -> Result<Vec<RedditPostData>, String> {
-    let url = format!("https://oauth.reddit.com/r/{}/hot?limit=25", subreddit);
+    listing: Listing,
+    max_items: u32,
+    limiter: &RateLimiter,
+) -> Result<Vec<RedditPostData>, FetchError> {
+    let max_items = max_items.max(1) as usize;
+    let mut posts = Vec::new();
+    let mut after: Option<String> = None;
+
+    loop {
+        let page_size = (max_items - posts.len()).min(LISTING_PAGE_SIZE as usize) as u32;
+        let fetched = rate_limit::retry_with_backoff(|| {
+            fetch_listing_page(client, token, subreddit, listing, page_size, after.as_deref())
+        })
+        .await;
+        let page = match fetched {
+            Ok(page) => page,
+            Err(e) if !posts.is_empty() => {
+                log::warn!("r/{}: stopped paging after {} posts: {}", subreddit, posts.len(), e);
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+
+        after = next_page_cursor(&page, posts.len(), max_items);
+        posts.extend(page.children.into_iter().map(|c| c.data));
+        if after.is_none() {
+            break;
+        }
+        limiter.acquire().await;
+    }
+
+    Ok(posts)
+}
+
+/// The `after` cursor to fetch once `page` is added to the `collected` posts so far, or
+/// None to stop: on the last page, on an empty one, or once `max_items` are in hand.
+fn next_page_cursor(page: &RedditListingData, collected: usize, max_items: usize) -> Option<String> {
+    if page.children.is_empty() || collected + page.children.len() >= max_items {
+        return None;
+    }
+    page.after.clone()
+}
+
+fn parse_listing(body: &str) -> Result<RedditListingData, FetchError> {
+    let listing: RedditListing = serde_json::from_str(body).map_err(|e| e.to_string())?;
+    Ok(listing.data)
+}
+
+async fn fetch_listing_page(
+    client: &reqwest::Client,
+    token: &str,
+    subreddit: &str,
+    listing: Listing,
+    limit: u32,
+    after: Option<&str>,
+) -> Result<RedditListingData, FetchError> {
+    let url = listing.url(subreddit, limit, after);
 
     let response = client
         .get(&url)
@@ -148,18 +645,117 @@ async fn fetch_subreddit_posts(
         .header("User-Agent", "Trendr/1.0.0")
         .send()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(FetchError::network)?;
 
-    if !response.status().is_success() {
-        return Err(format!("Failed to fetch r/{}: {}", subreddit, response.status()));
+    if let Some(error) = status_error(&response, &format!("r/{}", subreddit)) {
+        return Err(error);
     }
 
-    let listing: RedditListing = response.json().await.map_err(|e| e.to_string())?;
+    let body = response.text().await.map_err(FetchError::network)?;
+    parse_listing(&body)
+}
+
+/// The error for an unsuccessful response to a request for `what`; None on success
+fn status_error(response: &reqwest::Response, what: &str) -> Option<FetchError> {
+    let status = response.status();
+
+    if status == 429 {
+        let headers = response.headers();
+        let retry_after = rate_limit::seconds_header(headers, "retry-after")
+            .or_else(|| rate_limit::seconds_header(headers, "x-ratelimit-reset"));
+        return Some(FetchError::RateLimited { retry_after });
+    }
+
+    if status == 401 {
+        return Some(FetchError::Unauthorized);
+    }
+
+    if status.is_server_error() {
+        return Some(FetchError::server(response, format!("Failed to fetch {}: {}", what, status)));
+    }
+
+    if !status.is_success() {
+        return Some(format!("Failed to fetch {}: {}", what, status).into());
+    }
 
-    Ok(listing.data.children.into_iter().map(|c| c.data).collect())
+    None
 }
 
-async fn process_post(post: &RedditPostData) -> Result<u32, String> {
+/// Up to `limit` of a post's top-level comments, best first, leaving out deleted ones and
+/// moderator stickies. Takes a slot from the shared rate limiter, and gets a fresh token
+/// and retries once if the current one was rejected.
+async fn fetch_top_comments(
+    client: &reqwest::Client,
+    credentials: &RedditCredentials,
+    limiter: &RateLimiter,
+    post: &RedditPostData,
+    limit: u32,
+) -> Result<Vec<RedditCommentData>, FetchError> {
+    let token = get_access_token(credentials).await?;
+    limiter.acquire().await;
+    let mut fetched = rate_limit::retry_with_backoff(|| fetch_comments_page(client, &token, post, limit)).await;
+
+    if matches!(fetched, Err(FetchError::Unauthorized)) {
+        invalidate_token(&token);
+        let token = get_access_token(credentials).await?;
+        limiter.acquire().await;
+        fetched = rate_limit::retry_with_backoff(|| fetch_comments_page(client, &token, post, limit)).await;
+    }
+
+    fetched
+}
+
+async fn fetch_comments_page(
+    client: &reqwest::Client,
+    token: &str,
+    post: &RedditPostData,
+    limit: u32,
+) -> Result<Vec<RedditCommentData>, FetchError> {
+    let url = format!(
+        "https://oauth.reddit.com/r/{}/comments/{}?limit={}&sort=top&depth=1",
+        post.subreddit, post.id, limit
+    );
+
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("User-Agent", "Trendr/1.0.0")
+        .send()
+        .await
+        .map_err(FetchError::network)?;
+
+    if let Some(error) = status_error(&response, &format!("comments on post {}", post.id)) {
+        return Err(error);
+    }
+
+    let body = response.text().await.map_err(FetchError::network)?;
+    parse_top_comments(&body, limit)
+}
+
+/// Up to `limit` comments from a comments response, in listing order, skipping the post
+/// itself, "more" stubs, moderator stickies and deleted comments
+fn parse_top_comments(body: &str, limit: u32) -> Result<Vec<RedditCommentData>, FetchError> {
+    let listings: Vec<RedditCommentListing> = serde_json::from_str(body).map_err(|e| e.to_string())?;
+
+    Ok(listings
+        .into_iter()
+        .nth(1)
+        .map(|listing| listing.data.children)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|thing| thing.kind == "t1")
+        .filter_map(|thing| serde_json::from_value::<RedditCommentData>(thing.data).ok())
+        .filter(|comment| !comment.stickied && !comment.is_deleted())
+        .take(limit as usize)
+        .collect())
+}
+
+async fn process_post(
+    post: &RedditPostData,
+    subreddit: &str,
+    known_topics: &[TopicData],
+    settings: &AppSettings,
+) -> Result<Processed, TrendrError> {
     // Check if post already exists
     let exists = with_db(|conn| {
         let count: i64 = conn.query_row(
@@ -171,113 +767,396 @@ async fn process_post(post: &RedditPostData) -> Result<u32, String> {
     })?;
 
     if exists {
-        return Ok(0);
+        return Ok(Processed::Duplicate);
+    }
+
+    let (selftext, truncated) = truncate_body(&post.title, &post.selftext, settings.max_text_length);
+    if truncated {
+        log::info!("Truncated Reddit post {} to {} characters", post.id, settings.max_text_length);
     }
 
-    // Get or create creator
-    let creator_id = get_or_create_creator(&post.author)?;
+    // Extract topics
+    let topics = extract_item_topics(
+        known_topics,
+        settings,
+        "reddit",
+        Some(subreddit),
+        ItemText::Titled {
+            title: &post.title,
+            body: selftext,
+        },
+    )?;
+    let topics_count = topics.len() as u32;
+
+    if drop_untagged(&topics, settings) {
+        return Ok(Processed::Untagged);
+    }
 
     // Insert content
     let content_id = uuid::Uuid::new_v4().to_string();
-    let text_content = format!("{}\n\n{}", post.title, post.selftext).trim().to_string();
+    let text_content = format!("{}\n\n{}", post.title, selftext).trim().to_string();
+    let sentiment = sentiment::score(&text_content);
+    let (media_url, thumbnail_url) = if settings.store_media_urls {
+        (post.media_url(), post.thumbnail_url())
+    } else {
+        (None, None)
+    };
     let published_at = chrono::DateTime::from_timestamp(post.created_utc as i64, 0)
         .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
         .unwrap_or_default();
 
-    with_db(|conn| {
-        conn.execute(
+    // The creator, content row and topic links are written together or not at all
+    with_db_tx(|tx| {
+        let creator_id = get_or_create_creator(tx, &post.author)?;
+        tx.execute(
             r#"INSERT INTO content (id, platform, platform_id, creator_id, content_type, text_content,
-               engagement_likes, engagement_comments, published_at)
-               VALUES (?1, 'reddit', ?2, ?3, 'post', ?4, ?5, ?6, ?7)"#,
+               engagement_likes, engagement_comments, engagement_shares, published_at, media_url,
+               thumbnail_url, sentiment, url, source)
+               VALUES (?1, 'reddit', ?2, ?3, ?4, ?5, ?6, ?7, 0, ?8, ?9, ?10, ?11, ?12, ?13)"#,
             params![
                 &content_id,
                 &post.id,
                 &creator_id,
+                ContentType::Post.as_str(),
                 &text_content,
                 post.score,
                 post.num_comments,
-                &published_at
+                &published_at,
+                &media_url,
+                &thumbnail_url,
+                sentiment,
+                post.post_url(),
+                subreddit
             ],
         )?;
-        Ok(())
+        urls::record_canonical_url(tx, &content_id, post.media_url().as_deref(), settings.dedupe_by_url)?;
+        hashtags::record_tags(tx, &content_id, &text_content)?;
+        topics::link_topics(tx, &content_id, &topics)
     })?;
 
-    // Extract topics
-    let topics = extract_topics(&text_content)?;
-    let topics_count = topics.len() as u32;
+    Ok(Processed::Stored(topics_count))
+}
+
+/// Store each comment as its own content row, returning what happened to each
+fn store_comments(
+    comments: &[RedditCommentData],
+    subreddit: &str,
+    known_topics: &[TopicData],
+    settings: &AppSettings,
+) -> Vec<ItemOutcome> {
+    comments
+        .iter()
+        .map(|comment| {
+            let platform_id = comment.platform_id();
+            match process_comment(comment, subreddit, known_topics, settings) {
+                Ok(Processed::Stored(topics_found)) => ItemOutcome::stored("reddit", &platform_id, topics_found),
+                Ok(Processed::Duplicate) => ItemOutcome::duplicate("reddit", &platform_id),
+                Ok(Processed::Untagged) => {
+                    ItemOutcome::filtered("reddit", &platform_id, UNTAGGED_REASON.to_string())
+                }
+                Err(e) => {
+                    log::warn!("Failed to process comment {}: {}", platform_id, e);
+                    ItemOutcome::failed("reddit", &platform_id, e.to_string())
+                }
+            }
+        })
+        .collect()
+}
+
+/// Store a comment as its own content row, extracting topics from its body the same way
+/// as a post's.
+fn process_comment(
+    comment: &RedditCommentData,
+    subreddit: &str,
+    known_topics: &[TopicData],
+    settings: &AppSettings,
+) -> Result<Processed, TrendrError> {
+    let platform_id = comment.platform_id();
+    let exists = with_db(|conn| {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM content WHERE platform = 'reddit' AND platform_id = ?1",
+            params![&platform_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    })?;
 
-    // Link content to topics
-    for topic in &topics {
-        with_db(|conn| {
-            conn.execute(
-                "INSERT OR REPLACE INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, ?3)",
-                params![&content_id, &topic.topic_id, topic.confidence],
-            )?;
-            Ok(())
-        })?;
+    if exists {
+        return Ok(Processed::Duplicate);
     }
 
-    // Update co-occurrences
-    if topics.len() > 1 {
-        let topic_ids: Vec<String> = topics.iter().map(|t| t.topic_id.clone()).collect();
-        update_cooccurrences(&topic_ids)?;
+    let (body, truncated) = truncate_body("", &comment.body, settings.max_text_length);
+    if truncated {
+        log::info!("Truncated Reddit comment {} to {} characters", platform_id, settings.max_text_length);
     }
 
-    Ok(topics_count)
-}
+    let topics = extract_item_topics(
+        known_topics,
+        settings,
+        "reddit",
+        Some(subreddit),
+        ItemText::Titled { title: "", body },
+    )?;
+    let topics_count = topics.len() as u32;
 
-fn get_or_create_creator(username: &str) -> Result<String, String> {
-    // Check if creator exists
-    let existing = with_db(|conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id FROM creators WHERE platform = 'reddit' AND username = ?1"
+    if drop_untagged(&topics, settings) {
+        return Ok(Processed::Untagged);
+    }
+
+    let content_id = uuid::Uuid::new_v4().to_string();
+    let text_content = body.trim().to_string();
+    let sentiment = sentiment::score(&text_content);
+    let published_at = chrono::DateTime::from_timestamp(comment.created_utc as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default();
+
+    with_db_tx(|tx| {
+        let creator_id = get_or_create_creator(tx, &comment.author)?;
+        tx.execute(
+            r#"INSERT INTO content (id, platform, platform_id, creator_id, content_type, text_content,
+               engagement_likes, engagement_comments, engagement_shares, published_at, sentiment, url, source)
+               VALUES (?1, 'reddit', ?2, ?3, ?4, ?5, ?6, 0, 0, ?7, ?8, ?9, ?10)"#,
+            params![
+                &content_id,
+                &platform_id,
+                &creator_id,
+                ContentType::Comment.as_str(),
+                &text_content,
+                comment.score,
+                &published_at,
+                sentiment,
+                comment.comment_url(),
+                subreddit
+            ],
         )?;
-        let mut rows = stmt.query(params![username])?;
-        if let Some(row) = rows.next()? {
-            let id: String = row.get(0)?;
-            Ok(Some(id))
-        } else {
-            Ok(None)
-        }
+        hashtags::record_tags(tx, &content_id, &text_content)?;
+        topics::link_topics(tx, &content_id, &topics)
     })?;
 
+    Ok(Processed::Stored(topics_count))
+}
+
+fn get_or_create_creator(conn: &Connection, username: &str) -> Result<String, rusqlite::Error> {
+    // Check if creator exists
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM creators WHERE platform = 'reddit' AND username = ?1",
+            params![username],
+            |row| row.get(0),
+        )
+        .optional()?;
+
     if let Some(id) = existing {
         return Ok(id);
     }
 
     // Create new creator
     let creator_id = uuid::Uuid::new_v4().to_string();
-    with_db(|conn| {
-        conn.execute(
-            "INSERT INTO creators (id, platform, platform_id, username) VALUES (?1, 'reddit', ?2, ?2)",
-            params![&creator_id, username],
-        )?;
-        Ok(())
-    })?;
+    conn.execute(
+        "INSERT INTO creators (id, platform, platform_id, username) VALUES (?1, 'reddit', ?2, ?2)",
+        params![&creator_id, username],
+    )?;
 
     Ok(creator_id)
 }
 
-fn update_cooccurrences(topic_ids: &[String]) -> Result<(), String> {
-    for i in 0..topic_ids.len() {
-        for j in (i + 1)..topic_ids.len() {
-            let (a, b) = if topic_ids[i] < topic_ids[j] {
-                (&topic_ids[i], &topic_ids[j])
-            } else {
-                (&topic_ids[j], &topic_ids[i])
-            };
-
-            with_db(|conn| {
-                conn.execute(
-                    r#"INSERT INTO topic_cooccurrences (topic_a_id, topic_b_id, frequency, last_seen)
-                       VALUES (?1, ?2, 1, CURRENT_TIMESTAMP)
-                       ON CONFLICT(topic_a_id, topic_b_id) DO UPDATE SET
-                       frequency = frequency + 1, last_seen = CURRENT_TIMESTAMP"#,
-                    params![a, b],
-                )?;
-                Ok(())
-            })?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(sort: &str, time: &str) -> Listing {
+        Listing::from_settings(&RedditFetchSettings {
+            sort: sort.to_string(),
+            time: time.to_string(),
+            ..RedditFetchSettings::default()
+        })
+    }
+
+    fn credentials(client_secret: &str, username: &str, password: &str) -> RedditCredentials {
+        RedditCredentials {
+            client_id: "client".to_string(),
+            client_secret: client_secret.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
         }
     }
-    Ok(())
+
+    #[test]
+    fn token_body_uses_the_password_grant_for_accounts() {
+        assert_eq!(
+            token_request_body(&credentials("secret", "ferris", "hunter2")).unwrap(),
+            "grant_type=password&username=ferris&password=hunter2"
+        );
+        assert_eq!(
+            token_request_body(&credentials("secret", "ferris", "a&b=c d")).unwrap(),
+            "grant_type=password&username=ferris&password=a%26b%3Dc%20d"
+        );
+    }
+
+    #[test]
+    fn tokens_are_cached_per_account_until_they_expire() {
+        let requests = std::sync::atomic::AtomicUsize::new(0);
+        let token = |credentials: &RedditCredentials, expires_in: u64| {
+            let requests = &requests;
+            tokio::runtime::Runtime::new().unwrap().block_on(access_token_with(credentials, || async move {
+                let n = requests.fetch_add(1, Ordering::Relaxed) + 1;
+                Ok(TokenResponse {
+                    access_token: format!("token {}", n),
+                    token_type: "bearer".to_string(),
+                    expires_in,
+                })
+            }))
+        };
+        let ferris = credentials("secret", "ferris", "hunter2");
+        let crab = credentials("secret", "crab", "hunter2");
+
+        assert_eq!(token(&ferris, 3600).unwrap(), "token 1");
+        assert_eq!(token(&ferris, 3600).unwrap(), "token 1");
+        assert_eq!(requests.load(Ordering::Relaxed), 1);
+
+        // Another account doesn't get the cached token
+        assert_eq!(token(&crab, 3600).unwrap(), "token 2");
+
+        // A rejected token is dropped, but only if it is still the one cached
+        invalidate_token("token 1");
+        assert_eq!(token(&crab, 3600).unwrap(), "token 2");
+        invalidate_token("token 2");
+        assert_eq!(token(&crab, 3600).unwrap(), "token 3");
+
+        // Inside the expiry margin a token is already treated as expired
+        assert_eq!(token(&ferris, TOKEN_EXPIRY_MARGIN.as_secs()).unwrap(), "token 4");
+        assert_eq!(token(&ferris, 3600).unwrap(), "token 5");
+        assert_eq!(token(&ferris, 3600).unwrap(), "token 5");
+        assert_eq!(requests.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn token_body_without_an_account_is_app_only() {
+        assert_eq!(
+            token_request_body(&credentials("secret", " ", "")).unwrap(),
+            "grant_type=client_credentials"
+        );
+        assert_eq!(
+            token_request_body(&credentials("", "", "")).unwrap(),
+            "grant_type=https%3A%2F%2Foauth.reddit.com%2Fgrants%2Finstalled_client\
+             &device_id=DO_NOT_TRACK_THIS_DEVICE"
+        );
+    }
+
+    #[test]
+    fn token_body_needs_both_username_and_password() {
+        assert!(token_request_body(&credentials("secret", "ferris", "")).is_err());
+        assert!(token_request_body(&credentials("secret", "", "hunter2")).is_err());
+    }
+
+    #[test]
+    fn listing_url_includes_time_only_for_ranked_sorts() {
+        assert_eq!(
+            listing("new", "week").url("rust", 25, None),
+            "https://oauth.reddit.com/r/rust/new?limit=25"
+        );
+        assert_eq!(
+            listing(" Top ", "WEEK").url("rust", 100, None),
+            "https://oauth.reddit.com/r/rust/top?limit=100&t=week"
+        );
+        assert_eq!(
+            listing("controversial", "all").url("rust", 10, None),
+            "https://oauth.reddit.com/r/rust/controversial?limit=10&t=all"
+        );
+    }
+
+    #[test]
+    fn listing_url_encodes_the_page_cursor() {
+        assert_eq!(
+            listing("hot", "day").url("rust", 50, Some("t3_abc+/=")),
+            "https://oauth.reddit.com/r/rust/hot?limit=50&after=t3_abc%2B%2F%3D"
+        );
+    }
+
+    fn listing_page(ids: &[&str], after: Option<&str>) -> String {
+        let children: Vec<serde_json::Value> = ids
+            .iter()
+            .map(|id| {
+                serde_json::json!({"kind": "t3", "data": {
+                    "id": id, "subreddit": "rust", "author": "ferris", "title": format!("Post {}", id),
+                    "selftext": "", "score": 1, "num_comments": 0, "created_utc": 1700000000.0,
+                    "permalink": format!("/r/rust/comments/{}/post/", id),
+                }})
+            })
+            .collect();
+        serde_json::json!({"kind": "Listing", "data": {"children": children, "after": after}}).to_string()
+    }
+
+    #[test]
+    fn listing_pages_follow_after_until_the_last_page() {
+        let first = parse_listing(&listing_page(&["a", "b"], Some("t3_b"))).unwrap();
+        let ids: Vec<&str> = first.children.iter().map(|c| c.data.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(first.children[0].data.post_url(), "https://reddit.com/r/rust/comments/a/post/");
+        assert_eq!(next_page_cursor(&first, 0, 10).as_deref(), Some("t3_b"));
+
+        let last = parse_listing(&listing_page(&["c"], None)).unwrap();
+        assert_eq!(last.children[0].data.id, "c");
+        assert_eq!(next_page_cursor(&last, 2, 10), None);
+
+        assert!(parse_listing("<html>Too Many Requests</html>").is_err());
+    }
+
+    #[test]
+    fn listing_paging_stops_at_the_cap_or_an_empty_page() {
+        let page = parse_listing(&listing_page(&["a", "b"], Some("t3_b"))).unwrap();
+        assert_eq!(next_page_cursor(&page, 7, 10).as_deref(), Some("t3_b"));
+        assert_eq!(next_page_cursor(&page, 8, 10), None);
+
+        let empty = parse_listing(&listing_page(&[], Some("t3_b"))).unwrap();
+        assert_eq!(next_page_cursor(&empty, 2, 10), None);
+    }
+
+    fn comment(id: &str, author: &str, body: &str, stickied: bool) -> serde_json::Value {
+        serde_json::json!({"kind": "t1", "data": {
+            "id": id, "author": author, "body": body, "score": 10, "created_utc": 1700000000.0,
+            "permalink": format!("/r/rust/comments/a/post/{}/", id), "stickied": stickied,
+        }})
+    }
+
+    fn comments_body() -> String {
+        serde_json::json!([
+            {"kind": "Listing", "data": {"children": [{"kind": "t3", "data": {"id": "a", "title": "Post a"}}]}},
+            {"kind": "Listing", "data": {"children": [
+                comment("rules", "AutoModerator", "Please read the rules", true),
+                comment("c1", "ferris", "Cargo workspaces are great", false),
+                comment("c2", "[deleted]", "[deleted]", false),
+                comment("c3", "crab", "[removed]", false),
+                comment("c4", "bors", "Merged", false),
+                {"kind": "more", "data": {"count": 12, "children": ["c5", "c6"]}},
+            ]}},
+        ])
+        .to_string()
+    }
+
+    #[test]
+    fn comment_listings_keep_live_comments_in_order() {
+        let comments = parse_top_comments(&comments_body(), 10).unwrap();
+        let ids: Vec<String> = comments.iter().map(RedditCommentData::platform_id).collect();
+        assert_eq!(ids, ["t1_c1", "t1_c4"]);
+        assert_eq!(comments[0].body, "Cargo workspaces are great");
+        assert_eq!(comments[0].comment_url(), "https://reddit.com/r/rust/comments/a/post/c1/");
+
+        assert_eq!(parse_top_comments(&comments_body(), 1).unwrap().len(), 1);
+        // A post with no comments only has its own listing
+        assert!(parse_top_comments("[]", 10).unwrap().is_empty());
+        assert!(parse_top_comments("{}", 10).is_err());
+    }
+
+    #[test]
+    fn unknown_sort_and_time_fall_back_to_hot_and_day() {
+        assert_eq!(
+            listing("best", "week").url("rust", 25, None),
+            "https://oauth.reddit.com/r/rust/hot?limit=25"
+        );
+        assert_eq!(
+            listing("top", "decade").url("rust", 25, None),
+            "https://oauth.reddit.com/r/rust/top?limit=25&t=day"
+        );
+    }
 }
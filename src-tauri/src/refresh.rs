@@ -0,0 +1,223 @@
+use crate::content::status;
+use crate::database::with_db;
+use crate::error::TrendrError;
+use crate::settings::{AppSettings, EngagementRefreshSettings};
+use crate::x;
+use crate::youtube;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Fresh engagement numbers for one item, keyed by its platform id.
+#[derive(Debug, Clone)]
+pub struct EngagementUpdate {
+    pub platform_id: String,
+    pub likes: i64,
+    pub comments: i64,
+    pub shares: i64,
+    pub views: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct RefreshResult {
+    #[serde(rename = "itemsDue")]
+    pub items_due: u32,
+    #[serde(rename = "itemsUpdated")]
+    pub items_updated: u32,
+    #[serde(rename = "itemsDeleted")]
+    pub items_deleted: u32,
+}
+
+#[derive(Debug)]
+struct DueContent {
+    platform: String,
+    platform_id: String,
+    overdue_minutes: i64,
+}
+
+/// Refresh engagement for X and YouTube content that is due under the age-based
+/// cadence in settings. Platforms without credentials are skipped.
+pub async fn refresh_due_engagement(settings: &AppSettings) -> Result<RefreshResult, TrendrError> {
+    let config = &settings.engagement_refresh;
+    if !config.enabled || config.tiers.is_empty() {
+        return Ok(RefreshResult::default());
+    }
+
+    let due = select_due_content(config)?;
+    let mut result = RefreshResult {
+        items_due: due.len() as u32,
+        ..Default::default()
+    };
+
+    let ids_for = |platform: &str| -> Vec<String> {
+        due.iter()
+            .filter(|d| d.platform == platform)
+            .map(|d| d.platform_id.clone())
+            .collect()
+    };
+
+    if let Some(credentials) = &settings.x {
+        let ids = ids_for("x");
+        if !ids.is_empty() {
+            match x::fetch_engagement(credentials, &ids).await {
+                Ok(updates) => apply_updates("x", &ids, &updates, config, &mut result)?,
+                Err(e) => log::error!("Failed to refresh X engagement: {}", e),
+            }
+        }
+    }
+
+    if let Some(credentials) = &settings.youtube {
+        let ids = ids_for("youtube");
+        if !ids.is_empty() {
+            match youtube::fetch_engagement(credentials, &ids).await {
+                Ok(updates) => apply_updates("youtube", &ids, &updates, config, &mut result)?,
+                Err(e) => log::error!("Failed to refresh YouTube engagement: {}", e),
+            }
+        }
+    }
+
+    log::info!(
+        "Engagement refresh: {} due, {} updated, {} deleted",
+        result.items_due,
+        result.items_updated,
+        result.items_deleted
+    );
+    Ok(result)
+}
+
+fn select_due_content(config: &EngagementRefreshSettings) -> Result<Vec<DueContent>, TrendrError> {
+    let max_age_hours = config.tiers.iter().map(|t| t.max_age_hours).max().unwrap_or(0);
+    let now = Utc::now();
+
+    let candidates = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT platform, platform_id, published_at, collected_at, last_refreshed_at
+               FROM content
+               WHERE platform IN ('x', 'youtube') AND status != ?2
+               AND COALESCE(datetime(published_at), datetime(collected_at)) > datetime('now', ?1)"#,
+        )?;
+
+        let rows = stmt.query_map(params![format!("-{} hours", max_age_hours), status::DELETED], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+
+        let mut candidates = Vec::new();
+        for row in rows {
+            candidates.push(row?);
+        }
+        Ok(candidates)
+    })?;
+
+    let mut due = Vec::new();
+    for (platform, platform_id, published_at, collected_at, last_refreshed_at) in candidates {
+        let collected = match parse_timestamp(&collected_at) {
+            Some(t) => t,
+            None => continue,
+        };
+        let published = published_at.as_deref().and_then(parse_timestamp).unwrap_or(collected);
+        let last_refreshed = last_refreshed_at.as_deref().and_then(parse_timestamp).unwrap_or(collected);
+
+        let age_hours = (now - published).num_hours();
+        let tier = config
+            .tiers
+            .iter()
+            .find(|t| age_hours < t.max_age_hours as i64);
+
+        if let Some(tier) = tier {
+            let overdue_minutes = (now - last_refreshed).num_minutes() - tier.interval_minutes as i64;
+            if overdue_minutes >= 0 {
+                due.push(DueContent {
+                    platform,
+                    platform_id,
+                    overdue_minutes,
+                });
+            }
+        }
+    }
+
+    // Most overdue first so a capped run still makes progress on the backlog
+    due.sort_by_key(|d| std::cmp::Reverse(d.overdue_minutes));
+    due.truncate(config.max_items_per_run as usize);
+    Ok(due)
+}
+
+/// Write refreshed metrics. Every requested id is stamped as refreshed; ids the API
+/// didn't return are optionally marked deleted so they aren't re-requested.
+fn apply_updates(
+    platform: &str,
+    requested: &[String],
+    updates: &[EngagementUpdate],
+    config: &EngagementRefreshSettings,
+    result: &mut RefreshResult,
+) -> Result<(), TrendrError> {
+    let returned: HashSet<&str> = updates.iter().map(|u| u.platform_id.as_str()).collect();
+    let missing: Vec<&String> = requested
+        .iter()
+        .filter(|id| !returned.contains(id.as_str()))
+        .collect();
+
+    with_db(|conn| {
+        let tx = conn.unchecked_transaction()?;
+
+        for update in updates {
+            tx.execute(
+                r#"UPDATE content SET engagement_likes = ?1, engagement_comments = ?2,
+                   engagement_shares = ?3, engagement_views = COALESCE(?4, engagement_views),
+                   status = ?5
+                   WHERE platform = ?6 AND platform_id = ?7"#,
+                params![
+                    update.likes,
+                    update.comments,
+                    update.shares,
+                    update.views,
+                    status::ACTIVE,
+                    platform,
+                    &update.platform_id
+                ],
+            )?;
+        }
+
+        if config.mark_missing_as_deleted {
+            for platform_id in &missing {
+                tx.execute(
+                    "UPDATE content SET status = ?1 WHERE platform = ?2 AND platform_id = ?3",
+                    params![status::DELETED, platform, platform_id],
+                )?;
+            }
+        }
+
+        for platform_id in requested {
+            tx.execute(
+                "UPDATE content SET last_refreshed_at = CURRENT_TIMESTAMP WHERE platform = ?1 AND platform_id = ?2",
+                params![platform, platform_id],
+            )?;
+        }
+
+        tx.commit()
+    })?;
+
+    result.items_updated += updates.len() as u32;
+    if config.mark_missing_as_deleted {
+        result.items_deleted += missing.len() as u32;
+    }
+    Ok(())
+}
+
+/// Parse either an RFC 3339 platform timestamp or SQLite's `CURRENT_TIMESTAMP` format.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|dt| dt.and_utc())
+        })
+}
@@ -0,0 +1,151 @@
+use crate::database::{with_db, with_db_tx};
+use crate::error::TrendrError;
+use rusqlite::params;
+use serde::Serialize;
+
+/// Deleting at least this many rows is worth a VACUUM to give the space back
+const VACUUM_THRESHOLD: usize = 1_000;
+
+/// Content matched by a prune. ?1 is the age cutoff as a datetime modifier, ?2 the
+/// number of newest rows to keep, ?3 whether rows linked to a topic are kept; NULL
+/// cutoffs don't apply.
+const PRUNE_SELECTION: &str = r#"SELECT c.id FROM content c
+    WHERE ((?1 IS NOT NULL AND datetime(c.collected_at) < datetime('now', ?1))
+        OR (?2 IS NOT NULL AND c.id NOT IN (SELECT id FROM content ORDER BY collected_at DESC LIMIT ?2)))
+    AND NOT (?3 AND EXISTS (SELECT 1 FROM content_topics ct WHERE ct.content_id = c.id))"#;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneResult {
+    pub deleted: usize,
+    pub vacuumed: bool,
+}
+
+/// Delete content collected more than `older_than_days` ago, and/or all but the newest
+/// `max_rows` items. With `keep_linked`, content still linked to a topic is kept either
+/// way. Topic links, tags and search index entries go with the rows; entity mentions are
+/// removed explicitly since that table predates cascading deletes. The database is
+/// vacuumed afterwards when enough rows were removed.
+pub fn prune_content(
+    older_than_days: Option<i64>,
+    max_rows: Option<i64>,
+    keep_linked: bool,
+) -> Result<PruneResult, TrendrError> {
+    if older_than_days.is_none() && max_rows.is_none() {
+        return Err(TrendrError::Other("Give an age in days, a maximum row count, or both".to_string()));
+    }
+    if older_than_days.is_some_and(|days| days < 1) {
+        return Err(TrendrError::Other("Content age must be at least 1 day".to_string()));
+    }
+    if max_rows.is_some_and(|rows| rows < 0) {
+        return Err(TrendrError::Other("Maximum row count can't be negative".to_string()));
+    }
+    let cutoff = older_than_days.map(|days| format!("-{} days", days));
+
+    let deleted = with_db_tx(|tx| {
+        // Topic links, tags and entity mentions go with their content
+        let selection = params![&cutoff, max_rows, keep_linked];
+        let deleted = tx.execute(&format!("DELETE FROM content WHERE id IN ({})", PRUNE_SELECTION), selection)?;
+
+        // Reposts of deleted content become originals rather than pointing at nothing
+        tx.execute(
+            r#"UPDATE content SET duplicate_of = NULL
+               WHERE duplicate_of IS NOT NULL AND duplicate_of NOT IN (SELECT id FROM content)"#,
+            [],
+        )?;
+        Ok(deleted)
+    })?;
+
+    let vacuumed = deleted >= VACUUM_THRESHOLD;
+    if vacuumed {
+        with_db(|conn| conn.execute_batch("VACUUM"))?;
+    }
+
+    log::info!("Pruned {} content items{}", deleted, if vacuumed { " and vacuumed" } else { "" });
+    Ok(PruneResult { deleted, vacuumed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two month-old items (one linked to a topic), a repost of the unlinked one, and
+    /// two recent items, one linked
+    fn seed() {
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug, keywords) VALUES ('rust', 'Rust', 'rust', '[]');
+                   INSERT INTO entities (id, name, normalized) VALUES ('ferris', 'Ferris', 'ferris');
+                   INSERT INTO content (id, platform, platform_id, content_type, collected_at) VALUES
+                       ('old_linked', 'x', 't1', 'post', datetime('now', '-40 days')),
+                       ('old', 'x', 't2', 'post', datetime('now', '-35 days')),
+                       ('repost', 'x', 't3', 'post', datetime('now', '-2 days')),
+                       ('recent', 'x', 't4', 'post', datetime('now', '-1 days')),
+                       ('new_linked', 'x', 't5', 'post', datetime('now'));
+                   UPDATE content SET duplicate_of = 'old' WHERE id = 'repost';
+                   INSERT INTO content_topics (content_id, topic_id, confidence)
+                   VALUES ('old_linked', 'rust', 1.0), ('new_linked', 'rust', 1.0);
+                   INSERT INTO content_entities (content_id, entity_id) VALUES ('old', 'ferris');"#,
+            )
+        })
+        .unwrap();
+    }
+
+    fn remaining() -> Vec<String> {
+        with_db(|conn| {
+            let mut stmt = conn.prepare("SELECT id FROM content ORDER BY collected_at")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect()
+        })
+        .unwrap()
+    }
+
+    fn count(sql: &str) -> i64 {
+        with_db(|conn| conn.query_row(sql, [], |row| row.get(0))).unwrap()
+    }
+
+    #[test]
+    fn prunes_content_older_than_the_cutoff() {
+        let _db = crate::database::test_database();
+        seed();
+
+        let result = prune_content(Some(30), None, false).unwrap();
+        assert_eq!(result.deleted, 2);
+        assert!(!result.vacuumed);
+        assert_eq!(remaining(), ["repost", "recent", "new_linked"]);
+
+        // Links, entity mentions and duplicate pointers go with the rows
+        assert_eq!(count("SELECT COUNT(*) FROM content_topics WHERE content_id = 'old_linked'"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM content_topics"), 1);
+        assert_eq!(count("SELECT COUNT(*) FROM content_entities"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM content WHERE duplicate_of IS NOT NULL"), 0);
+    }
+
+    #[test]
+    fn prunes_all_but_the_newest_rows() {
+        let _db = crate::database::test_database();
+        seed();
+
+        assert_eq!(prune_content(None, Some(2), false).unwrap().deleted, 3);
+        assert_eq!(remaining(), ["recent", "new_linked"]);
+    }
+
+    #[test]
+    fn keep_linked_spares_content_with_topic_links() {
+        let _db = crate::database::test_database();
+        seed();
+
+        assert_eq!(prune_content(Some(30), None, true).unwrap().deleted, 1);
+        assert_eq!(remaining(), ["old_linked", "repost", "recent", "new_linked"]);
+
+        assert_eq!(prune_content(None, Some(1), true).unwrap().deleted, 2);
+        assert_eq!(remaining(), ["old_linked", "new_linked"]);
+        assert_eq!(count("SELECT COUNT(*) FROM content_topics"), 2);
+    }
+
+    #[test]
+    fn rejects_missing_or_invalid_limits() {
+        assert!(prune_content(None, None, false).is_err());
+        assert!(prune_content(Some(0), None, false).is_err());
+        assert!(prune_content(None, Some(-1), false).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::commands;
+use crate::error::TrendrError;
+use crate::settings;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How often the background task checks whether a scheduled run is due, so interval
+/// changes and start/stop take effect without waiting out a full interval
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Shared state for the interval-based collection scheduler.
+#[derive(Debug, Clone, Default)]
+pub struct SchedulerState {
+    pub running: bool,
+    pub paused: bool,
+    /// When the scheduler was last started. Runs are only remembered in memory, so after
+    /// a launch the first run waits one interval from here instead of firing right away.
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+pub static SCHEDULER_STATE: once_cell::sync::Lazy<Mutex<SchedulerState>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(SchedulerState::default()));
+
+pub fn state() -> Result<SchedulerState, TrendrError> {
+    SCHEDULER_STATE
+        .lock()
+        .map(|state| state.clone())
+        .map_err(|e| TrendrError::Other(e.to_string()))
+}
+
+pub fn set_running(running: bool) -> Result<(), TrendrError> {
+    let mut state = SCHEDULER_STATE.lock().map_err(|e| TrendrError::Other(e.to_string()))?;
+    if running && !state.running {
+        state.started_at = Some(chrono::Utc::now());
+    }
+    state.running = running;
+    Ok(())
+}
+
+/// Start the background task that runs collection every `collectionIntervalMinutes`.
+/// Settings are re-read on every check, so interval, credential and source changes
+/// apply to the next run.
+pub fn spawn(app: tauri::AppHandle) -> Result<(), TrendrError> {
+    set_running(true)?;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if let Err(e) = run_if_due(&app).await {
+                log::warn!("Scheduled collection failed: {}", e);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn run_if_due(app: &tauri::AppHandle) -> Result<(), TrendrError> {
+    let settings = settings::load_settings();
+    let last_run_at = commands::last_collection_run_at()?;
+    let now = chrono::Utc::now();
+    let due = next_run_at(&state()?, last_run_at.as_deref(), settings.collection_interval_minutes, now)
+        .is_some_and(|next| next <= now);
+
+    if due {
+        commands::run_scheduled_collection(app, &settings).await?;
+    }
+    Ok(())
+}
+
+/// When the next scheduled run is due: one interval after the last run, or one interval
+/// after the scheduler started if nothing has run yet, but never before `now`. None when
+/// the scheduler is stopped or paused.
+pub fn next_run_at(
+    state: &SchedulerState,
+    last_run_at: Option<&str>,
+    interval_minutes: u32,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    if !state.running || state.paused {
+        return None;
+    }
+
+    let next = last_run_at
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .or(state.started_at)
+        .map(|t| t + chrono::Duration::minutes(interval_minutes as i64))
+        .unwrap_or(now);
+
+    Some(next.max(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn started(minutes_ago: i64) -> SchedulerState {
+        SchedulerState {
+            running: true,
+            paused: false,
+            started_at: Some(Utc::now() - Duration::minutes(minutes_ago)),
+        }
+    }
+
+    #[test]
+    fn first_run_waits_one_interval_after_start() {
+        let now = Utc::now();
+        let state = started(0);
+        let next = next_run_at(&state, None, 15, now).unwrap();
+        assert_eq!(next, state.started_at.unwrap() + Duration::minutes(15));
+        assert!(next > now);
+    }
+
+    #[test]
+    fn short_interval_comes_due() {
+        let now = Utc::now();
+        assert!(next_run_at(&started(2), None, 1, now).unwrap() <= now);
+
+        let last_run = (now - Duration::minutes(5)).to_rfc3339();
+        assert_eq!(next_run_at(&started(60), Some(&last_run), 1, now), Some(now));
+        assert!(next_run_at(&started(60), Some(&last_run), 10, now).unwrap() > now);
+    }
+
+    #[test]
+    fn stopped_or_paused_has_no_next_run() {
+        let now = Utc::now();
+        let mut state = started(60);
+        state.paused = true;
+        assert_eq!(next_run_at(&state, None, 1, now), None);
+        assert_eq!(next_run_at(&SchedulerState::default(), None, 1, now), None);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::alerts;
+use crate::database::with_db;
+use crate::error::TrendrError;
+use rusqlite::params;
+use serde::Serialize;
+
+const POSITIVE: &[&str] = &[
+    "amazing", "awesome", "best", "bull", "bullish", "excellent", "excited", "gain", "gains",
+    "good", "great", "growth", "happy", "love", "moon", "opportunity", "positive", "profit",
+    "profitable", "rally", "recommend", "rich", "strong", "success", "successful", "up",
+    "win", "winning", "wins", "worth",
+];
+
+const NEGATIVE: &[&str] = &[
+    "bad", "bear", "bearish", "broke", "bubble", "crash", "crashed", "dead", "debt", "down",
+    "dump", "fail", "failed", "fear", "fraud", "hate", "lose", "losing", "loss", "losses",
+    "negative", "panic", "poor", "risk", "risky", "scam", "terrible", "weak", "worst", "worthless",
+];
+
+const NEGATIONS: &[&str] = &["not", "no", "never", "dont", "don't", "isnt", "isn't", "wasnt", "wasn't"];
+
+/// Lexicon-based sentiment in [-1, 1]: (positive - negative) / (positive + negative),
+/// with a word's polarity flipped when it directly follows a negation. None when the
+/// text has no sentiment-bearing words, so neutral text doesn't dilute averages.
+pub fn score(text: &str) -> Option<f64> {
+    let lowered = text.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut positive = 0u32;
+    let mut negative = 0u32;
+
+    for (i, word) in words.iter().enumerate() {
+        let polarity = if POSITIVE.contains(word) {
+            1
+        } else if NEGATIVE.contains(word) {
+            -1
+        } else {
+            continue;
+        };
+
+        let negated = i > 0 && NEGATIONS.contains(&words[i - 1]);
+        if (polarity > 0) != negated {
+            positive += 1;
+        } else {
+            negative += 1;
+        }
+    }
+
+    let total = positive + negative;
+    if total == 0 {
+        None
+    } else {
+        Some((positive as f64 - negative as f64) / total as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SentimentShift {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    #[serde(rename = "recentAverage")]
+    pub recent_average: f64,
+    #[serde(rename = "trailingAverage")]
+    pub trailing_average: f64,
+    pub delta: f64,
+    #[serde(rename = "recentSamples")]
+    pub recent_samples: i64,
+    #[serde(rename = "trailingSamples")]
+    pub trailing_samples: i64,
+}
+
+/// Minimum scored items on each side of the comparison before a shift is reported
+const MIN_SAMPLES: i64 = 5;
+
+/// Trailing baseline covers this many windows before the recent one
+const TRAILING_WINDOWS: i64 = 4;
+
+/// Compare each topic's average sentiment over the last `days` against the preceding
+/// `TRAILING_WINDOWS * days`, returning topics whose average moved by at least `threshold`.
+pub fn detect_shifts(days: i64, threshold: f64) -> Result<Vec<SentimentShift>, TrendrError> {
+    let days = days.max(1);
+    let recent = format!("-{} days", days);
+    let trailing = format!("-{} days", days * (TRAILING_WINDOWS + 1));
+
+    let mut shifts = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT t.id, t.name,
+               AVG(CASE WHEN w.at > datetime('now', ?1) THEN w.sentiment END) as recent_avg,
+               COUNT(CASE WHEN w.at > datetime('now', ?1) THEN 1 END) as recent_n,
+               AVG(CASE WHEN w.at <= datetime('now', ?1) THEN w.sentiment END) as trailing_avg,
+               COUNT(CASE WHEN w.at <= datetime('now', ?1) THEN 1 END) as trailing_n
+               FROM topics t
+               JOIN (
+                   SELECT ct.topic_id, c.sentiment,
+                   COALESCE(datetime(c.published_at), datetime(c.collected_at)) as at
+                   FROM content_topics ct
+                   JOIN content c ON c.id = ct.content_id
+                   WHERE c.sentiment IS NOT NULL
+               ) w ON w.topic_id = t.id
+               WHERE w.at > datetime('now', ?2)
+               GROUP BY t.id
+               HAVING recent_n >= ?3 AND trailing_n >= ?3"#,
+        )?;
+
+        let rows = stmt.query_map(params![&recent, &trailing, MIN_SAMPLES], |row| {
+            let recent_average: f64 = row.get(2)?;
+            let trailing_average: f64 = row.get(4)?;
+            Ok(SentimentShift {
+                topic_id: row.get(0)?,
+                topic_name: row.get(1)?,
+                recent_average,
+                trailing_average,
+                delta: recent_average - trailing_average,
+                recent_samples: row.get(3)?,
+                trailing_samples: row.get(5)?,
+            })
+        })?;
+
+        let mut shifts = Vec::new();
+        for row in rows {
+            let shift = row?;
+            if shift.delta.abs() >= threshold {
+                shifts.push(shift);
+            }
+        }
+        Ok(shifts)
+    })?;
+
+    shifts.sort_by(|a, b| b.delta.abs().partial_cmp(&a.delta.abs()).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(shifts)
+}
+
+/// Raise a `sentiment_shift` alert for each detected shift, at most one per topic per window.
+pub fn alert_on_shifts(shifts: &[SentimentShift], days: i64) -> Result<u32, TrendrError> {
+    let mut created = 0u32;
+
+    for shift in shifts {
+        let direction = if shift.delta > 0.0 { "positive" } else { "negative" };
+        let message = format!(
+            "Sentiment for {} turned {}: {:+.2} over the last {} days (from {:.2} to {:.2})",
+            shift.topic_name, direction, shift.delta, days, shift.trailing_average, shift.recent_average
+        );
+
+        if alerts::create_alert_once("sentiment_shift", Some(&shift.topic_id), &message, days * 24)? {
+            created += 1;
+        }
+    }
+
+    Ok(created)
+}
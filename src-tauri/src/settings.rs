@@ -1,18 +1,32 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use directories::ProjectDirs;
+use std::path::{Path, PathBuf};
+use crate::error::TrendrError;
+use crate::paths;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct RedditCredentials {
     #[serde(rename = "clientId")]
     pub client_id: String,
+    /// Blank for an installed app, which has no secret
     #[serde(rename = "clientSecret")]
     pub client_secret: String,
+    /// Leave username and password blank to sign in as the app alone. That is enough for
+    /// reading public listings and works for accounts with 2FA.
+    #[serde(default)]
     pub username: String,
+    #[serde(default)]
     pub password: String,
 }
 
+impl RedditCredentials {
+    /// Whether no Reddit account is configured, so tokens are application-only
+    pub fn is_app_only(&self) -> bool {
+        self.username.trim().is_empty() && self.password.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct XCredentials {
     #[serde(rename = "bearerToken")]
@@ -25,6 +39,352 @@ pub struct YouTubeCredentials {
     pub api_key: String,
 }
 
+/// Refresh content younger than `max_age_hours` at most every `interval_minutes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshTier {
+    #[serde(rename = "maxAgeHours")]
+    pub max_age_hours: u32,
+    #[serde(rename = "intervalMinutes")]
+    pub interval_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngagementRefreshSettings {
+    pub enabled: bool,
+    /// Checked in order; content older than every tier is no longer refreshed.
+    pub tiers: Vec<RefreshTier>,
+    #[serde(rename = "maxItemsPerRun")]
+    pub max_items_per_run: u32,
+    /// Mark content the API no longer returns as deleted
+    #[serde(rename = "markMissingAsDeleted")]
+    pub mark_missing_as_deleted: bool,
+}
+
+impl Default for EngagementRefreshSettings {
+    fn default() -> Self {
+        EngagementRefreshSettings {
+            enabled: true,
+            tiers: vec![
+                RefreshTier { max_age_hours: 6, interval_minutes: 60 },
+                RefreshTier { max_age_hours: 48, interval_minutes: 360 },
+                RefreshTier { max_age_hours: 24 * 7, interval_minutes: 1440 },
+            ],
+            max_items_per_run: 500,
+            mark_missing_as_deleted: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractionSettings {
+    /// Count overlapping keyword/alias hits on the same span of text once
+    #[serde(rename = "dedupeOverlappingMatches")]
+    pub dedupe_overlapping_matches: bool,
+    /// Multiplier for keyword matches in a title (Reddit post title, YouTube video title)
+    #[serde(rename = "titleWeight")]
+    pub title_weight: f64,
+    /// Multiplier for keyword matches in a body (Reddit selftext, YouTube description)
+    #[serde(rename = "bodyWeight")]
+    pub body_weight: f64,
+    /// Accept one-character keywords when saving topics. They match almost everything,
+    /// so they are rejected by default.
+    #[serde(rename = "allowSingleCharKeywords")]
+    pub allow_single_char_keywords: bool,
+}
+
+impl Default for ExtractionSettings {
+    fn default() -> Self {
+        ExtractionSettings {
+            dedupe_overlapping_matches: true,
+            title_weight: 1.0,
+            body_weight: 1.0,
+            allow_single_char_keywords: false,
+        }
+    }
+}
+
+/// Skip content from creators below these follower/subscriber counts. Reddit exposes no
+/// follower data, so it has no threshold.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct MinFollowerCount {
+    pub x: i64,
+    pub youtube: i64,
+}
+
+/// Most items taken from one subreddit or search query per run. Requests ask for this many,
+/// within each API's page size limits (Reddit 1–100, X 10–100, YouTube 1–50), and anything
+/// beyond the cap is dropped before processing. Results are paged until the cap is reached,
+/// within `maxPagesPerQuery` for X and YouTube.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaxItemsPerQuery {
+    pub reddit: u32,
+    pub x: u32,
+    pub youtube: u32,
+}
+
+impl Default for MaxItemsPerQuery {
+    fn default() -> Self {
+        MaxItemsPerQuery {
+            reddit: 25,
+            x: 100,
+            youtube: 25,
+        }
+    }
+}
+
+/// Most result pages requested per search query per run. Paging also stops once
+/// `maxItemsPerQuery` is reached or the results run out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaxPagesPerQuery {
+    pub x: u32,
+    /// Each YouTube search page costs 100 quota units, so this stays low
+    pub youtube: u32,
+}
+
+impl Default for MaxPagesPerQuery {
+    fn default() -> Self {
+        MaxPagesPerQuery { x: 3, youtube: 2 }
+    }
+}
+
+/// Weights for blending trend-score signals. They are normalized by their sum, so only
+/// their ratios matter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrendScoreSettings {
+    #[serde(rename = "windowDays")]
+    pub window_days: u32,
+    #[serde(rename = "volumeWeight")]
+    pub volume_weight: f64,
+    #[serde(rename = "momentumWeight")]
+    pub momentum_weight: f64,
+    #[serde(rename = "recencyWeight")]
+    pub recency_weight: f64,
+    #[serde(rename = "engagementWeight")]
+    pub engagement_weight: f64,
+}
+
+impl Default for TrendScoreSettings {
+    fn default() -> Self {
+        TrendScoreSettings {
+            window_days: 7,
+            volume_weight: 0.4,
+            momentum_weight: 0.3,
+            recency_weight: 0.15,
+            engagement_weight: 0.15,
+        }
+    }
+}
+
+/// Sensitivity of generated alerts. A trend_spike needs at least `minAbsoluteCount` items
+/// in the last 24 hours and `trendSpikeRatio` times the topic's daily average over the
+/// week before, and a topic raises at most one per `cooldownHours`.
+/// Alert types missing from `enabledAlertTypes` are never raised. Notes are always allowed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertSettings {
+    #[serde(rename = "trendSpikeRatio")]
+    pub trend_spike_ratio: f64,
+    #[serde(rename = "minAbsoluteCount")]
+    pub min_absolute_count: i64,
+    #[serde(rename = "cooldownHours")]
+    pub cooldown_hours: u32,
+    #[serde(rename = "enabledAlertTypes")]
+    pub enabled_alert_types: Vec<String>,
+}
+
+impl AlertSettings {
+    pub fn is_enabled(&self, alert_type: &str) -> bool {
+        self.enabled_alert_types.iter().any(|t| t == alert_type)
+    }
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        AlertSettings {
+            trend_spike_ratio: 2.0,
+            min_absolute_count: 5,
+            cooldown_hours: 24,
+            enabled_alert_types: vec![
+                "trend_spike".to_string(),
+                "sentiment_shift".to_string(),
+                "topic_auto_created".to_string(),
+                "collection_stalled".to_string(),
+            ],
+        }
+    }
+}
+
+/// Trust multipliers for individual sources, applied to topic confidence. Reddit is
+/// keyed by subreddit name, X and YouTube by search query. Unlisted sources weigh 1.0.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SourceWeights {
+    pub reddit: HashMap<String, f64>,
+    pub x: HashMap<String, f64>,
+    pub youtube: HashMap<String, f64>,
+}
+
+impl SourceWeights {
+    pub fn weight_for(&self, platform: &str, source: &str) -> f64 {
+        let weights = match platform {
+            "reddit" => &self.reddit,
+            "x" => &self.x,
+            "youtube" => &self.youtube,
+            _ => return 1.0,
+        };
+
+        weights
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(source))
+            .map(|(_, weight)| weight.max(0.0))
+            .unwrap_or(1.0)
+    }
+}
+
+/// How many subreddits are fetched at once, and the overall request budget they share.
+/// Reddit allows roughly 60 requests per minute for OAuth clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedditFetchSettings {
+    pub concurrency: u32,
+    #[serde(rename = "requestsPerMinute")]
+    pub requests_per_minute: u32,
+    /// Also store the top comments of each newly collected post. Every post with comments
+    /// costs one more request from the same budget, so this is off by default.
+    #[serde(rename = "collectComments")]
+    pub collect_comments: bool,
+    /// Top-level comments stored per post when `collectComments` is on
+    #[serde(rename = "commentsPerPost")]
+    pub comments_per_post: u32,
+    /// Subreddit listing to read: hot, new, top, rising or controversial
+    pub sort: String,
+    /// Time window for the top and controversial listings: hour, day, week, month, year
+    /// or all
+    pub time: String,
+}
+
+impl Default for RedditFetchSettings {
+    fn default() -> Self {
+        RedditFetchSettings {
+            concurrency: 3,
+            requests_per_minute: 50,
+            collect_comments: false,
+            comments_per_post: 10,
+            sort: "hot".to_string(),
+            time: "day".to_string(),
+        }
+    }
+}
+
+/// How many video detail batches (50 ids each) are fetched at once, and the request rate
+/// they share. Every batch costs one quota unit whether or not it runs concurrently.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct YouTubeFetchSettings {
+    pub concurrency: u32,
+    #[serde(rename = "requestsPerMinute")]
+    pub requests_per_minute: u32,
+}
+
+impl Default for YouTubeFetchSettings {
+    fn default() -> Self {
+        YouTubeFetchSettings {
+            concurrency: 1,
+            requests_per_minute: 60,
+        }
+    }
+}
+
+/// Promote frequently used hashtags to topics. Off by default; capped per day so the
+/// taxonomy can't grow unchecked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoCreateTopicsSettings {
+    pub enabled: bool,
+    /// Distinct content items that must use a hashtag within the window
+    #[serde(rename = "minMentions")]
+    pub min_mentions: u32,
+    #[serde(rename = "windowHours")]
+    pub window_hours: u32,
+    #[serde(rename = "maxPerDay")]
+    pub max_per_day: u32,
+}
+
+impl Default for AutoCreateTopicsSettings {
+    fn default() -> Self {
+        AutoCreateTopicsSettings {
+            enabled: false,
+            min_mentions: 20,
+            window_hours: 24,
+            max_per_day: 3,
+        }
+    }
+}
+
+/// Cleaners applied to a platform's text before topic extraction
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TextCleaners {
+    #[serde(rename = "stripMarkdown")]
+    pub strip_markdown: bool,
+    #[serde(rename = "stripHtml")]
+    pub strip_html: bool,
+    #[serde(rename = "stripMentions")]
+    pub strip_mentions: bool,
+    #[serde(rename = "stripUrls")]
+    pub strip_urls: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreprocessingSettings {
+    pub reddit: TextCleaners,
+    pub x: TextCleaners,
+    pub youtube: TextCleaners,
+}
+
+impl PreprocessingSettings {
+    /// Cleaners for a platform's text; None for platforms without any
+    pub fn for_platform(&self, platform: &str) -> Option<&TextCleaners> {
+        match platform {
+            "reddit" => Some(&self.reddit),
+            "x" => Some(&self.x),
+            "youtube" => Some(&self.youtube),
+            _ => None,
+        }
+    }
+}
+
+impl Default for PreprocessingSettings {
+    fn default() -> Self {
+        PreprocessingSettings {
+            // Self posts are markdown
+            reddit: TextCleaners {
+                strip_markdown: true,
+                strip_urls: true,
+                ..TextCleaners::default()
+            },
+            // Replies start with @handles and links are t.co shorteners
+            x: TextCleaners {
+                strip_mentions: true,
+                strip_urls: true,
+                ..TextCleaners::default()
+            },
+            // Descriptions are full of affiliate and social links
+            youtube: TextCleaners {
+                strip_urls: true,
+                ..TextCleaners::default()
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppSettings {
     pub reddit: Option<RedditCredentials>,
@@ -36,15 +396,126 @@ pub struct AppSettings {
     #[serde(rename = "xQueries")]
     #[serde(default)]
     pub x_queries: Vec<String>,
+    /// Only collect tweets X detected as this language (e.g. "en"). None collects all.
+    #[serde(rename = "xLang")]
+    #[serde(default)]
+    pub x_lang: Option<String>,
     #[serde(rename = "youtubeQueries")]
     #[serde(default)]
     pub youtube_queries: Vec<String>,
     #[serde(rename = "searchQueries")]
     pub search_queries: Vec<String>,
+    #[serde(rename = "engagementRefresh")]
+    #[serde(default)]
+    pub engagement_refresh: EngagementRefreshSettings,
+    #[serde(default)]
+    pub extraction: ExtractionSettings,
+    #[serde(rename = "minFollowerCount")]
+    #[serde(default)]
+    pub min_follower_count: MinFollowerCount,
+    #[serde(rename = "maxItemsPerQuery")]
+    #[serde(default)]
+    pub max_items_per_query: MaxItemsPerQuery,
+    #[serde(rename = "maxPagesPerQuery")]
+    #[serde(default)]
+    pub max_pages_per_query: MaxPagesPerQuery,
+    #[serde(rename = "trendScore")]
+    #[serde(default)]
+    pub trend_score: TrendScoreSettings,
+    #[serde(default)]
+    pub alerts: AlertSettings,
+    /// Longest text_content stored per item, in characters. Titles are always kept whole.
+    #[serde(rename = "maxTextLength")]
+    #[serde(default = "default_max_text_length")]
+    pub max_text_length: usize,
+    /// Store media links and thumbnails alongside text
+    #[serde(rename = "storeMediaUrls")]
+    #[serde(default = "default_store_media_urls")]
+    pub store_media_urls: bool,
+    #[serde(rename = "sourceWeights")]
+    #[serde(default)]
+    pub source_weights: SourceWeights,
+    /// Consecutive empty runs on a platform before a collection_stalled alert; 0 disables
+    #[serde(rename = "stalledRunThreshold")]
+    #[serde(default = "default_stalled_run_threshold")]
+    pub stalled_run_threshold: u32,
+    #[serde(rename = "redditFetch")]
+    #[serde(default)]
+    pub reddit_fetch: RedditFetchSettings,
+    #[serde(rename = "youtubeFetch")]
+    #[serde(default)]
+    pub youtube_fetch: YouTubeFetchSettings,
+    /// Stop reading a listing once it reaches items older than the newest one already
+    /// stored for that source. Only effective for newest-first listings.
+    #[serde(rename = "onlyNewerContent")]
+    #[serde(default)]
+    pub only_newer_content: bool,
+    #[serde(rename = "autoCreateTopics")]
+    #[serde(default)]
+    pub auto_create_topics: AutoCreateTopicsSettings,
+    /// Allow looking up a creator's accounts on other platforms by shared handle
+    #[serde(rename = "creatorGrouping")]
+    #[serde(default)]
+    pub creator_grouping: bool,
+    /// Longest a collection run will sleep for a rate limit to reset before retrying.
+    /// Longer resets stop the platform's run and skip its remaining sources.
+    #[serde(rename = "rateLimitMaxWaitSecs")]
+    #[serde(default = "default_rate_limit_max_wait_secs")]
+    pub rate_limit_max_wait_secs: u64,
+    /// Flag content linking a page that earlier content already linked (cross-posts,
+    /// reposts) so it isn't counted twice. Both records are kept.
+    #[serde(rename = "dedupeByUrl")]
+    #[serde(default)]
+    pub dedupe_by_url: bool,
+    /// When set, an RSS feed of recent alerts is rewritten here whenever an alert is raised
+    #[serde(rename = "alertsRssPath")]
+    #[serde(default)]
+    pub alerts_rss_path: Option<String>,
+    /// Read alerts older than this many days are deleted after each collection run.
+    /// Unread alerts and manual notes are kept; 0 keeps everything.
+    #[serde(rename = "alertRetentionDays")]
+    #[serde(default = "default_alert_retention_days")]
+    pub alert_retention_days: u32,
+    /// Most subreddits or queries collected per platform in one run; 0 collects all.
+    /// Capped runs rotate through the full list across successive runs.
+    #[serde(rename = "maxQueriesPerRun")]
+    #[serde(default)]
+    pub max_queries_per_run: u32,
+    /// Keep fetched content that matches no topic. Turning this off keeps the database
+    /// to content about tracked topics only.
+    #[serde(rename = "storeUntaggedContent")]
+    #[serde(default = "default_store_untagged_content")]
+    pub store_untagged_content: bool,
+    #[serde(default)]
+    pub preprocessing: PreprocessingSettings,
+}
+
+fn default_max_text_length() -> usize {
+    10_000
+}
+
+fn default_store_media_urls() -> bool {
+    true
+}
+
+fn default_stalled_run_threshold() -> u32 {
+    3
+}
+
+fn default_rate_limit_max_wait_secs() -> u64 {
+    60
+}
+
+fn default_alert_retention_days() -> u32 {
+    30
+}
+
+fn default_store_untagged_content() -> bool {
+    true
 }
 
 impl AppSettings {
-    fn default_settings() -> Self {
+    pub(crate) fn default_settings() -> Self {
         AppSettings {
             reddit: None,
             x: None,
@@ -58,27 +529,48 @@ impl AppSettings {
                 "entrepreneur".to_string(),
             ],
             x_queries: vec![],
+            x_lang: None,
             youtube_queries: vec![],
             search_queries: vec![],
+            engagement_refresh: EngagementRefreshSettings::default(),
+            extraction: ExtractionSettings::default(),
+            min_follower_count: MinFollowerCount::default(),
+            max_items_per_query: MaxItemsPerQuery::default(),
+            max_pages_per_query: MaxPagesPerQuery::default(),
+            trend_score: TrendScoreSettings::default(),
+            alerts: AlertSettings::default(),
+            max_text_length: default_max_text_length(),
+            store_media_urls: default_store_media_urls(),
+            source_weights: SourceWeights::default(),
+            stalled_run_threshold: default_stalled_run_threshold(),
+            reddit_fetch: RedditFetchSettings::default(),
+            youtube_fetch: YouTubeFetchSettings::default(),
+            only_newer_content: false,
+            auto_create_topics: AutoCreateTopicsSettings::default(),
+            creator_grouping: false,
+            rate_limit_max_wait_secs: default_rate_limit_max_wait_secs(),
+            dedupe_by_url: false,
+            alerts_rss_path: None,
+            alert_retention_days: default_alert_retention_days(),
+            max_queries_per_run: 0,
+            store_untagged_content: default_store_untagged_content(),
+            preprocessing: PreprocessingSettings::default(),
         }
     }
 }
 
 fn get_settings_path() -> PathBuf {
-    if let Some(proj_dirs) = ProjectDirs::from("com", "trendr", "Trendr") {
-        let config_dir = proj_dirs.config_dir();
-        fs::create_dir_all(config_dir).ok();
-        config_dir.join("settings.json")
-    } else {
-        PathBuf::from("settings.json")
-    }
+    paths::config_dir().join("settings.json")
 }
 
 pub fn load_settings() -> AppSettings {
-    let path = get_settings_path();
+    load_settings_from(&get_settings_path())
+}
 
+/// Missing or unreadable files give the defaults
+fn load_settings_from(path: &Path) -> AppSettings {
     if path.exists() {
-        match fs::read_to_string(&path) {
+        match fs::read_to_string(path) {
             Ok(content) => {
                 serde_json::from_str(&content).unwrap_or_else(|_| AppSettings::default_settings())
             }
@@ -89,9 +581,88 @@ pub fn load_settings() -> AppSettings {
     }
 }
 
-pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
-    let path = get_settings_path();
-    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&path, content).map_err(|e| e.to_string())?;
+pub fn save_settings(settings: &AppSettings) -> Result<(), TrendrError> {
+    save_settings_to(settings, &get_settings_path())
+}
+
+fn save_settings_to(settings: &AppSettings, path: &Path) -> Result<(), TrendrError> {
+    let content = serde_json::to_string_pretty(settings)?;
+    fs::write(path, content)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_settings_path() -> PathBuf {
+        std::env::temp_dir().join(format!("trendr-settings-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn credentials_for_every_platform_survive_a_save_and_load() {
+        let mut settings = AppSettings::default_settings();
+        settings.reddit = Some(RedditCredentials {
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            username: "user".to_string(),
+            password: "hunter2".to_string(),
+        });
+        settings.x = Some(XCredentials {
+            bearer_token: "bearer".to_string(),
+        });
+        settings.youtube = Some(YouTubeCredentials {
+            api_key: "key".to_string(),
+        });
+        settings.x_queries = vec!["rust lang".to_string()];
+        settings.youtube_queries = vec!["rust tutorial".to_string()];
+
+        let path = temp_settings_path();
+        save_settings_to(&settings, &path).unwrap();
+        let loaded = load_settings_from(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&settings).unwrap()
+        );
+        assert_eq!(loaded.x.unwrap().bearer_token, "bearer");
+        assert_eq!(loaded.youtube.unwrap().api_key, "key");
+    }
+
+    #[test]
+    fn older_files_without_newer_keys_still_load() {
+        let path = temp_settings_path();
+        fs::write(
+            &path,
+            r#"{
+                "reddit": { "clientId": "client", "clientSecret": "secret" },
+                "collectionIntervalMinutes": 15,
+                "subreddits": ["rust"],
+                "searchQueries": []
+            }"#,
+        )
+        .unwrap();
+        let loaded = load_settings_from(&path);
+        fs::remove_file(&path).ok();
+
+        // Fields from the file rather than the defaults, so it parsed
+        assert_eq!(loaded.collection_interval_minutes, 15);
+        assert_eq!(loaded.subreddits, vec!["rust".to_string()]);
+        assert!(loaded.reddit.unwrap().is_app_only());
+
+        assert!(loaded.x.is_none());
+        assert!(loaded.youtube.is_none());
+        assert!(loaded.x_queries.is_empty());
+        assert_eq!(loaded.max_text_length, default_max_text_length());
+        assert!(loaded.store_untagged_content);
+        assert!(loaded.preprocessing.x.strip_mentions);
+    }
+
+    #[test]
+    fn missing_file_gives_defaults() {
+        let loaded = load_settings_from(&temp_settings_path());
+        assert_eq!(loaded.collection_interval_minutes, 30);
+        assert!(loaded.reddit.is_none());
+    }
+}
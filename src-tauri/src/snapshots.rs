@@ -0,0 +1,253 @@
+use crate::content::status;
+use crate::database::with_db;
+use crate::error::TrendrError;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Window for the "recent" count stored with each topic
+const RECENT_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotTopic {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    pub name: String,
+    #[serde(rename = "contentCount")]
+    pub content_count: i64,
+    #[serde(rename = "recentCount")]
+    pub recent_count: i64,
+    #[serde(rename = "trendScore")]
+    pub trend_score: Option<f64>,
+}
+
+/// Topic stats frozen under a user label for before/after comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedSnapshot {
+    pub label: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "totalContent")]
+    pub total_content: i64,
+    pub topics: Vec<SnapshotTopic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotSummary {
+    pub label: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "totalContent")]
+    pub total_content: i64,
+    #[serde(rename = "topicCount")]
+    pub topic_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicDelta {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    pub name: String,
+    #[serde(rename = "contentBefore")]
+    pub content_before: i64,
+    #[serde(rename = "contentNow")]
+    pub content_now: i64,
+    #[serde(rename = "contentDelta")]
+    pub content_delta: i64,
+    #[serde(rename = "recentBefore")]
+    pub recent_before: i64,
+    #[serde(rename = "recentNow")]
+    pub recent_now: i64,
+    #[serde(rename = "trendScoreBefore")]
+    pub trend_score_before: Option<f64>,
+    #[serde(rename = "trendScoreNow")]
+    pub trend_score_now: Option<f64>,
+    /// Topic didn't exist when the snapshot was pinned
+    #[serde(rename = "isNew")]
+    pub is_new: bool,
+    /// Topic has been deleted since the snapshot was pinned
+    #[serde(rename = "isRemoved")]
+    pub is_removed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotComparison {
+    pub label: String,
+    #[serde(rename = "pinnedAt")]
+    pub pinned_at: String,
+    #[serde(rename = "totalContentBefore")]
+    pub total_content_before: i64,
+    #[serde(rename = "totalContentNow")]
+    pub total_content_now: i64,
+    /// Largest content growth first
+    pub topics: Vec<TopicDelta>,
+}
+
+fn current_state(conn: &Connection) -> Result<(i64, Vec<SnapshotTopic>), rusqlite::Error> {
+    let total_content: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM content WHERE status != ?1",
+        params![status::DELETED],
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(
+        r#"SELECT t.id, t.name, COUNT(c.id),
+           COALESCE(SUM(CASE WHEN COALESCE(datetime(c.published_at), datetime(c.collected_at)) > datetime('now', ?2) THEN 1 ELSE 0 END), 0),
+           s.score
+           FROM topics t
+           LEFT JOIN content_topics ct ON ct.topic_id = t.id
+           LEFT JOIN content c ON c.id = ct.content_id AND c.status != ?1
+           LEFT JOIN topic_trend_scores s ON s.topic_id = t.id
+           GROUP BY t.id
+           ORDER BY COUNT(c.id) DESC"#,
+    )?;
+    let rows = stmt.query_map(
+        params![status::DELETED, format!("-{} days", RECENT_DAYS)],
+        |row| {
+            Ok(SnapshotTopic {
+                topic_id: row.get(0)?,
+                name: row.get(1)?,
+                content_count: row.get(2)?,
+                recent_count: row.get(3)?,
+                trend_score: row.get(4)?,
+            })
+        },
+    )?;
+
+    let topics = rows.collect::<Result<Vec<_>, _>>()?;
+    Ok((total_content, topics))
+}
+
+/// Store the current per-topic stats under `label`, replacing any snapshot with that label.
+pub fn pin_snapshot(label: &str) -> Result<SnapshotSummary, TrendrError> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(TrendrError::Other("Snapshot label cannot be empty".to_string()));
+    }
+
+    with_db(|conn| {
+        let (total_content, topics) = current_state(conn)?;
+        let snapshot = PinnedSnapshot {
+            label: label.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            total_content,
+            topics,
+        };
+        let data = serde_json::to_string(&snapshot)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        conn.execute(
+            r#"INSERT INTO pinned_snapshots (label, data, created_at) VALUES (?1, ?2, ?3)
+               ON CONFLICT(label) DO UPDATE SET data = excluded.data, created_at = excluded.created_at"#,
+            params![snapshot.label, data, snapshot.created_at],
+        )?;
+
+        Ok(SnapshotSummary {
+            topic_count: snapshot.topics.len(),
+            label: snapshot.label,
+            created_at: snapshot.created_at,
+            total_content,
+        })
+    })
+}
+
+pub fn list_snapshots() -> Result<Vec<SnapshotSummary>, TrendrError> {
+    let snapshots = with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT data FROM pinned_snapshots ORDER BY created_at DESC")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<Result<Vec<_>, _>>()
+    })?;
+
+    Ok(snapshots
+        .iter()
+        .filter_map(|data| parse_snapshot(data))
+        .map(|snapshot| SnapshotSummary {
+            topic_count: snapshot.topics.len(),
+            label: snapshot.label,
+            created_at: snapshot.created_at,
+            total_content: snapshot.total_content,
+        })
+        .collect())
+}
+
+/// Per-topic changes between the snapshot pinned as `label` and the current state.
+pub fn compare_snapshot(label: &str) -> Result<SnapshotComparison, TrendrError> {
+    let (data, (total_now, topics_now)) = with_db(|conn| {
+        let data: Option<String> = conn
+            .query_row(
+                "SELECT data FROM pinned_snapshots WHERE label = ?1",
+                params![label],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok((data, current_state(conn)?))
+    })?;
+
+    let data = data.ok_or_else(|| TrendrError::Other(format!("No snapshot pinned as '{}'", label)))?;
+    let before = parse_snapshot(&data).ok_or_else(|| TrendrError::Parse(format!("Snapshot '{}' is corrupt", label)))?;
+
+    let mut previous: HashMap<String, SnapshotTopic> = before
+        .topics
+        .into_iter()
+        .map(|topic| (topic.topic_id.clone(), topic))
+        .collect();
+
+    let mut deltas: Vec<TopicDelta> = topics_now
+        .into_iter()
+        .map(|now| {
+            let was = previous.remove(&now.topic_id);
+            let content_before = was.as_ref().map(|t| t.content_count).unwrap_or(0);
+            TopicDelta {
+                content_delta: now.content_count - content_before,
+                content_before,
+                content_now: now.content_count,
+                recent_before: was.as_ref().map(|t| t.recent_count).unwrap_or(0),
+                recent_now: now.recent_count,
+                trend_score_before: was.as_ref().and_then(|t| t.trend_score),
+                trend_score_now: now.trend_score,
+                is_new: was.is_none(),
+                is_removed: false,
+                topic_id: now.topic_id,
+                name: now.name,
+            }
+        })
+        .collect();
+
+    deltas.extend(previous.into_values().map(|was| TopicDelta {
+        content_delta: -was.content_count,
+        content_before: was.content_count,
+        content_now: 0,
+        recent_before: was.recent_count,
+        recent_now: 0,
+        trend_score_before: was.trend_score,
+        trend_score_now: None,
+        is_new: false,
+        is_removed: true,
+        topic_id: was.topic_id,
+        name: was.name,
+    }));
+
+    deltas.sort_by(|a, b| b.content_delta.cmp(&a.content_delta).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(SnapshotComparison {
+        label: before.label,
+        pinned_at: before.created_at,
+        total_content_before: before.total_content,
+        total_content_now: total_now,
+        topics: deltas,
+    })
+}
+
+/// Remove a pinned snapshot. Returns whether one existed.
+pub fn delete_snapshot(label: &str) -> Result<bool, TrendrError> {
+    with_db(|conn| {
+        let removed = conn.execute("DELETE FROM pinned_snapshots WHERE label = ?1", params![label])?;
+        Ok(removed > 0)
+    })
+}
+
+fn parse_snapshot(data: &str) -> Option<PinnedSnapshot> {
+    serde_json::from_str(data)
+        .map_err(|e| log::warn!("Skipping unreadable pinned snapshot: {}", e))
+        .ok()
+}
@@ -1,7 +1,14 @@
-use crate::database::with_db;
-use regex::Regex;
-use rusqlite::params;
+use crate::content::status;
+use crate::database::{with_db, with_db_tx};
+use crate::error::TrendrError;
+use crate::preprocess;
+use crate::sentiment;
+use crate::settings::{self, AppSettings, ExtractionSettings};
+use regex::{Regex, RegexSet};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedTopic {
@@ -11,57 +18,478 @@ pub struct ExtractedTopic {
     pub mentions: u32,
 }
 
+/// A saved topic keyword. Stored as a bare string, or as `{ "term", "weight" }` when it
+/// should count for more or less than an ordinary keyword toward confidence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Keyword {
+    Plain(String),
+    Weighted { term: String, weight: f64 },
+}
+
+impl Keyword {
+    pub fn term(&self) -> &str {
+        match self {
+            Keyword::Plain(term) | Keyword::Weighted { term, .. } => term,
+        }
+    }
+
+    pub fn weight(&self) -> f64 {
+        match self {
+            Keyword::Plain(_) => 1.0,
+            Keyword::Weighted { weight, .. } => *weight,
+        }
+    }
+}
+
+/// A topic's matching data. Load once with `load_topics` and reuse it across items.
 #[derive(Debug)]
-struct TopicData {
+pub struct TopicData {
     id: String,
     name: String,
-    keywords: Vec<String>,
+    /// Keywords, then aliases, paired with their compiled word-boundary patterns. Both
+    /// count as mentions of the topic.
+    terms: Vec<(String, Regex)>,
+    /// How much a match of each of `terms` counts toward confidence
+    term_weights: Vec<f64>,
+    /// How many of `terms` are keywords; the rest are aliases
+    keyword_count: usize,
+    /// Every term pattern in one set, so a topic with no hits is ruled out in a single
+    /// pass. None if the set was too large to compile; every term is then tried.
+    term_set: Option<RegexSet>,
+    /// Terms that rule the topic out when any of them appears, e.g. "pie" for a company
+    /// that shares its name with a fruit. None if the topic has none or they couldn't
+    /// be compiled.
+    exclusion_set: Option<RegexSet>,
+}
+
+impl TopicData {
+    fn compile(
+        id: String,
+        name: String,
+        keywords: Vec<Keyword>,
+        aliases: Vec<String>,
+        exclusions: Vec<String>,
+    ) -> TopicData {
+        let mut terms: Vec<(String, Regex)> = Vec::with_capacity(keywords.len() + aliases.len());
+        let mut term_weights = Vec::with_capacity(keywords.len() + aliases.len());
+        let mut keyword_count = 0;
+        let keyword_total = keywords.len();
+
+        // Aliases are saved as typed, so they get the same cleanup keywords get on save
+        let keywords = keywords.iter().map(|keyword| (bare_term(keyword.term()), keyword.weight()));
+        let aliases = aliases.iter().map(|alias| (bare_term(&normalize(alias)), ALIAS_WEIGHT));
+        for (index, (term, weight)) in keywords.chain(aliases).enumerate() {
+            if term.is_empty() || terms.iter().any(|(existing, _)| *existing == term) {
+                continue;
+            }
+            match Regex::new(&keyword_pattern(&term)) {
+                Ok(regex) => {
+                    terms.push((term, regex));
+                    term_weights.push(weight);
+                    if index < keyword_total {
+                        keyword_count += 1;
+                    }
+                }
+                Err(e) => log::warn!("Topic {} term {:?} can't be matched: {}", id, term, e),
+            }
+        }
+
+        let term_set = match RegexSet::new(terms.iter().map(|(_, regex)| regex.as_str())) {
+            Ok(set) => Some(set),
+            Err(e) => {
+                log::warn!("Topic {} terms can't be combined, matching one by one: {}", id, e);
+                None
+            }
+        };
+
+        let exclusion_patterns: Vec<String> = exclusions
+            .iter()
+            .map(|term| normalize(term))
+            .filter(|term| !term.is_empty())
+            .map(|term| keyword_pattern(&term))
+            .collect();
+        let exclusion_set = if exclusion_patterns.is_empty() {
+            None
+        } else {
+            match RegexSet::new(&exclusion_patterns) {
+                Ok(set) => Some(set),
+                Err(e) => {
+                    log::warn!("Topic {} exclusions can't be matched: {}", id, e);
+                    None
+                }
+            }
+        };
+
+        TopicData {
+            id,
+            name,
+            terms,
+            term_weights,
+            keyword_count,
+            term_set,
+            exclusion_set,
+        }
+    }
+
+    fn keywords(&self) -> &[(String, Regex)] {
+        &self.terms[..self.keyword_count]
+    }
+
+    /// Whether any exclusion term appears in any field
+    fn is_excluded(&self, fields: &[(Field, String)]) -> bool {
+        self.exclusion_set
+            .as_ref()
+            .is_some_and(|set| fields.iter().any(|(_, text)| set.is_match(text)))
+    }
+
+    /// Indices into `terms` that match at least one field, in term order
+    fn matching_terms(&self, fields: &[(Field, String)]) -> Vec<usize> {
+        let Some(set) = &self.term_set else {
+            return (0..self.terms.len()).collect();
+        };
+
+        let mut matched = vec![false; self.terms.len()];
+        for (_, text) in fields {
+            for index in set.matches(text) {
+                matched[index] = true;
+            }
+        }
+        (0..matched.len()).filter(|&index| matched[index]).collect()
+    }
+}
+
+/// A term without its leading '#' or '$', so a keyword saved as "#ai" or "$btc" matches
+/// the tag and the plain word alike. Bare keywords already match inside tags, since the
+/// prefix is a word boundary.
+fn bare_term(term: &str) -> String {
+    term.trim_start_matches(['#', '$']).to_string()
+}
+
+/// Regex matching a keyword as whole words. The words of a phrase may be separated by any
+/// run of whitespace or punctuation, so "side hustle" also matches "side-hustle" and
+/// "side   hustle", but never "sidehustle".
+fn keyword_pattern(keyword: &str) -> String {
+    let words: Vec<String> = keyword.split_whitespace().map(regex::escape).collect();
+    format!(r"\b{}\b", words.join(r"[\s\p{P}]+"))
 }
 
-pub fn extract_topics(text: &str) -> Result<Vec<ExtractedTopic>, String> {
-    let topics = load_topics()?;
-    let normalized_text = text.to_lowercase();
-    let mut extracted: Vec<ExtractedTopic> = Vec::new();
+/// Maximum number of topics linked to a single piece of content
+const MAX_TOPICS_PER_CONTENT: usize = 5;
 
+/// One topic's matches against a piece of text, with the keywords that produced them.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicMatch {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    #[serde(rename = "keywordHits")]
+    pub keyword_hits: Vec<KeywordHit>,
+    pub mentions: u32,
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordHit {
+    pub keyword: String,
+    pub count: u32,
+}
+
+/// Debug view of the extraction pipeline for arbitrary text. Nothing is written.
+#[derive(Debug, Clone, Serialize)]
+pub struct TextAnalysis {
+    #[serde(rename = "normalizedText")]
+    pub normalized_text: String,
+    /// Every topic with at least one hit, best first
+    pub matches: Vec<TopicMatch>,
+    /// The subset that extract_topics would actually link
+    #[serde(rename = "linkedTopicIds")]
+    pub linked_topic_ids: Vec<String>,
+    pub sentiment: Option<f64>,
+}
+
+/// Which part of a piece of content a chunk of text came from. Title and body matches
+/// are scaled by the configured extraction weights; plain text counts at face value.
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    Text,
+    Title,
+    Body,
+}
+
+/// Extract topics from one piece of text, loading the topic list for this call only.
+/// Callers handling many items should load topics once and use `extract_topics_with`.
+#[allow(dead_code)]
+pub fn extract_topics(text: &str) -> Result<Vec<ExtractedTopic>, TrendrError> {
+    extract_topics_with(&load_topics()?, &settings::load_settings().extraction, text)
+}
+
+pub fn extract_topics_with(
+    topics: &[TopicData],
+    config: &ExtractionSettings,
+    text: &str,
+) -> Result<Vec<ExtractedTopic>, TrendrError> {
+    extract_from_fields(topics, config, &[(Field::Text, normalize(text))])
+}
+
+/// Extract topics from content with a separate title and body, so title matches can
+/// count for more than body matches.
+pub fn extract_topics_weighted_with(
+    topics: &[TopicData],
+    config: &ExtractionSettings,
+    title: &str,
+    body: &str,
+) -> Result<Vec<ExtractedTopic>, TrendrError> {
+    extract_from_fields(
+        topics,
+        config,
+        &[(Field::Title, normalize(title)), (Field::Body, normalize(body))],
+    )
+}
+
+fn extract_from_fields(
+    topics: &[TopicData],
+    config: &ExtractionSettings,
+    fields: &[(Field, String)],
+) -> Result<Vec<ExtractedTopic>, TrendrError> {
+    let matches = match_topics(topics, config, fields)?;
+
+    Ok(matches
+        .into_iter()
+        .take(MAX_TOPICS_PER_CONTENT)
+        .map(|m| ExtractedTopic {
+            topic_id: m.topic_id,
+            topic_name: m.topic_name,
+            confidence: m.confidence,
+            mentions: m.mentions,
+        })
+        .collect())
+}
+
+/// How a collected item's text is laid out for matching.
+pub enum ItemText<'a> {
+    /// One block of text, such as a tweet
+    Plain(&'a str),
+    /// A title and body, scaled by the configured title and body weights. Items without
+    /// a title, such as Reddit comments, pass an empty one.
+    Titled { title: &'a str, body: &'a str },
+}
+
+/// Extract topics from one collected item the way collection does: clean its text with
+/// the platform's preprocessing, match it, and scale by the trust weight of the source
+/// it came from. Items from an unknown source weigh 1.0.
+pub fn extract_item_topics(
+    topics: &[TopicData],
+    settings: &AppSettings,
+    platform: &str,
+    source: Option<&str>,
+    text: ItemText,
+) -> Result<Vec<ExtractedTopic>, TrendrError> {
+    let cleaners = settings.preprocessing.for_platform(platform);
+    let clean = |text: &str| match cleaners {
+        Some(cleaners) => preprocess::clean(text, cleaners),
+        None => text.to_string(),
+    };
+
+    let mut found = match text {
+        ItemText::Plain(text) => extract_topics_with(topics, &settings.extraction, &clean(text))?,
+        ItemText::Titled { title, body } => {
+            extract_topics_weighted_with(topics, &settings.extraction, &clean(title), &clean(body))?
+        }
+    };
+    if let Some(source) = source {
+        apply_source_weight(
+            &mut found,
+            settings.source_weights.weight_for(platform, source),
+        );
+    }
+    Ok(found)
+}
+
+/// Scale confidences by a source's trust weight, keeping them within 0–1.
+pub fn apply_source_weight(topics: &mut [ExtractedTopic], weight: f64) {
     for topic in topics {
-        let mut match_count = 0u32;
+        topic.confidence = (topic.confidence * weight).clamp(0.0, 1.0);
+    }
+}
+
+pub fn analyze_text(text: &str) -> Result<TextAnalysis, TrendrError> {
+    let normalized_text = normalize(text);
+    let config = settings::load_settings().extraction;
+    let matches = match_topics(&load_topics()?, &config, &[(Field::Text, normalized_text.clone())])?;
+    let linked_topic_ids = matches
+        .iter()
+        .take(MAX_TOPICS_PER_CONTENT)
+        .map(|m| m.topic_id.clone())
+        .collect();
+
+    Ok(TextAnalysis {
+        normalized_text,
+        matches,
+        linked_topic_ids,
+        sentiment: sentiment::score(text),
+    })
+}
+
+/// Lowercase and collapse whitespace runs to single spaces
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn field_weight(config: &ExtractionSettings, field: Field) -> f64 {
+    match field {
+        Field::Text => 1.0,
+        Field::Title => config.title_weight,
+        Field::Body => config.body_weight,
+    }
+}
+
+/// Aliases are looser names for a topic, so each alias match counts for this fraction
+/// of an ordinary keyword match
+const ALIAS_WEIGHT: f64 = 0.5;
+
+/// Weighted matches per 100 words at which confidence reaches 1
+const FULL_CONFIDENCE_DENSITY: f64 = 10.0;
+
+/// Match every topic's keywords and aliases against already-normalized fields, sorted by
+/// confidence descending. Confidence is the weighted mention count per 100 words, so a
+/// passing mention in a long post scores lower than the same mention in a short one.
+/// Topics with an exclusion term in the text are left out entirely.
+fn match_topics(
+    topics: &[TopicData],
+    config: &ExtractionSettings,
+    fields: &[(Field, String)],
+) -> Result<Vec<TopicMatch>, TrendrError> {
+    let word_count: usize = fields.iter().map(|(_, text)| text.split_whitespace().count()).sum();
+    let mut matches: Vec<TopicMatch> = Vec::new();
 
-        for keyword in &topic.keywords {
-            let pattern = format!(r"\b{}\b", regex::escape(keyword));
-            if let Ok(regex) = Regex::new(&pattern) {
-                match_count += regex.find_iter(&normalized_text).count() as u32;
+    for topic in topics {
+        if topic.is_excluded(fields) {
+            continue;
+        }
+
+        let mut field_spans: Vec<Vec<Span>> = vec![Vec::new(); fields.len()];
+        let mut keyword_hits = Vec::new();
+
+        for index in topic.matching_terms(fields) {
+            let (keyword, regex) = &topic.terms[index];
+            let term_weight = topic.term_weights[index];
+            let mut count = 0u32;
+            for (spans, (_, text)) in field_spans.iter_mut().zip(fields) {
+                let before = spans.len();
+                spans.extend(regex.find_iter(text).map(|m| (m.start(), m.end(), term_weight)));
+                count += (spans.len() - before) as u32;
+            }
+
+            if count > 0 {
+                keyword_hits.push(KeywordHit {
+                    keyword: keyword.clone(),
+                    count,
+                });
             }
         }
 
-        if match_count > 0 {
-            let confidence = (match_count as f64 * 0.2).min(1.0);
+        let mut match_count = 0u32;
+        let mut weighted_count = 0.0;
+        for (spans, (field, _)) in field_spans.into_iter().zip(fields) {
+            let (count, weight) = if config.dedupe_overlapping_matches {
+                count_distinct_spans(spans)
+            } else {
+                (spans.len() as u32, spans.iter().map(|&(_, _, weight)| weight).sum())
+            };
+            match_count += count;
+            weighted_count += weight * field_weight(config, *field);
+        }
 
-            extracted.push(ExtractedTopic {
-                topic_id: topic.id,
-                topic_name: topic.name,
-                confidence,
+        if match_count > 0 && weighted_count > 0.0 {
+            matches.push(TopicMatch {
+                topic_id: topic.id.clone(),
+                topic_name: topic.name.clone(),
+                keyword_hits,
                 mentions: match_count,
+                confidence: confidence_score(weighted_count, word_count),
             });
         }
     }
 
-    // Sort by confidence descending and take top 5
-    extracted.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
-    extracted.truncate(5);
+    // Ties fall back to mentions, then name, so the same text always links the same topics
+    matches.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| b.mentions.cmp(&a.mentions))
+            .then_with(|| a.topic_name.cmp(&b.topic_name))
+    });
 
-    Ok(extracted)
+    Ok(matches)
 }
 
-fn load_topics() -> Result<Vec<TopicData>, String> {
+/// Weighted matches per 100 words, scaled so `FULL_CONFIDENCE_DENSITY` scores 1 and
+/// clamped to 0–1. A NaN (say, from a bad field weight in settings) scores 0.
+fn confidence_score(weighted_count: f64, word_count: usize) -> f64 {
+    let per_100_words = weighted_count * 100.0 / word_count.max(1) as f64;
+    let confidence = per_100_words / FULL_CONFIDENCE_DENSITY;
+    if confidence.is_nan() {
+        0.0
+    } else {
+        confidence.clamp(0.0, 1.0)
+    }
+}
+
+/// A match's (start, end) byte range and the weight of the term that produced it
+type Span = (usize, usize, f64);
+
+/// Count matches after merging overlapping byte ranges, so the same piece of text matched
+/// by several keywords only counts once. Returns the count and the summed weight, where a
+/// merged match takes the weight of its strongest term.
+fn count_distinct_spans(mut spans: Vec<Span>) -> (u32, f64) {
+    spans.sort_unstable_by_key(|s| (s.0, s.1));
+
+    let mut count = 0u32;
+    let mut weight = 0.0;
+    let mut current: Option<(usize, f64)> = None;
+    for (start, end, term_weight) in spans {
+        match current {
+            Some((current_end, current_weight)) if start < current_end => {
+                current = Some((current_end.max(end), current_weight.max(term_weight)));
+            }
+            _ => {
+                if let Some((_, current_weight)) = current {
+                    weight += current_weight;
+                }
+                count += 1;
+                current = Some((end, term_weight));
+            }
+        }
+    }
+    if let Some((_, current_weight)) = current {
+        weight += current_weight;
+    }
+    (count, weight)
+}
+
+/// Times `load_topics` has run, so tests can check it runs once per collection
+#[cfg(test)]
+pub static LOAD_TOPICS_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+pub fn load_topics() -> Result<Vec<TopicData>, TrendrError> {
+    #[cfg(test)]
+    LOAD_TOPICS_CALLS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
     with_db(|conn| {
-        let mut stmt = conn.prepare("SELECT id, name, keywords FROM topics")?;
+        let mut stmt = conn.prepare("SELECT id, name, keywords, aliases, exclusions FROM topics")?;
         let rows = stmt.query_map([], |row| {
             let id: String = row.get(0)?;
             let name: String = row.get(1)?;
-            let keywords_json: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
-            let keywords: Vec<String> = serde_json::from_str(&keywords_json).unwrap_or_default();
+            let keywords_json: Option<String> = row.get(2)?;
+            let keywords = parse_keywords(keywords_json.as_deref(), &id);
+            let aliases_json: Option<String> = row.get(3)?;
+            let aliases = parse_string_list(aliases_json.as_deref(), &id, "aliases");
 
-            Ok(TopicData { id, name, keywords })
+            let exclusions_json: Option<String> = row.get(4)?;
+            let exclusions = parse_string_list(exclusions_json.as_deref(), &id, "exclusions");
+
+            Ok(TopicData::compile(id, name, keywords, aliases, exclusions))
         })?;
 
         let mut topics = Vec::new();
@@ -71,3 +499,914 @@ fn load_topics() -> Result<Vec<TopicData>, String> {
         Ok(topics)
     })
 }
+
+/// Most recent content items scanned by `keyword_stats`
+const KEYWORD_STATS_SAMPLE: i64 = 20_000;
+
+/// How often one topic keyword has matched stored content.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordStat {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    pub keyword: String,
+    /// Scanned content items containing the keyword at least once
+    #[serde(rename = "matchCount")]
+    pub match_count: i64,
+    /// Published (or collected) time of the newest scanned item that matched
+    #[serde(rename = "lastMatchedAt")]
+    pub last_matched_at: Option<String>,
+}
+
+/// Keyword match counts for every topic. On large databases only the most recent
+/// `scannedContent` of `totalContent` items are scanned, and `sampled` is set; counts and
+/// dates then describe that recent slice, not all history.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordStats {
+    pub keywords: Vec<KeywordStat>,
+    #[serde(rename = "scannedContent")]
+    pub scanned_content: i64,
+    #[serde(rename = "totalContent")]
+    pub total_content: i64,
+    pub sampled: bool,
+}
+
+/// Match every topic keyword against stored content, the same way extraction does, to
+/// find keywords that never match or match far too much. Topics are ordered by name and
+/// keywords keep their saved order.
+pub fn keyword_stats() -> Result<KeywordStats, TrendrError> {
+    let mut topics = load_topics()?;
+    topics.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let (total_content, content) = with_db(|conn| {
+        let total: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM content WHERE text_content IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT text_content, COALESCE(datetime(published_at), datetime(collected_at)) as at
+               FROM content
+               WHERE text_content IS NOT NULL
+               ORDER BY at DESC
+               LIMIT ?1"#,
+        )?;
+        let rows = stmt.query_map(params![KEYWORD_STATS_SAMPLE], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+
+        let mut content = Vec::new();
+        for row in rows {
+            let (text, at) = row?;
+            content.push((normalize(&text), at));
+        }
+        Ok((total, content))
+    })?;
+
+    let mut keywords = Vec::new();
+    for topic in &topics {
+        for (keyword, regex) in topic.keywords() {
+            let mut match_count = 0i64;
+            let mut last_matched_at: Option<&String> = None;
+            for (text, at) in &content {
+                if regex.is_match(text) {
+                    match_count += 1;
+                    if let Some(at) = at {
+                        last_matched_at = Some(last_matched_at.map_or(at, |last| last.max(at)));
+                    }
+                }
+            }
+
+            keywords.push(KeywordStat {
+                topic_id: topic.id.clone(),
+                topic_name: topic.name.clone(),
+                keyword: keyword.clone(),
+                match_count,
+                last_matched_at: last_matched_at.cloned(),
+            });
+        }
+    }
+
+    let scanned_content = content.len() as i64;
+    Ok(KeywordStats {
+        keywords,
+        scanned_content,
+        total_content,
+        sampled: total_content > scanned_content,
+    })
+}
+
+/// Most recent linked content items scanned by `suggest_keywords`
+const SUGGESTION_SAMPLE: i64 = 2_000;
+
+/// Shortest word `suggest_keywords` will offer
+const MIN_SUGGESTION_LENGTH: usize = 3;
+
+/// Fewest linked content items a word must appear in to be suggested
+const MIN_SUGGESTION_CONTENT: i64 = 2;
+
+/// Common words (and URL fragments) that appear in every topic's content
+const SUGGESTION_STOPWORDS: &[&str] = &[
+    "about", "after", "again", "all", "also", "and", "any", "are", "because", "been", "before",
+    "being", "but", "can", "com", "could", "did", "does", "doing", "don", "down", "each", "even",
+    "few", "for", "from", "get", "got", "had", "has", "have", "her", "here", "him", "his", "how",
+    "http", "https", "into", "its", "just", "know", "like", "make", "many", "more", "most", "much",
+    "now", "off", "one", "only", "other", "our", "out", "over", "own", "really", "same", "see",
+    "she", "should", "some", "still", "such", "than", "that", "the", "their", "them", "then",
+    "there", "these", "they", "thing", "think", "this", "those", "through", "too", "under", "very",
+    "want", "was", "way", "well", "were", "what", "when", "where", "which", "while", "who", "why",
+    "will", "with", "would", "www", "yes", "you", "your",
+];
+
+/// A word common in a topic's content that none of its keywords, aliases or exclusions
+/// cover yet.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordSuggestion {
+    pub term: String,
+    /// Scanned content items using the word at least once
+    #[serde(rename = "contentCount")]
+    pub content_count: i64,
+    /// Total uses across those items
+    pub occurrences: i64,
+}
+
+/// Words that show up across a topic's linked content but aren't keywords yet, most
+/// widespread first. Only the most recent linked items are scanned. Nothing is written.
+pub fn suggest_keywords(topic_id: &str, limit: usize) -> Result<Vec<KeywordSuggestion>, TrendrError> {
+    let (known, texts) = with_db(|conn| {
+        let columns: Option<(Option<String>, Option<String>, Option<String>)> = conn
+            .query_row(
+                "SELECT keywords, aliases, exclusions FROM topics WHERE id = ?1",
+                params![topic_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let Some((keywords_json, aliases_json, exclusions_json)) = columns else {
+            return Ok((None, Vec::new()));
+        };
+
+        // Every word of every term, so the halves of "side hustle" aren't offered either
+        let mut known: HashSet<String> = HashSet::new();
+        let terms = parse_keywords(keywords_json.as_deref(), topic_id)
+            .iter()
+            .map(|keyword| keyword.term().to_string())
+            .chain(parse_string_list(aliases_json.as_deref(), topic_id, "aliases"))
+            .chain(parse_string_list(exclusions_json.as_deref(), topic_id, "exclusions"))
+            .collect::<Vec<_>>();
+        for term in &terms {
+            known.extend(words(&normalize(term)).map(str::to_string));
+        }
+
+        let mut stmt = conn.prepare(
+            r#"SELECT c.text_content
+               FROM content_topics ct
+               JOIN content c ON c.id = ct.content_id
+               WHERE ct.topic_id = ?1 AND c.status != ?2 AND c.text_content IS NOT NULL
+               ORDER BY COALESCE(datetime(c.published_at), datetime(c.collected_at)) DESC
+               LIMIT ?3"#,
+        )?;
+        let rows = stmt.query_map(params![topic_id, status::DELETED, SUGGESTION_SAMPLE], |row| {
+            row.get::<_, String>(0)
+        })?;
+        let texts = rows.collect::<Result<Vec<_>, _>>()?;
+
+        Ok((Some(known), texts))
+    })?;
+    let known = known.ok_or_else(|| TrendrError::Other(format!("Topic not found: {}", topic_id)))?;
+
+    let mut counts: HashMap<String, (i64, i64)> = HashMap::new();
+    for text in &texts {
+        let text = normalize(text);
+        let mut seen_here: HashSet<&str> = HashSet::new();
+        for word in words(&text) {
+            if word.chars().count() < MIN_SUGGESTION_LENGTH
+                || word.chars().all(|c| c.is_ascii_digit())
+                || SUGGESTION_STOPWORDS.contains(&word)
+                || known.contains(word)
+            {
+                continue;
+            }
+            let entry = counts.entry(word.to_string()).or_insert((0, 0));
+            if seen_here.insert(word) {
+                entry.0 += 1;
+            }
+            entry.1 += 1;
+        }
+    }
+
+    let mut suggestions: Vec<KeywordSuggestion> = counts
+        .into_iter()
+        .filter(|(_, (content_count, _))| *content_count >= MIN_SUGGESTION_CONTENT)
+        .map(|(term, (content_count, occurrences))| KeywordSuggestion {
+            term,
+            content_count,
+            occurrences,
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.content_count
+            .cmp(&a.content_count)
+            .then_with(|| b.occurrences.cmp(&a.occurrences))
+            .then_with(|| a.term.cmp(&b.term))
+    });
+    suggestions.truncate(limit);
+
+    Ok(suggestions)
+}
+
+/// Words of already-normalized text, split on anything that isn't a letter or digit
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty())
+}
+
+/// Clean keywords before they are saved on a topic: trim, collapse inner whitespace,
+/// lowercase (extraction matches against lowercased text), drop empties and duplicates
+/// while keeping first-seen order. One-character keywords are rejected unless
+/// `allow_single_char` is set, as are weights that aren't positive numbers. A weight of
+/// exactly 1 is saved as a bare string.
+pub fn normalize_keywords(keywords: &[Keyword], allow_single_char: bool) -> Result<Vec<Keyword>, TrendrError> {
+    let mut normalized: Vec<Keyword> = Vec::with_capacity(keywords.len());
+
+    for keyword in keywords {
+        let term = keyword.term().split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if term.is_empty() || normalized.iter().any(|k| k.term() == term) {
+            continue;
+        }
+        if term.chars().count() < 2 && !allow_single_char {
+            return Err(TrendrError::Other(format!(
+                "Keyword '{}' is too short; enable single-character keywords in settings to allow it",
+                term
+            )));
+        }
+        let weight = keyword.weight();
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(TrendrError::Other(format!("Keyword '{}' needs a weight above 0", term)));
+        }
+        normalized.push(if weight == 1.0 {
+            Keyword::Plain(term)
+        } else {
+            Keyword::Weighted { term, weight }
+        });
+    }
+
+    Ok(normalized)
+}
+
+/// Parse a topic's keywords column, accepting bare strings and weighted entries alike
+pub fn parse_keywords(json: Option<&str>, topic_id: &str) -> Vec<Keyword> {
+    parse_json_list(json, topic_id, "keywords")
+}
+
+/// Parse a JSON string array column (aliases, exclusions), falling back to an empty list.
+/// A NULL column is treated as empty; malformed JSON is logged with the topic id so
+/// corrupt rows can be found and repaired instead of silently losing their values.
+pub fn parse_string_list(json: Option<&str>, topic_id: &str, field: &str) -> Vec<String> {
+    parse_json_list(json, topic_id, field)
+}
+
+fn parse_json_list<T: DeserializeOwned>(json: Option<&str>, topic_id: &str, field: &str) -> Vec<T> {
+    match json {
+        None => Vec::new(),
+        Some(raw) if raw.trim().is_empty() => Vec::new(),
+        Some(raw) => serde_json::from_str(raw).unwrap_or_else(|e| {
+            log::warn!(
+                "Topic {} has malformed {} JSON ({}): {:?}",
+                topic_id,
+                field,
+                e,
+                raw
+            );
+            Vec::new()
+        }),
+    }
+}
+
+/// Link a content item to its extracted topics and bump pairwise co-occurrence counts.
+/// Takes a connection so callers can batch several items in one transaction.
+pub fn link_topics(conn: &Connection, content_id: &str, topics: &[ExtractedTopic]) -> Result<(), rusqlite::Error> {
+    for topic in topics {
+        conn.execute(
+            "INSERT OR REPLACE INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, ?3)",
+            params![content_id, &topic.topic_id, topic.confidence],
+        )?;
+    }
+
+    for i in 0..topics.len() {
+        for j in (i + 1)..topics.len() {
+            // Ensure consistent ordering (smaller id first)
+            let (a, b) = if topics[i].topic_id < topics[j].topic_id {
+                (&topics[i].topic_id, &topics[j].topic_id)
+            } else {
+                (&topics[j].topic_id, &topics[i].topic_id)
+            };
+
+            conn.execute(
+                r#"INSERT INTO topic_cooccurrences (topic_a_id, topic_b_id, frequency, last_seen)
+                   VALUES (?1, ?2, 1, CURRENT_TIMESTAMP)
+                   ON CONFLICT(topic_a_id, topic_b_id) DO UPDATE SET
+                   frequency = frequency + 1, last_seen = CURRENT_TIMESTAMP"#,
+                params![a, b],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Content items re-extracted per transaction by `reextract_all`
+const REEXTRACT_BATCH: i64 = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReextractResult {
+    #[serde(rename = "contentProcessed")]
+    pub content_processed: u32,
+    #[serde(rename = "linksCreated")]
+    pub links_created: u32,
+}
+
+/// A stored content row, as much of it as re-extraction needs
+struct StoredItem {
+    rowid: i64,
+    id: String,
+    platform: String,
+    content_type: String,
+    source: Option<String>,
+    text: Option<String>,
+}
+
+/// Extract topics from stored content as its collector did. Reddit posts and YouTube
+/// videos are stored as title, blank line, body (titles never contain a blank line), so
+/// they are split back apart for title and body weighting.
+pub fn extract_stored_topics(
+    topics: &[TopicData],
+    settings: &AppSettings,
+    platform: &str,
+    content_type: &str,
+    source: Option<&str>,
+    text: &str,
+) -> Result<Vec<ExtractedTopic>, TrendrError> {
+    let item_text = match (platform, content_type) {
+        ("reddit", "comment") => ItemText::Titled {
+            title: "",
+            body: text,
+        },
+        ("reddit", _) | ("youtube", _) => {
+            let (title, body) = text.split_once("\n\n").unwrap_or((text, ""));
+            ItemText::Titled { title, body }
+        }
+        _ => ItemText::Plain(text),
+    };
+    extract_item_topics(topics, settings, platform, source, item_text)
+}
+
+/// Re-run extraction over stored content with the current topics, replacing each item's
+/// topic links, then rebuild co-occurrence counts from the new links. Items go through
+/// the same preprocessing, title/body weighting and source weight as at collection, so
+/// confidences match what a fresh collection would store. Deleted content keeps its
+/// links. Works in batches, one transaction each, calling `on_progress(processed, total)`
+/// after every batch.
+pub fn reextract_all(
+    settings: &AppSettings,
+    mut on_progress: impl FnMut(u32, u32),
+) -> Result<ReextractResult, TrendrError> {
+    let known_topics = load_topics()?;
+    let total: i64 = with_db(|conn| {
+        conn.query_row(
+            "SELECT COUNT(*) FROM content WHERE status != ?1",
+            params![status::DELETED],
+            |row| row.get(0),
+        )
+    })?;
+
+    let mut last_rowid = 0i64;
+    let mut content_processed = 0u32;
+    let mut links_created = 0u32;
+
+    loop {
+        let batch: Vec<StoredItem> = with_db(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT rowid, id, platform, content_type, source, text_content FROM content
+                   WHERE rowid > ?1 AND status != ?2
+                   ORDER BY rowid LIMIT ?3"#,
+            )?;
+            let rows = stmt.query_map(
+                params![last_rowid, status::DELETED, REEXTRACT_BATCH],
+                |row| {
+                    Ok(StoredItem {
+                        rowid: row.get(0)?,
+                        id: row.get(1)?,
+                        platform: row.get(2)?,
+                        content_type: row.get(3)?,
+                        source: row.get(4)?,
+                        text: row.get(5)?,
+                    })
+                },
+            )?;
+
+            let mut batch = Vec::new();
+            for row in rows {
+                batch.push(row?);
+            }
+            Ok(batch)
+        })?;
+
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_rowid = last.rowid;
+
+        let mut extracted = Vec::with_capacity(batch.len());
+        for item in &batch {
+            let found = match &item.text {
+                Some(text) => extract_stored_topics(
+                    &known_topics,
+                    settings,
+                    &item.platform,
+                    &item.content_type,
+                    item.source.as_deref(),
+                    text,
+                )?,
+                None => Vec::new(),
+            };
+            extracted.push((&item.id, found));
+        }
+
+        links_created += with_db_tx(|tx| {
+            let mut links = 0u32;
+            for (content_id, found) in &extracted {
+                tx.execute("DELETE FROM content_topics WHERE content_id = ?1", params![content_id])?;
+                for topic in found {
+                    tx.execute(
+                        "INSERT INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, ?3)",
+                        params![content_id, &topic.topic_id, topic.confidence],
+                    )?;
+                    links += 1;
+                }
+            }
+            Ok(links)
+        })?;
+
+        content_processed += batch.len() as u32;
+        on_progress(content_processed, total as u32);
+    }
+
+    with_db_tx(|tx| {
+        tx.execute("DELETE FROM topic_cooccurrences", [])?;
+        tx.execute(
+            r#"INSERT INTO topic_cooccurrences (topic_a_id, topic_b_id, frequency, last_seen)
+               SELECT a.topic_id, b.topic_id, COUNT(*), MAX(c.collected_at)
+               FROM content_topics a
+               JOIN content_topics b ON b.content_id = a.content_id AND a.topic_id < b.topic_id
+               JOIN content c ON c.id = a.content_id
+               GROUP BY a.topic_id, b.topic_id"#,
+            [],
+        )?;
+        Ok(())
+    })?;
+
+    log::info!(
+        "Re-extracted topics for {} content items, {} links created",
+        content_processed,
+        links_created
+    );
+
+    Ok(ReextractResult {
+        content_processed,
+        links_created,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topic(name: &str, keywords: &[&str], aliases: &[&str]) -> TopicData {
+        TopicData::compile(
+            name.to_lowercase(),
+            name.to_string(),
+            keywords.iter().map(|k| Keyword::Plain(k.to_string())).collect(),
+            aliases.iter().map(|a| a.to_string()).collect(),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn keyword_and_alias_matching_the_same_text_count_once() {
+        let topics = [topic("AI", &["ai"], &["ai"])];
+        let found = extract_topics_with(&topics, &ExtractionSettings::default(), "New AI models shipped").unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].mentions, 1);
+    }
+
+    #[test]
+    fn overlapping_terms_count_once_only_when_deduplicating() {
+        let topics = [topic("AI", &["artificial intelligence"], &["intelligence"])];
+        let text = "Artificial intelligence is everywhere";
+
+        let deduped = extract_topics_with(&topics, &ExtractionSettings::default(), text).unwrap();
+        assert_eq!(deduped[0].mentions, 1);
+
+        let config = ExtractionSettings {
+            dedupe_overlapping_matches: false,
+            ..ExtractionSettings::default()
+        };
+        let counted = extract_topics_with(&topics, &config, text).unwrap();
+        assert_eq!(counted[0].mentions, 2);
+    }
+
+    #[test]
+    fn distinct_spans_merge_overlaps_and_keep_the_strongest_weight() {
+        assert_eq!(count_distinct_spans(Vec::new()), (0, 0.0));
+
+        // The same range twice, a range inside another, and two that only touch
+        let spans = vec![(10, 14, 1.0), (0, 2, 0.5), (0, 2, 1.0), (9, 20, 0.5), (20, 22, 0.5)];
+        assert_eq!(count_distinct_spans(spans), (3, 2.5));
+    }
+
+    #[test]
+    fn confidence_scales_with_match_density() {
+        // FULL_CONFIDENCE_DENSITY matches per 100 words is full confidence
+        assert_eq!(confidence_score(10.0, 100), 1.0);
+        assert_eq!(confidence_score(5.0, 100), 0.5);
+        assert_eq!(confidence_score(30.0, 100), 1.0);
+        assert_eq!(confidence_score(0.0, 100), 0.0);
+
+        // One mention in a long post counts for less than one in a short one
+        assert!(confidence_score(1.0, 200) < confidence_score(1.0, 20));
+        assert_eq!(confidence_score(1.0, 200), 0.05);
+    }
+
+    #[test]
+    fn confidence_handles_empty_text_and_nan() {
+        assert_eq!(confidence_score(0.0, 0), 0.0);
+        assert_eq!(confidence_score(1.0, 0), 1.0);
+        assert_eq!(confidence_score(f64::NAN, 50), 0.0);
+        assert_eq!(confidence_score(f64::INFINITY, 50), 1.0);
+        assert_eq!(confidence_score(-1.0, 50), 0.0);
+
+        let topics = [topic("Rust", &["rust"], &[])];
+        assert!(extract_topics_with(&topics, &ExtractionSettings::default(), "").unwrap().is_empty());
+    }
+
+    fn plain(terms: &[&str]) -> Vec<Keyword> {
+        terms.iter().map(|t| Keyword::Plain(t.to_string())).collect()
+    }
+
+    #[test]
+    fn keywords_are_trimmed_lowercased_and_deduplicated() {
+        let keywords = plain(&["  Machine   Learning ", "AI", "", "   ", "ai", "machine learning"]);
+        assert_eq!(
+            normalize_keywords(&keywords, false).unwrap(),
+            plain(&["machine learning", "ai"])
+        );
+    }
+
+    #[test]
+    fn single_character_keywords_need_opting_in() {
+        let keywords = plain(&["x", "rust"]);
+        assert!(normalize_keywords(&keywords, false).is_err());
+        assert_eq!(normalize_keywords(&keywords, true).unwrap(), keywords);
+    }
+
+    #[test]
+    fn keyword_weights_must_be_positive_and_one_is_saved_plain() {
+        let weighted = |term: &str, weight: f64| Keyword::Weighted {
+            term: term.to_string(),
+            weight,
+        };
+
+        assert_eq!(
+            normalize_keywords(&[weighted("Rust", 1.0), weighted("Cargo", 2.5)], false).unwrap(),
+            vec![Keyword::Plain("rust".to_string()), weighted("cargo", 2.5)]
+        );
+        for weight in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(normalize_keywords(&[weighted("rust", weight)], false).is_err());
+        }
+    }
+
+    fn matches_keyword(keyword: &str, text: &str) -> bool {
+        Regex::new(&keyword_pattern(keyword)).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn phrases_match_across_whitespace_and_punctuation() {
+        assert!(matches_keyword("side hustle", "my side hustle pays"));
+        assert!(matches_keyword("side hustle", "my side-hustle pays"));
+        assert!(matches_keyword("side hustle", "my side   hustle pays"));
+        assert!(matches_keyword("side hustle", "side,\nhustle"));
+        assert!(!matches_keyword("side hustle", "my sidehustle pays"));
+        assert!(!matches_keyword("side hustle", "inside hustler"));
+    }
+
+    #[test]
+    fn keywords_match_whole_words_only() {
+        assert!(matches_keyword("ai", "ai is here"));
+        assert!(matches_keyword("ai", "#ai trends"));
+        assert!(!matches_keyword("ai", "she said so"));
+        assert!(matches_keyword("node.js", "built on node.js today"));
+        assert!(!matches_keyword("node.js", "built on nodexjs today"));
+    }
+
+    /// Topics the old matcher linked: any term found as a plain substring of the text
+    fn substring_topic_ids(topics: &[TopicData], text: &str) -> Vec<String> {
+        let text = text.to_lowercase();
+        topics
+            .iter()
+            .filter(|topic| topic.terms.iter().any(|(term, _)| text.contains(term.as_str())))
+            .map(|topic| topic.id.clone())
+            .collect()
+    }
+
+    fn compiled_topic_ids(topics: &[TopicData], text: &str) -> Vec<String> {
+        let mut ids: Vec<String> = extract_topics_with(topics, &ExtractionSettings::default(), text)
+            .unwrap()
+            .into_iter()
+            .map(|topic| topic.topic_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn compiled_patterns_agree_with_substring_matching_except_at_word_edges() {
+        let topics = [
+            topic("AI", &["ai", "machine learning"], &["artificial intelligence"]),
+            topic("Rust", &["rust", "cargo"], &[]),
+            topic("Side Hustle", &["side hustle"], &[]),
+        ];
+        let agreeing = [
+            "Machine learning and AI news",
+            "Cargo 1.80 ships with the new rust release",
+            "Artificial intelligence meets rust",
+            "My side hustle grew this year",
+            "Nothing relevant here",
+        ];
+        for text in agreeing {
+            assert_eq!(compiled_topic_ids(&topics, text), substring_topic_ids(&topics, text), "{}", text);
+        }
+
+        // Substrings inside longer words no longer count
+        assert_eq!(substring_topic_ids(&topics, "She said the cargoship was rusty"), ["ai", "rust"]);
+        assert!(compiled_topic_ids(&topics, "She said the cargoship was rusty").is_empty());
+
+        // Phrases now match across punctuation and runs of whitespace between their words
+        for text in ["My side-hustle grew", "Machine   learning, in short"] {
+            assert!(substring_topic_ids(&topics, text).is_empty(), "{}", text);
+        }
+        assert_eq!(compiled_topic_ids(&topics, "My side-hustle grew"), ["side hustle"]);
+        assert_eq!(compiled_topic_ids(&topics, "Machine   learning, in short"), ["ai"]);
+    }
+
+    #[test]
+    fn an_exclusion_term_keeps_the_topic_from_linking() {
+        let topics = [TopicData::compile(
+            "apple".to_string(),
+            "Apple".to_string(),
+            plain(&["apple"]),
+            Vec::new(),
+            vec!["Pie".to_string(), "fruit".to_string()],
+        )];
+        let config = ExtractionSettings::default();
+
+        let found = extract_topics_with(&topics, &config, "Apple announced a new phone").unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(extract_topics_with(&topics, &config, "Grandma's apple pie recipe").unwrap().is_empty());
+        assert!(extract_topics_weighted_with(&topics, &config, "Apple harvest", "Best fruit of autumn")
+            .unwrap()
+            .is_empty());
+    }
+
+    fn filler(words: usize) -> String {
+        vec!["word"; words].join(" ")
+    }
+
+    #[test]
+    fn keyword_weights_change_the_ranking() {
+        // Forty words, so one plain mention scores 0.25 and nothing is clamped to 1
+        let text = format!("rust go {}", filler(38));
+        let config = ExtractionSettings::default();
+        let ranking = |topics: &[TopicData]| -> Vec<(String, f64)> {
+            extract_topics_with(topics, &config, &text)
+                .unwrap()
+                .into_iter()
+                .map(|t| (t.topic_id, t.confidence))
+                .collect()
+        };
+
+        // Equal scores fall back to name order
+        let unweighted = [topic("Go", &["go"], &[]), topic("Rust", &["rust"], &[])];
+        assert_eq!(
+            ranking(&unweighted),
+            vec![("go".to_string(), 0.25), ("rust".to_string(), 0.25)]
+        );
+
+        let weighted = [
+            topic("Go", &["go"], &[]),
+            TopicData::compile(
+                "rust".to_string(),
+                "Rust".to_string(),
+                vec![Keyword::Weighted {
+                    term: "rust".to_string(),
+                    weight: 2.0,
+                }],
+                Vec::new(),
+                Vec::new(),
+            ),
+        ];
+        assert_eq!(
+            ranking(&weighted),
+            vec![("rust".to_string(), 0.5), ("go".to_string(), 0.25)]
+        );
+    }
+
+    #[test]
+    fn hashtags_and_cashtags_match_their_keywords() {
+        let topics = [
+            topic("Bitcoin", &["#bitcoin"], &[]),
+            topic("Apple", &["$aapl"], &[]),
+            topic("Ethereum", &["ethereum"], &[]),
+        ];
+        let found = extract_topics_with(
+            &topics,
+            &ExtractionSettings::default(),
+            "Rotating out of #Bitcoin into $AAPL and #ethereum",
+        )
+        .unwrap();
+
+        let mut ids: Vec<&str> = found.iter().map(|t| t.topic_id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, ["apple", "bitcoin", "ethereum"]);
+    }
+
+    #[test]
+    fn title_and_body_matches_count_by_their_own_weights() {
+        let topics = [topic("Rust", &["rust"], &[])];
+        let config = ExtractionSettings {
+            title_weight: 2.0,
+            body_weight: 0.5,
+            ..ExtractionSettings::default()
+        };
+        let confidence = |title: &str, body: &str| {
+            extract_topics_weighted_with(&topics, &config, title, body).unwrap()[0].confidence
+        };
+
+        // Forty words either way; only the field holding the mention differs
+        assert_eq!(confidence("rust", &filler(39)), 0.5);
+        assert_eq!(confidence("news", &format!("rust {}", filler(38))), 0.125);
+    }
+
+    fn links(content_id: &str) -> Vec<(String, f64)> {
+        with_db(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT topic_id, confidence FROM content_topics WHERE content_id = ?1")?;
+            let rows = stmt.query_map([content_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn content_naming_only_an_alias_links_at_alias_weight() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug, keywords, aliases)
+                   VALUES ('rust', 'Rust', 'rust', '["rust"]', '["Ferris"]');
+                   INSERT INTO content (id, platform, platform_id, content_type, text_content) VALUES
+                       ('alias', 'x', 't1', 'post', 'ferris'),
+                       ('keyword', 'x', 't2', 'post', 'rust');"#,
+            )
+        })
+        .unwrap();
+
+        // Ten words with one mention each, so a keyword alone reaches full confidence
+        let topics = load_topics().unwrap();
+        let filler = "one two three four five six seven eight nine";
+        for (content_id, term) in [("alias", "Ferris"), ("keyword", "rust")] {
+            let text = format!("{} {}", term, filler);
+            let found = extract_topics_with(&topics, &ExtractionSettings::default(), &text).unwrap();
+            with_db(|conn| link_topics(conn, content_id, &found)).unwrap();
+        }
+
+        assert_eq!(links("alias"), vec![("rust".to_string(), ALIAS_WEIGHT)]);
+        assert_eq!(links("keyword"), vec![("rust".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn suggestions_are_unknown_words_shared_by_linked_content() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO topics (id, name, slug, keywords, aliases, exclusions)
+                   VALUES ('rust', 'Rust', 'rust', '["rust", "side hustle"]', '["ferris"]', '["rusty"]');
+                   INSERT INTO content (id, platform, platform_id, content_type, text_content) VALUES
+                       ('c1', 'x', 't1', 'post', 'Rust borrow checker and cargo cargo tips'),
+                       ('c2', 'x', 't2', 'post', 'The borrow checker again, cargo build hustle tokio 2024 2024'),
+                       ('c3', 'x', 't3', 'post', 'cargo ferris side rusty rusty'),
+                       ('unlinked', 'x', 't4', 'post', 'tokio tokio tips');
+                   INSERT INTO content (id, platform, platform_id, content_type, text_content, status)
+                   VALUES ('gone', 'x', 't5', 'post', 'tokio build tips', 'deleted');
+                   INSERT INTO content_topics (content_id, topic_id, confidence)
+                   VALUES ('c1', 'rust', 1.0), ('c2', 'rust', 1.0), ('c3', 'rust', 1.0), ('gone', 'rust', 1.0);"#,
+            )
+        })
+        .unwrap();
+
+        let suggested: Vec<(String, i64, i64)> = suggest_keywords("rust", 10)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.term, s.content_count, s.occurrences))
+            .collect();
+        assert_eq!(
+            suggested,
+            vec![
+                ("cargo".to_string(), 3, 4),
+                ("borrow".to_string(), 2, 2),
+                ("checker".to_string(), 2, 2),
+            ]
+        );
+
+        assert_eq!(suggest_keywords("rust", 1).unwrap().len(), 1);
+        assert!(suggest_keywords("missing", 10).is_err());
+    }
+
+    #[test]
+    fn reextraction_applies_new_keywords_like_collection_would() {
+        let _db = crate::database::test_database();
+        let post_body = "We rebuilt the whole build pipeline this week and the new release went out \
+                         to every team without a single problem so far, which made for a very quiet \
+                         Friday afternoon";
+        let tweet_text = "@alice @bob @carol @dave cargo ships another release for everyone building \
+                          things this week with faster builds and better errors";
+        with_db(|conn| {
+            conn.execute(
+                "INSERT INTO topics (id, name, slug, keywords) VALUES ('rust', 'Rust', 'rust', '[\"rust\"]')",
+                [],
+            )?;
+            conn.execute(
+                r#"INSERT INTO content (id, platform, platform_id, content_type, text_content, source)
+                   VALUES ('post', 'reddit', 'p1', 'post', ?1, 'rust'),
+                          ('tweet', 'x', 't1', 'post', ?2, 'cargo')"#,
+                params![format!("Cargo news\n\n{}", post_body), tweet_text],
+            )?;
+            conn.execute(
+                r#"INSERT INTO content (id, platform, platform_id, content_type, text_content, status)
+                   VALUES ('gone', 'x', 't2', 'post', 'cargo', 'deleted')"#,
+                [],
+            )
+        })
+        .unwrap();
+
+        let mut settings = AppSettings::default();
+        settings.extraction.title_weight = 2.0;
+        settings.source_weights.reddit.insert("rust".to_string(), 0.8);
+
+        let first = reextract_all(&settings, |_, _| {}).unwrap();
+        assert_eq!(first.content_processed, 2);
+        assert_eq!(first.links_created, 0);
+
+        with_db(|conn| {
+            conn.execute(
+                "UPDATE topics SET keywords = '[\"rust\", \"cargo\"]' WHERE id = 'rust'",
+                [],
+            )
+        })
+        .unwrap();
+        let second = reextract_all(&settings, |_, _| {}).unwrap();
+        assert_eq!(second.links_created, 2);
+        assert!(links("gone").is_empty());
+
+        // Same cleaning, title weighting and source weight as at collection
+        let known_topics = load_topics().unwrap();
+        let collected = |platform: &str, source: &str, text: ItemText| {
+            extract_item_topics(&known_topics, &settings, platform, Some(source), text).unwrap()[0]
+                .confidence
+        };
+        let post = collected(
+            "reddit",
+            "rust",
+            ItemText::Titled {
+                title: "Cargo news",
+                body: post_body,
+            },
+        );
+        let tweet = collected("x", "cargo", ItemText::Plain(tweet_text));
+        assert_eq!(links("post"), vec![("rust".to_string(), post)]);
+        assert_eq!(links("tweet"), vec![("rust".to_string(), tweet)]);
+        // Not clamped, so the weights and cleaning actually show in the values
+        assert!(post < 1.0 && tweet < 1.0);
+        assert_ne!(
+            post,
+            extract_topics_with(
+                &known_topics,
+                &settings.extraction,
+                &format!("Cargo news {}", post_body)
+            )
+            .unwrap()[0]
+                .confidence
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use crate::alerts;
+use crate::content::status;
+use crate::database::{with_db, with_db_tx};
+use crate::error::TrendrError;
+use crate::settings::{AlertSettings, TrendScoreSettings};
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A topic's blended trend score and the normalized signals it was built from.
+///
+/// Each signal is scaled to 0–1:
+/// - volume: content in the window, relative to the busiest topic
+/// - momentum: growth g = (current - previous) / (previous + 1) versus the window
+///   before it, squashed as g / (g + 1) so shrinking topics score 0
+/// - recency: 1 / (1 + hours since the newest item / 24)
+/// - engagement: ln(1 + average likes+comments+shares in the window), relative to the
+///   most engaging topic
+///
+/// score = 100 × Σ(weight × signal) / Σ(weight)
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendScore {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    pub score: f64,
+    pub volume: f64,
+    pub momentum: f64,
+    pub recency: f64,
+    pub engagement: f64,
+    #[serde(rename = "computedAt")]
+    pub computed_at: String,
+}
+
+struct RawSignals {
+    topic_id: String,
+    topic_name: String,
+    current: i64,
+    previous: i64,
+    hours_since_last: Option<f64>,
+    avg_engagement: f64,
+}
+
+/// Recompute every topic's trend score and cache it in `topic_trend_scores`.
+pub fn compute_trend_scores(config: &TrendScoreSettings) -> Result<Vec<TrendScore>, TrendrError> {
+    let window = format!("-{} days", config.window_days.max(1));
+    let previous_window = format!("-{} days", config.window_days.max(1) * 2);
+
+    let raw = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT t.id, t.name,
+               COUNT(CASE WHEN w.at > datetime('now', ?1) THEN 1 END) as current_count,
+               COUNT(CASE WHEN w.at <= datetime('now', ?1) AND w.at > datetime('now', ?2) THEN 1 END) as previous_count,
+               (julianday('now') - julianday(MAX(w.at))) * 24 as hours_since_last,
+               AVG(CASE WHEN w.at > datetime('now', ?1) THEN w.engagement END) as avg_engagement
+               FROM topics t
+               LEFT JOIN (
+                   SELECT ct.topic_id,
+                   COALESCE(datetime(c.published_at), datetime(c.collected_at)) as at,
+                   c.engagement_likes + c.engagement_comments + c.engagement_shares as engagement
+                   FROM content_topics ct
+                   JOIN content c ON c.id = ct.content_id
+                   WHERE c.status != ?3
+               ) w ON w.topic_id = t.id
+               GROUP BY t.id"#,
+        )?;
+
+        let rows = stmt.query_map(params![&window, &previous_window, status::DELETED], |row| {
+            Ok(RawSignals {
+                topic_id: row.get(0)?,
+                topic_name: row.get(1)?,
+                current: row.get(2)?,
+                previous: row.get(3)?,
+                hours_since_last: row.get(4)?,
+                avg_engagement: row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+            })
+        })?;
+
+        let mut raw = Vec::new();
+        for row in rows {
+            raw.push(row?);
+        }
+        Ok(raw)
+    })?;
+
+    let max_volume = raw.iter().map(|r| r.current).max().unwrap_or(0).max(1) as f64;
+    let max_engagement = raw
+        .iter()
+        .map(|r| (1.0 + r.avg_engagement.max(0.0)).ln())
+        .fold(0.0, f64::max);
+    let total_weight = config.volume_weight + config.momentum_weight + config.recency_weight + config.engagement_weight;
+    let computed_at = chrono::Utc::now().to_rfc3339();
+
+    let scores: Vec<TrendScore> = raw
+        .into_iter()
+        .map(|r| {
+            let volume = r.current as f64 / max_volume;
+            let growth = ((r.current - r.previous) as f64 / (r.previous + 1) as f64).max(0.0);
+            let momentum = growth / (growth + 1.0);
+            let recency = r.hours_since_last.map(|h| 1.0 / (1.0 + h.max(0.0) / 24.0)).unwrap_or(0.0);
+            let engagement = if max_engagement > 0.0 {
+                (1.0 + r.avg_engagement.max(0.0)).ln() / max_engagement
+            } else {
+                0.0
+            };
+
+            let blended = config.volume_weight * volume
+                + config.momentum_weight * momentum
+                + config.recency_weight * recency
+                + config.engagement_weight * engagement;
+            let score = if total_weight > 0.0 { 100.0 * blended / total_weight } else { 0.0 };
+
+            TrendScore {
+                topic_id: r.topic_id,
+                topic_name: r.topic_name,
+                score,
+                volume,
+                momentum,
+                recency,
+                engagement,
+                computed_at: computed_at.clone(),
+            }
+        })
+        .collect();
+
+    with_db_tx(|tx| {
+        for s in &scores {
+            tx.execute(
+                r#"INSERT OR REPLACE INTO topic_trend_scores
+                   (topic_id, score, volume, momentum, recency, engagement, computed_at)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+                params![&s.topic_id, s.score, s.volume, s.momentum, s.recency, s.engagement, &s.computed_at],
+            )?;
+        }
+        Ok(())
+    })?;
+
+    Ok(scores)
+}
+
+/// Cached scores from the last computation, highest first.
+pub fn get_trend_scores(limit: i64) -> Result<Vec<TrendScore>, TrendrError> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT s.topic_id, t.name, s.score, s.volume, s.momentum, s.recency, s.engagement, s.computed_at
+               FROM topic_trend_scores s
+               JOIN topics t ON t.id = s.topic_id
+               ORDER BY s.score DESC
+               LIMIT ?1"#,
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(TrendScore {
+                topic_id: row.get(0)?,
+                topic_name: row.get(1)?,
+                score: row.get(2)?,
+                volume: row.get(3)?,
+                momentum: row.get(4)?,
+                recency: row.get(5)?,
+                engagement: row.get(6)?,
+                computed_at: row.get(7)?,
+            })
+        })?;
+
+        let mut scores = Vec::new();
+        for row in rows {
+            scores.push(row?);
+        }
+        Ok(scores)
+    })
+}
+
+/// Refresh cached scores after a collection run; failures are logged, not surfaced.
+pub fn refresh_after_collection(config: &TrendScoreSettings) {
+    if let Err(e) = compute_trend_scores(config) {
+        log::warn!("Failed to update trend scores: {}", e);
+    }
+}
+
+/// Days of history a topic's last 24 hours are compared against when looking for spikes
+const SPIKE_BASELINE_DAYS: i64 = 7;
+
+/// A topic whose last 24 hours, as of some moment, spiked past the alert settings.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedAlert {
+    #[serde(rename = "topicId")]
+    pub topic_id: String,
+    #[serde(rename = "topicName")]
+    pub topic_name: String,
+    /// Day the spiking 24 hours ended on, YYYY-MM-DD (UTC)
+    pub date: String,
+    pub count: i64,
+    /// Mean daily count over the preceding baseline days
+    pub baseline: f64,
+    /// count / max(baseline, 1)
+    #[serde(rename = "spikeFactor")]
+    pub spike_factor: f64,
+}
+
+/// Every topic's content count in the 24 hours ending `hours_ago` hours before now, and
+/// the `SPIKE_BASELINE_DAYS` before that. Deleted content doesn't count.
+fn window_counts(hours_ago: i64) -> Result<Vec<(String, String, i64, i64)>, TrendrError> {
+    let window_end = format!("-{} hours", hours_ago);
+    let window_start = format!("-{} hours", hours_ago + 24);
+    let history_start = format!("-{} hours", hours_ago + (SPIKE_BASELINE_DAYS + 1) * 24);
+
+    with_db(|conn| {
+        let mut stmt = conn.prepare(
+            r#"SELECT t.id, t.name,
+               SUM(CASE WHEN w.at > datetime('now', ?2) THEN 1 ELSE 0 END) as recent,
+               SUM(CASE WHEN w.at <= datetime('now', ?2) THEN 1 ELSE 0 END) as prior
+               FROM (
+                   SELECT ct.topic_id,
+                   COALESCE(datetime(c.published_at), datetime(c.collected_at)) as at
+                   FROM content_topics ct
+                   JOIN content c ON c.id = ct.content_id
+                   WHERE c.status != ?1
+               ) w
+               JOIN topics t ON t.id = w.topic_id
+               WHERE w.at > datetime('now', ?3) AND w.at <= datetime('now', ?4)
+               GROUP BY t.id"#,
+        )?;
+
+        let rows = stmt.query_map(params![status::DELETED, &window_start, &history_start, &window_end], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+
+        let mut counts = Vec::new();
+        for row in rows {
+            counts.push(row?);
+        }
+        Ok(counts)
+    })
+}
+
+/// The spike test shared by `detect_trends` and `simulate_spikes`: `recent` items in 24
+/// hours must reach `minAbsoluteCount` and at least `trendSpikeRatio` times the daily
+/// average of the `prior` week. Returns that baseline and the spike factor when it fires.
+fn spike(recent: i64, prior: i64, config: &AlertSettings) -> Option<(f64, f64)> {
+    if recent < config.min_absolute_count {
+        return None;
+    }
+
+    let baseline = prior as f64 / SPIKE_BASELINE_DAYS as f64;
+    let spike_factor = recent as f64 / baseline.max(1.0);
+    (spike_factor >= config.trend_spike_ratio).then_some((baseline, spike_factor))
+}
+
+/// Replay `detect_trends` once a day, at this time of day, over the last `days` days of
+/// stored content, including its cooldown. The `trend_spike` type doesn't have to be
+/// enabled. Read-only; returns the alerts that would have fired, oldest first.
+pub fn simulate_spikes(config: &AlertSettings, days: i64) -> Result<Vec<SimulatedAlert>, TrendrError> {
+    let now = chrono::Utc::now();
+    let mut last_fired: HashMap<String, i64> = HashMap::new();
+    let mut alerts = Vec::new();
+
+    for hours_ago in (0..days.max(1)).rev().map(|day| day * 24) {
+        let date = (now - chrono::Duration::hours(hours_ago)).format("%Y-%m-%d").to_string();
+        let mut fired = Vec::new();
+
+        for (topic_id, topic_name, recent, prior) in window_counts(hours_ago)? {
+            let Some((baseline, spike_factor)) = spike(recent, prior, config) else {
+                continue;
+            };
+            let cooling = last_fired
+                .get(&topic_id)
+                .is_some_and(|&at| at - hours_ago < config.cooldown_hours as i64);
+            if cooling {
+                continue;
+            }
+
+            last_fired.insert(topic_id.clone(), hours_ago);
+            fired.push(SimulatedAlert {
+                topic_id,
+                topic_name,
+                date: date.clone(),
+                count: recent,
+                baseline,
+                spike_factor,
+            });
+        }
+
+        fired.sort_by(|a, b| a.topic_name.cmp(&b.topic_name));
+        alerts.extend(fired);
+    }
+
+    Ok(alerts)
+}
+
+/// Raise a trend_spike alert for every topic whose content in the last 24 hours passes
+/// the spike test. Topics already alerted within the cooldown are skipped. Returns how
+/// many alerts were raised.
+pub fn detect_trends(config: &AlertSettings) -> Result<u32, TrendrError> {
+    if !config.is_enabled("trend_spike") || config.trend_spike_ratio.is_nan() || config.trend_spike_ratio <= 0.0 {
+        return Ok(0);
+    }
+
+    let mut created = 0u32;
+    for (topic_id, topic_name, recent, prior) in window_counts(0)? {
+        let Some((baseline, spike_factor)) = spike(recent, prior, config) else {
+            continue;
+        };
+
+        let message = format!(
+            "{} is spiking: {} items in the last 24 hours, {:.1}× its daily average of {:.1} over the previous {} days",
+            topic_name, recent, spike_factor, baseline, SPIKE_BASELINE_DAYS
+        );
+        if alerts::create_alert_once("trend_spike", Some(&topic_id), &message, config.cooldown_hours as i64)? {
+            created += 1;
+        }
+    }
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed_topic(id: &str) {
+        with_db(|conn| {
+            conn.execute(
+                "INSERT INTO topics (id, name, slug, keywords) VALUES (?1, ?1, ?1, '[]')",
+                params![id],
+            )
+        })
+        .unwrap();
+    }
+
+    /// `count` items for `topic_id`, collected `hours_ago` hours ago
+    fn seed_items(topic_id: &str, hours_ago: i64, count: usize, item_status: &str) {
+        with_db(|conn| {
+            for _ in 0..count {
+                let id = uuid::Uuid::new_v4().to_string();
+                conn.execute(
+                    r#"INSERT INTO content (id, platform, platform_id, content_type, status, collected_at)
+                       VALUES (?1, 'x', ?1, 'post', ?2, datetime('now', ?3))"#,
+                    params![&id, item_status, format!("-{} hours", hours_ago)],
+                )?;
+                conn.execute(
+                    "INSERT INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, 1.0)",
+                    params![&id, topic_id],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    /// One item a day for the baseline week, then a burst of `burst` in the last 24 hours
+    fn seed_spike(topic_id: &str, burst: usize, item_status: &str) {
+        seed_topic(topic_id);
+        for day in 1..=SPIKE_BASELINE_DAYS {
+            seed_items(topic_id, day * 24 + 12, 1, status::ACTIVE);
+        }
+        seed_items(topic_id, 2, burst, item_status);
+    }
+
+    fn spike_alert_topics() -> Vec<String> {
+        with_db(|conn| {
+            let mut stmt = conn.prepare("SELECT topic_id FROM alerts WHERE alert_type = 'trend_spike' ORDER BY topic_id")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn a_spike_raises_one_alert_until_the_cooldown_ends() {
+        let _db = crate::database::test_database();
+        let config = AlertSettings::default();
+        seed_spike("spiking", 10, status::ACTIVE);
+
+        assert_eq!(detect_trends(&config).unwrap(), 1);
+        assert_eq!(detect_trends(&config).unwrap(), 0);
+        assert_eq!(spike_alert_topics(), ["spiking"]);
+    }
+
+    #[test]
+    fn spikes_below_the_minimum_count_are_ignored() {
+        let _db = crate::database::test_database();
+        seed_topic("small");
+        // 4 items against an empty week is a 4× spike, but under the minimum of 5
+        seed_items("small", 2, 4, status::ACTIVE);
+
+        let config = AlertSettings::default();
+        assert_eq!(detect_trends(&config).unwrap(), 0);
+        assert!(spike_alert_topics().is_empty());
+
+        let lower = AlertSettings {
+            min_absolute_count: 4,
+            ..AlertSettings::default()
+        };
+        assert_eq!(detect_trends(&lower).unwrap(), 1);
+    }
+
+    #[test]
+    fn simulation_agrees_with_detection() {
+        let _db = crate::database::test_database();
+        let config = AlertSettings::default();
+        seed_spike("spiking", 8, status::ACTIVE);
+        seed_spike("deleted", 8, status::DELETED);
+        seed_spike("small", 3, status::ACTIVE);
+        seed_spike("steady", 1, status::ACTIVE);
+
+        let simulated = simulate_spikes(&config, 1).unwrap();
+        let detected = detect_trends(&config).unwrap();
+
+        let simulated_topics: Vec<&str> = simulated.iter().map(|a| a.topic_id.as_str()).collect();
+        assert_eq!(simulated_topics, ["spiking"]);
+        assert_eq!(detected, 1);
+        assert_eq!(spike_alert_topics(), ["spiking"]);
+
+        let alert = &simulated[0];
+        assert_eq!(alert.count, 8);
+        assert_eq!(alert.baseline, 1.0);
+        assert_eq!(alert.spike_factor, 8.0);
+        assert_eq!(alert.date, chrono::Utc::now().format("%Y-%m-%d").to_string());
+    }
+
+    #[test]
+    fn simulation_replays_earlier_days_with_a_rolling_window() {
+        let _db = crate::database::test_database();
+        let config = AlertSettings::default();
+        seed_topic("earlier");
+        // A burst two days ago falls in the 24 hours ending 48 hours ago, and no other
+        seed_items("earlier", 50, 6, status::ACTIVE);
+
+        let simulated = simulate_spikes(&config, 3).unwrap();
+        assert_eq!(simulated.len(), 1);
+        assert_eq!(simulated[0].count, 6);
+        assert_eq!(
+            simulated[0].date,
+            (chrono::Utc::now() - chrono::Duration::hours(48)).format("%Y-%m-%d").to_string()
+        );
+        assert_eq!(detect_trends(&config).unwrap(), 0);
+    }
+}
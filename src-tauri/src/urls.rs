@@ -0,0 +1,155 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Query parameters that only identify where a click came from
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "igshid", "igsh", "mc_cid", "mc_eid", "ref", "ref_src",
+    "ref_url", "si", "feature", "pp", "_ga", "yclid",
+];
+
+/// Host prefixes that serve the same pages as the bare domain
+const HOST_PREFIXES: &[&str] = &["www.", "m.", "mobile.", "amp."];
+
+/// Reduce a link to a canonical form so the same page shared from different places
+/// compares equal: https scheme, lowercase host without www/m/mobile prefixes, no
+/// fragment, no tracking parameters (utm_*, fbclid, ...), remaining parameters sorted,
+/// no trailing slash. youtu.be and twitter.com links are rewritten to their main domains.
+/// Returns None for anything that isn't an http(s) URL.
+pub fn canonicalize(raw: &str) -> Option<String> {
+    let mut url = reqwest::Url::parse(raw.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let mut host = url.host_str()?.to_lowercase();
+    while let Some(stripped) = HOST_PREFIXES.iter().find_map(|prefix| host.strip_prefix(prefix)) {
+        host = stripped.to_string();
+    }
+
+    if host == "youtu.be" {
+        let video_id = url.path().trim_matches('/').to_string();
+        if video_id.is_empty() {
+            return None;
+        }
+        host = "youtube.com".to_string();
+        url.set_path("/watch");
+        url.query_pairs_mut().append_pair("v", &video_id);
+    } else if host == "twitter.com" {
+        host = "x.com".to_string();
+    }
+
+    url.set_scheme("https").ok()?;
+    url.set_host(Some(&host)).ok()?;
+    url.set_port(None).ok()?;
+    url.set_fragment(None);
+
+    let mut kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking_param(key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    kept.sort();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept.iter());
+    }
+
+    let path = url.path().trim_end_matches('/').to_string();
+    url.set_path(&path);
+
+    let canonical = url.to_string();
+    Some(match canonical.strip_suffix('/') {
+        Some(bare) if url.query().is_none() => bare.to_string(),
+        _ => canonical,
+    })
+}
+
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+/// Store the canonical form of `raw_url` on a content row. With `flag_duplicates`, also
+/// point `duplicate_of` at the earliest other content that links the same page; the row
+/// itself is kept either way.
+pub fn record_canonical_url(
+    conn: &Connection,
+    content_id: &str,
+    raw_url: Option<&str>,
+    flag_duplicates: bool,
+) -> Result<(), rusqlite::Error> {
+    let canonical = match raw_url.and_then(canonicalize) {
+        Some(canonical) => canonical,
+        None => return Ok(()),
+    };
+
+    let original: Option<String> = if flag_duplicates {
+        conn.query_row(
+            r#"SELECT id FROM content
+               WHERE canonical_url = ?1 AND id != ?2 AND duplicate_of IS NULL
+               ORDER BY collected_at, rowid
+               LIMIT 1"#,
+            params![&canonical, content_id],
+            |row| row.get(0),
+        )
+        .optional()?
+    } else {
+        None
+    };
+
+    conn.execute(
+        "UPDATE content SET canonical_url = ?1, duplicate_of = ?2 WHERE id = ?3",
+        params![&canonical, original, content_id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(raw: &str) -> String {
+        canonicalize(raw).unwrap()
+    }
+
+    #[test]
+    fn strips_tracking_parameters() {
+        assert_eq!(
+            canonical("https://example.com/article?utm_source=twitter&utm_medium=social&id=7"),
+            "https://example.com/article?id=7"
+        );
+        assert_eq!(canonical("https://example.com/article?fbclid=abc123"), "https://example.com/article");
+        assert_eq!(canonical("https://example.com/a?UTM_Campaign=x&gclid=y&ref=home"), "https://example.com/a");
+    }
+
+    #[test]
+    fn keeps_and_sorts_meaningful_parameters() {
+        assert_eq!(canonical("https://example.com/search?q=rust&page=2"), "https://example.com/search?page=2&q=rust");
+    }
+
+    #[test]
+    fn normalizes_scheme_host_fragment_and_trailing_slash() {
+        let expected = "https://example.com/news/story";
+        assert_eq!(canonical("http://www.example.com/news/story/"), expected);
+        assert_eq!(canonical("https://m.Example.COM/news/story#comments"), expected);
+        assert_eq!(canonical("https://example.com:8443/news/story"), expected);
+        assert_eq!(canonical("https://www.example.com/"), "https://example.com");
+    }
+
+    #[test]
+    fn rewrites_short_and_legacy_domains() {
+        assert_eq!(canonical("https://youtu.be/dQw4w9WgXcQ?si=share"), "https://youtube.com/watch?v=dQw4w9WgXcQ");
+        assert_eq!(
+            canonical("https://m.youtube.com/watch?v=dQw4w9WgXcQ&feature=share"),
+            "https://youtube.com/watch?v=dQw4w9WgXcQ"
+        );
+        assert_eq!(canonical("https://twitter.com/user/status/1"), "https://x.com/user/status/1");
+    }
+
+    #[test]
+    fn rejects_non_http_links() {
+        assert_eq!(canonicalize("mailto:someone@example.com"), None);
+        assert_eq!(canonicalize("not a url"), None);
+        assert_eq!(canonicalize("https://youtu.be/"), None);
+    }
+}
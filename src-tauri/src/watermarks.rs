@@ -0,0 +1,49 @@
+use crate::database::with_db;
+use crate::error::TrendrError;
+use crate::settings::AppSettings;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, OptionalExtension};
+
+/// The newest timestamp previously stored for a source, when `onlyNewerContent` is on and
+/// the listing is sorted newest-first. Relevance- or score-sorted listings return None so
+/// they fall back to per-item duplicate checks.
+pub fn cutoff(settings: &AppSettings, platform: &str, source: &str, time_ordered: bool) -> Option<DateTime<Utc>> {
+    if !settings.only_newer_content || !time_ordered {
+        return None;
+    }
+
+    load(platform, source).unwrap_or_else(|e| {
+        log::warn!("Failed to load watermark for {} '{}': {}", platform, source, e);
+        None
+    })
+}
+
+fn load(platform: &str, source: &str) -> Result<Option<DateTime<Utc>>, TrendrError> {
+    let newest: Option<String> = with_db(|conn| {
+        conn.query_row(
+            "SELECT newest_at FROM source_watermarks WHERE platform = ?1 AND source = ?2",
+            params![platform, source],
+            |row| row.get(0),
+        )
+        .optional()
+    })?;
+
+    Ok(newest
+        .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        .map(|dt| dt.with_timezone(&Utc)))
+}
+
+/// Move a source's watermark forward to `newest`; never moves it back.
+pub fn advance(platform: &str, source: &str, newest: DateTime<Utc>) -> Result<(), TrendrError> {
+    let newest = newest.to_rfc3339_opts(SecondsFormat::Secs, true);
+    with_db(|conn| {
+        conn.execute(
+            r#"INSERT INTO source_watermarks (platform, source, newest_at, updated_at)
+               VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)
+               ON CONFLICT(platform, source) DO UPDATE SET
+               newest_at = MAX(newest_at, excluded.newest_at), updated_at = CURRENT_TIMESTAMP"#,
+            params![platform, source, newest],
+        )?;
+        Ok(())
+    })
+}
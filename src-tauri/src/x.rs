@@ -1,8 +1,22 @@
-use crate::database::with_db;
-use crate::settings::XCredentials;
-use crate::topics::extract_topics;
-use rusqlite::params;
+use crate::content::{
+    cap_items, drop_untagged, truncate_chars, CollectionProgress, ContentType, ItemOutcome, Processed,
+    UNTAGGED_REASON,
+};
+use crate::database::{with_db, with_db_tx};
+use crate::error::TrendrError;
+use crate::hashtags;
+use crate::progress;
+use crate::rate_limit::{self, FetchError};
+use crate::refresh::{parse_timestamp, EngagementUpdate};
+use crate::sentiment;
+use crate::settings::{AppSettings, XCredentials};
+use crate::topics::{self, extract_item_topics, ItemText, TopicData};
+use crate::urls;
+use crate::watermarks;
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::Deserialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 // X API v2 Response Types
 #[derive(Debug, Deserialize)]
@@ -12,6 +26,36 @@ struct TweetSearchResponse {
     meta: Option<Meta>,
 }
 
+impl TweetSearchResponse {
+    /// Add a later page's tweets and includes, taking over its pagination state
+    fn append(&mut self, page: TweetSearchResponse) {
+        if let Some(tweets) = page.data {
+            self.data.get_or_insert_with(Vec::new).extend(tweets);
+        }
+        if let Some(includes) = page.includes {
+            let own = self.includes.get_or_insert(Includes { users: None, media: None });
+            if let Some(users) = includes.users {
+                own.users.get_or_insert_with(Vec::new).extend(users);
+            }
+            if let Some(media) = includes.media {
+                own.media.get_or_insert_with(Vec::new).extend(media);
+            }
+        }
+        match (&mut self.meta, page.meta) {
+            (Some(own), Some(meta)) => {
+                own.result_count = Some(own.result_count.unwrap_or(0) + meta.result_count.unwrap_or(0));
+                own.next_token = meta.next_token;
+            }
+            (Some(own), None) => own.next_token = None,
+            (own, meta) => *own = meta,
+        }
+    }
+
+    fn next_token(&self) -> Option<&str> {
+        self.meta.as_ref().and_then(|m| m.next_token.as_deref())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Tweet {
     id: String,
@@ -19,6 +63,67 @@ struct Tweet {
     author_id: String,
     created_at: Option<String>,
     public_metrics: Option<PublicMetrics>,
+    attachments: Option<Attachments>,
+    entities: Option<TweetEntities>,
+    /// Language X detected, e.g. "en"; "und" when it couldn't tell
+    lang: Option<String>,
+}
+
+impl Tweet {
+    fn post_url(&self) -> String {
+        format!("https://twitter.com/i/web/status/{}", self.id)
+    }
+
+    /// First outbound link, skipping links back to X itself (quoted tweets, attached media)
+    fn external_url(&self) -> Option<&str> {
+        self.entities
+            .as_ref()
+            .and_then(|e| e.urls.as_ref())?
+            .iter()
+            .filter_map(|u| u.expanded_url.as_deref())
+            .find(|url| {
+                let host = reqwest::Url::parse(url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(str::to_lowercase))
+                    .unwrap_or_default();
+                !matches!(host.as_str(), "x.com" | "twitter.com" | "www.x.com" | "www.twitter.com" | "t.co")
+            })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetEntities {
+    urls: Option<Vec<TweetUrl>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetUrl {
+    expanded_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Attachments {
+    media_keys: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Media {
+    media_key: String,
+    /// Set for photos
+    url: Option<String>,
+    /// Set for videos and animated GIFs
+    preview_image_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetLookupResponse {
+    data: Option<Vec<TweetMetrics>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetMetrics {
+    id: String,
+    public_metrics: Option<PublicMetrics>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,6 +138,7 @@ struct PublicMetrics {
 #[derive(Debug, Deserialize)]
 struct Includes {
     users: Option<Vec<XUser>>,
+    media: Option<Vec<Media>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,13 +201,21 @@ struct ErrorResponse {
 pub struct CollectionResult {
     pub posts_collected: u32,
     pub topics_extracted: u32,
+    pub outcomes: Vec<ItemOutcome>,
+    pub rate_limited: bool,
+    pub skipped_sources: Vec<String>,
+    /// Whether the run was cancelled before every source was collected
+    pub cancelled: bool,
 }
 
 const USER_AGENT: &str = "Trendr/1.0.0";
 const BASE_URL: &str = "https://api.twitter.com/2";
 
+/// Gap between requests when a response didn't report its rate limit window
+const DEFAULT_REQUEST_GAP: tokio::time::Duration = tokio::time::Duration::from_millis(1100);
+
 /// Test connection to X API using the bearer token
-pub async fn test_connection(bearer_token: &str) -> Result<bool, String> {
+pub async fn test_connection(bearer_token: &str) -> Result<bool, TrendrError> {
     let client = reqwest::Client::new();
 
     let response = client
@@ -110,22 +224,23 @@ pub async fn test_connection(bearer_token: &str) -> Result<bool, String> {
         .header("User-Agent", USER_AGENT)
         .send()
         .await
-        .map_err(|e| format!("Failed to connect to X API: {}", e))?;
+        .map_err(|e| TrendrError::Network(format!("Failed to connect to X API: {}", e)))?;
 
-    if response.status().is_success() {
+    let status = response.status();
+    if status.is_success() {
         let user_response: UserMeResponse = response
             .json()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+            .map_err(|e| TrendrError::Parse(format!("Failed to parse response: {}", e)))?;
 
         Ok(user_response.data.is_some())
-    } else if response.status() == 401 {
-        Err("Invalid bearer token. Please check your credentials.".to_string())
-    } else if response.status() == 403 {
-        Err("Access forbidden. Your API tier may not support this endpoint.".to_string())
+    } else if status == 401 {
+        Err(TrendrError::Auth("Invalid bearer token. Please check your credentials.".to_string()))
+    } else if status == 403 {
+        Err(TrendrError::Auth("Access forbidden. Your API tier may not support this endpoint.".to_string()))
     } else {
         let error_text = response.text().await.unwrap_or_default();
-        Err(format!("X API error: {}", error_text))
+        Err(TrendrError::from_status(status, format!("X API error: {}", error_text)))
     }
 }
 
@@ -133,30 +248,143 @@ pub async fn test_connection(bearer_token: &str) -> Result<bool, String> {
 pub async fn collect(
     credentials: &XCredentials,
     queries: &[String],
-) -> Result<CollectionResult, String> {
-    let client = reqwest::Client::new();
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+    on_progress: impl FnMut(CollectionProgress),
+) -> Result<CollectionResult, TrendrError> {
+    let client = &reqwest::Client::new();
+    let max_items = settings.max_items_per_query.x;
+    let max_pages = settings.max_pages_per_query.x;
+    let lang = search_language(settings.x_lang.as_deref());
+
+    collect_queries(queries, settings, cancel, on_progress, |query: String| async move {
+        search_tweets(client, &credentials.bearer_token, &query, lang, max_items, max_pages).await
+    })
+    .await
+}
+
+/// The per-query loop behind `collect`, with the search itself left to `search` so it
+/// can run without the API.
+async fn collect_queries<S, F>(
+    queries: &[String],
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(CollectionProgress),
+    mut search: S,
+) -> Result<CollectionResult, TrendrError>
+where
+    S: FnMut(String) -> F,
+    F: Future<Output = Result<(TweetSearchResponse, RateWindow), FetchError>>,
+{
+    let min_followers = settings.min_follower_count.x;
+    let max_items = settings.max_items_per_query.x;
+
+    // Loaded once for the whole run rather than once per tweet
+    let known_topics = topics::load_topics()?;
 
     let mut total_posts = 0u32;
     let mut total_topics = 0u32;
+    let mut outcomes = Vec::new();
+    let mut total_filtered = 0u32;
+    let mut total_untagged = 0u32;
+    let max_wait = tokio::time::Duration::from_secs(settings.rate_limit_max_wait_secs);
+    let mut rate_limited = false;
+    let mut skipped_sources = Vec::new();
+    let mut cancelled = false;
+
+    for (query_index, query) in queries.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            log::info!("X collection cancelled, {} queries not collected", queries.len() - query_index);
+            cancelled = true;
+            break;
+        }
 
-    for query in queries {
         log::info!("Searching X for: {}", query);
 
-        match search_tweets(&client, &credentials.bearer_token, query).await {
-            Ok(response) => {
-                if let Some(tweets) = response.data {
+        // Recent search returns newest tweets first
+        let cutoff = watermarks::cutoff(settings, "x", query, true);
+        let mut newest_seen = cutoff;
+
+        let mut searched = search(query.clone()).await;
+        if let Err(FetchError::RateLimited { retry_after }) = &searched {
+            if rate_limit::wait_for_reset(*retry_after, max_wait).await {
+                searched = search(query.clone()).await;
+            }
+        }
+
+        // Time until the window resets when this request used up the last of it
+        let mut exhausted_for = None;
+        let mut pace = DEFAULT_REQUEST_GAP;
+
+        match searched {
+            Ok((response, window)) => {
+                exhausted_for = window.exhausted_for();
+                pace = window.pace();
+                if let (Some(remaining), Some(reset_in)) = (window.remaining, window.reset_in) {
+                    log::info!(
+                        "X rate limit: {} requests left, window resets in {}s",
+                        remaining,
+                        reset_in.as_secs()
+                    );
+                }
+                if let Some(mut tweets) = response.data {
+                    cap_items(&mut tweets, max_items, "x", query);
                     let users_map = build_users_map(&response.includes);
+                    let media_map = build_media_map(&response.includes);
+                    let tweet_count = tweets.len();
+
+                    for (index, tweet) in tweets.into_iter().enumerate() {
+                        let created_at = tweet.created_at.as_deref().and_then(parse_timestamp);
+                        if let (Some(cutoff), Some(created_at)) = (cutoff, created_at) {
+                            if created_at <= cutoff {
+                                log::info!(
+                                    "Query '{}': reached previously collected tweets, skipping {} older",
+                                    query,
+                                    tweet_count - index
+                                );
+                                break;
+                            }
+                        }
+                        newest_seen = newest_seen.max(created_at);
 
-                    for tweet in tweets {
                         let author = users_map.get(&tweet.author_id);
 
-                        match process_tweet(&tweet, author).await {
-                            Ok(topics_found) => {
+                        // Only filter when follower data came back with the author
+                        let followers = author
+                            .and_then(|user| user.public_metrics.as_ref())
+                            .map(|m| m.followers_count);
+                        if let Some(count) = followers.filter(|&count| count < min_followers) {
+                            total_filtered += 1;
+                            outcomes.push(ItemOutcome::filtered(
+                                "x",
+                                &tweet.id,
+                                format!("Author has {} followers (minimum {})", count, min_followers),
+                            ));
+                            continue;
+                        }
+
+                        let media = tweet
+                            .attachments
+                            .as_ref()
+                            .and_then(|a| a.media_keys.as_ref())
+                            .and_then(|keys| keys.first())
+                            .and_then(|key| media_map.get(key))
+                            .copied();
+
+                        match process_tweet(&tweet, author, media, query, &known_topics, settings).await {
+                            Ok(Processed::Stored(topics_found)) => {
                                 total_posts += 1;
                                 total_topics += topics_found;
+                                outcomes.push(ItemOutcome::stored("x", &tweet.id, topics_found));
+                            }
+                            Ok(Processed::Duplicate) => outcomes.push(ItemOutcome::duplicate("x", &tweet.id)),
+                            Ok(Processed::Untagged) => {
+                                total_untagged += 1;
+                                outcomes.push(ItemOutcome::filtered("x", &tweet.id, UNTAGGED_REASON.to_string()));
                             }
                             Err(e) => {
                                 log::warn!("Failed to process tweet {}: {}", tweet.id, e);
+                                outcomes.push(ItemOutcome::failed("x", &tweet.id, e.to_string()));
                             }
                         }
                     }
@@ -169,22 +397,126 @@ pub async fn collect(
                         meta.result_count.unwrap_or(0)
                     );
                 }
+                progress::mark_done("x", query);
+            }
+            Err(FetchError::RateLimited { .. }) => {
+                rate_limited = true;
+                skipped_sources = queries[query_index..].to_vec();
+                break;
+            }
+            // Every query shares the token, so the rest would be rejected too
+            Err(FetchError::Unauthorized) => {
+                return Err(TrendrError::Auth(
+                    "X rejected the bearer token. Please check your credentials.".to_string(),
+                ));
             }
             Err(e) => {
                 log::error!("Failed to search X for '{}': {}", query, e);
             }
         }
 
-        // Rate limiting: ~1 second between requests to stay well under limits
-        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        if settings.only_newer_content {
+            if let Some(newest) = newest_seen.filter(|&newest| Some(newest) != cutoff) {
+                if let Err(e) = watermarks::advance("x", query, newest) {
+                    log::warn!("Failed to record watermark for '{}': {}", query, e);
+                }
+            }
+        }
+
+        on_progress(CollectionProgress::new("x", query_index + 1, queries.len(), total_posts));
+
+        let remaining_queries = &queries[query_index + 1..];
+        if !remaining_queries.is_empty() && exhausted_for.is_some() {
+            if !rate_limit::wait_for_reset(exhausted_for, max_wait).await {
+                rate_limited = true;
+                skipped_sources = remaining_queries.to_vec();
+                break;
+            }
+            continue;
+        }
+
+        // Spread the requests left in the rate limit window over the time until it resets
+        tokio::time::sleep(pace).await;
+    }
+
+    if rate_limited {
+        log::warn!("X rate limited, {} queries skipped", skipped_sources.len());
+    }
+
+    if total_filtered > 0 {
+        log::info!(
+            "Skipped {} tweets from authors with fewer than {} followers",
+            total_filtered,
+            min_followers
+        );
+    }
+
+    if total_untagged > 0 {
+        log::info!("Dropped {} X tweets that matched no topics", total_untagged);
     }
 
     Ok(CollectionResult {
         posts_collected: total_posts,
         topics_extracted: total_topics,
+        outcomes,
+        rate_limited,
+        skipped_sources,
+        cancelled,
     })
 }
 
+/// Look up current public metrics for already-collected tweets, 100 ids per request
+pub async fn fetch_engagement(
+    credentials: &XCredentials,
+    tweet_ids: &[String],
+) -> Result<Vec<EngagementUpdate>, TrendrError> {
+    let client = reqwest::Client::new();
+    let mut updates = Vec::new();
+
+    for (i, chunk) in tweet_ids.chunks(100).enumerate() {
+        if i > 0 {
+            tokio::time::sleep(DEFAULT_REQUEST_GAP).await;
+        }
+
+        let url = format!(
+            "{}/tweets?ids={}&tweet.fields=public_metrics",
+            BASE_URL,
+            chunk.join(",")
+        );
+
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", credentials.bearer_token))
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(TrendrError::from_status(status, format!("X API error: {}", status)));
+        }
+
+        let lookup: TweetLookupResponse = response
+            .json()
+            .await
+            .map_err(|e| TrendrError::Parse(format!("Failed to parse tweets: {}", e)))?;
+
+        for tweet in lookup.data.unwrap_or_default() {
+            if let Some(metrics) = tweet.public_metrics {
+                updates.push(EngagementUpdate {
+                    platform_id: tweet.id,
+                    likes: metrics.like_count,
+                    comments: metrics.reply_count,
+                    shares: metrics.retweet_count,
+                    views: metrics.impression_count,
+                });
+            }
+        }
+    }
+
+    Ok(updates)
+}
+
 /// Build a map of user_id -> XUser for easy lookup
 fn build_users_map(includes: &Option<Includes>) -> std::collections::HashMap<String, &XUser> {
     let mut map = std::collections::HashMap::new();
@@ -200,45 +532,154 @@ fn build_users_map(includes: &Option<Includes>) -> std::collections::HashMap<Str
     map
 }
 
-/// Search for recent tweets matching a query
+/// Build a map of media_key -> Media for attaching media to tweets
+fn build_media_map(includes: &Option<Includes>) -> std::collections::HashMap<String, &Media> {
+    let mut map = std::collections::HashMap::new();
+
+    if let Some(includes) = includes {
+        if let Some(media) = &includes.media {
+            for item in media {
+                map.insert(item.media_key.clone(), item);
+            }
+        }
+    }
+
+    map
+}
+
+/// The `lang:` code to restrict searches to. Blank means no restriction; anything
+/// other than a lowercase two or three letter code is ignored with a warning, since X
+/// rejects the whole query when the operator is malformed.
+fn search_language(setting: Option<&str>) -> Option<&str> {
+    let lang = setting.map(str::trim).filter(|lang| !lang.is_empty())?;
+    if (2..=3).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_lowercase()) {
+        Some(lang)
+    } else {
+        log::warn!("Ignoring X language filter '{}': expected a code like \"en\"", lang);
+        None
+    }
+}
+
+/// Search recent tweets, following `next_token` for up to `max_pages` pages or until
+/// `max_items` tweets are in. Paging stops early when a page uses up the rate limit
+/// window. If a later page fails, the tweets from earlier pages are kept. Alongside the
+/// combined response, returns the rate limit state reported by the last page.
 async fn search_tweets(
     client: &reqwest::Client,
     bearer_token: &str,
     query: &str,
-) -> Result<TweetSearchResponse, String> {
-    // Build search query - exclude retweets for cleaner data
-    let search_query = format!("{} -is:retweet", query);
+    lang: Option<&str>,
+    max_items: u32,
+    max_pages: u32,
+) -> Result<(TweetSearchResponse, RateWindow), FetchError> {
+    let (mut combined, mut window) =
+        rate_limit::retry_with_backoff(|| search_page(client, bearer_token, query, lang, max_items, None)).await?;
 
-    let url = format!(
-        "{}/tweets/search/recent?query={}&tweet.fields=id,text,author_id,created_at,public_metrics&user.fields=id,username,name,public_metrics&expansions=author_id&max_results=100",
+    let mut pages = 1;
+    while let Some(next_token) = next_page_token(&combined, &window, pages, max_pages, max_items) {
+        let fetched = combined.data.as_ref().map_or(0, Vec::len) as u32;
+        tokio::time::sleep(window.pace()).await;
+        let page = rate_limit::retry_with_backoff(|| {
+            search_page(client, bearer_token, query, lang, max_items - fetched, Some(next_token.as_str()))
+        })
+        .await;
+        match page {
+            Ok((page, page_window)) => {
+                combined.append(page);
+                window = page_window;
+                pages += 1;
+            }
+            Err(e) => {
+                log::warn!("Query '{}': stopped paging after {} tweets: {}", query, fetched, e);
+                break;
+            }
+        }
+    }
+
+    Ok((combined, window))
+}
+
+/// The `next_token` to fetch after `pages` pages have been combined, or None to stop: on
+/// the last page, at `max_pages` or `max_items`, or once `window` is used up.
+fn next_page_token(
+    combined: &TweetSearchResponse,
+    window: &RateWindow,
+    pages: u32,
+    max_pages: u32,
+    max_items: u32,
+) -> Option<String> {
+    let fetched = combined.data.as_ref().map_or(0, Vec::len) as u32;
+    if pages >= max_pages || fetched >= max_items || window.exhausted_for().is_some() {
+        return None;
+    }
+    combined.next_token().map(str::to_string)
+}
+
+/// The search expression sent for `query`: retweets are left out for cleaner data, and
+/// the `lang` filter is added unless the query already has its own `lang:` operator.
+fn search_query(query: &str, lang: Option<&str>) -> String {
+    let mut search_query = format!("{} -is:retweet", query);
+    let has_lang = query
+        .split_whitespace()
+        .any(|term| term.trim_start_matches(['-', '(']).to_ascii_lowercase().starts_with("lang:"));
+    if let Some(lang) = lang.filter(|_| !has_lang) {
+        search_query.push_str(&format!(" lang:{}", lang));
+    }
+    search_query
+}
+
+/// Recent search URL for one page of `query`
+fn search_url(query: &str, lang: Option<&str>, max_results: u32, next_token: Option<&str>) -> String {
+    let search_query = search_query(query, lang);
+    let mut url = format!(
+        "{}/tweets/search/recent?query={}&tweet.fields=id,text,author_id,created_at,public_metrics,attachments,entities,lang&user.fields=id,username,name,public_metrics&media.fields=media_key,type,url,preview_image_url&expansions=author_id,attachments.media_keys&max_results={}",
         BASE_URL,
-        urlencoding::encode(&search_query)
+        urlencoding::encode(&search_query),
+        max_results.clamp(10, 100)
     );
+    if let Some(next_token) = next_token {
+        url.push_str(&format!("&next_token={}", urlencoding::encode(next_token)));
+    }
+    url
+}
+
+fn parse_search_page(body: &str) -> Result<TweetSearchResponse, FetchError> {
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse tweets: {}", e).into())
+}
 
+/// Run one page of a recent search. Alongside the response, returns the rate limit
+/// state it reported.
+async fn search_page(
+    client: &reqwest::Client,
+    bearer_token: &str,
+    query: &str,
+    lang: Option<&str>,
+    max_results: u32,
+    next_token: Option<&str>,
+) -> Result<(TweetSearchResponse, RateWindow), FetchError> {
+    let url = search_url(query, lang, max_results, next_token);
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", bearer_token))
         .header("User-Agent", USER_AGENT)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .map_err(FetchError::network)?;
 
     let status = response.status();
+    let window = RateWindow::from_headers(response.headers());
 
     if status.is_success() {
-        let tweet_response: TweetSearchResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse tweets: {}", e))?;
-
-        Ok(tweet_response)
+        let body = response.text().await.map_err(FetchError::network)?;
+        Ok((parse_search_page(&body)?, window))
     } else if status == 429 {
-        // Rate limited
-        Err("Rate limited by X API. Please wait before collecting again.".to_string())
+        Err(FetchError::RateLimited { retry_after: window.reset_in })
     } else if status == 401 {
-        Err("Invalid bearer token".to_string())
+        Err(FetchError::Unauthorized)
     } else if status == 403 {
-        Err("Access forbidden. Your API tier may not support search.".to_string())
+        Err("Access forbidden. Your API tier may not support search.".to_string().into())
+    } else if status.is_server_error() {
+        Err(FetchError::server(&response, format!("X API error: HTTP {}", status)))
     } else {
         // Try to get error details
         let error_response: Result<ErrorResponse, _> = response.json().await;
@@ -258,12 +699,63 @@ async fn search_tweets(
             Err(_) => format!("HTTP {}", status),
         };
 
-        Err(format!("X API error: {}", error_msg))
+        Err(format!("X API error: {}", error_msg).into())
+    }
+}
+
+/// Rate limit state from a response's x-rate-limit-remaining and x-rate-limit-reset headers
+#[derive(Debug, Clone, Copy, Default)]
+struct RateWindow {
+    remaining: Option<u64>,
+    /// Time until the window resets
+    reset_in: Option<tokio::time::Duration>,
+}
+
+impl RateWindow {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<i64>().ok())
+        };
+        let reset_in = header("x-rate-limit-reset").map(|reset_at| {
+            let secs = (reset_at - chrono::Utc::now().timestamp()).max(0) as u64;
+            tokio::time::Duration::from_secs(secs + 1)
+        });
+
+        RateWindow {
+            remaining: header("x-rate-limit-remaining").map(|n| n.max(0) as u64),
+            reset_in,
+        }
+    }
+
+    /// Time until the window resets when this request used up the last of it
+    fn exhausted_for(&self) -> Option<tokio::time::Duration> {
+        self.reset_in.filter(|_| self.remaining == Some(0))
+    }
+
+    /// Gap before the next request that spreads the remaining calls evenly over what's
+    /// left of the window, or the fixed default when the headers were missing
+    fn pace(&self) -> tokio::time::Duration {
+        match (self.remaining, self.reset_in) {
+            (Some(remaining), Some(reset_in)) if remaining > 0 => {
+                reset_in / u32::try_from(remaining).unwrap_or(u32::MAX)
+            }
+            _ => DEFAULT_REQUEST_GAP,
+        }
     }
 }
 
 /// Process a single tweet and store it in the database
-async fn process_tweet(tweet: &Tweet, author: Option<&&XUser>) -> Result<u32, String> {
+async fn process_tweet(
+    tweet: &Tweet,
+    author: Option<&&XUser>,
+    media: Option<&Media>,
+    query: &str,
+    known_topics: &[TopicData],
+    settings: &AppSettings,
+) -> Result<Processed, TrendrError> {
     // Check if tweet already exists
     let exists = with_db(|conn| {
         let count: i64 = conn.query_row(
@@ -275,14 +767,27 @@ async fn process_tweet(tweet: &Tweet, author: Option<&&XUser>) -> Result<u32, St
     })?;
 
     if exists {
-        return Ok(0);
+        return Ok(Processed::Duplicate);
     }
 
-    // Get or create creator
-    let creator_id = match author {
-        Some(user) => get_or_create_creator(user)?,
-        None => get_or_create_creator_by_id(&tweet.author_id)?,
-    };
+    let text = truncate_chars(&tweet.text, settings.max_text_length);
+    if text.len() < tweet.text.len() {
+        log::info!("Truncated tweet {} to {} characters", tweet.id, settings.max_text_length);
+    }
+
+    // Extract topics from tweet text
+    let topics = extract_item_topics(
+        known_topics,
+        settings,
+        "x",
+        Some(query),
+        ItemText::Plain(text),
+    )?;
+    let topics_count = topics.len() as u32;
+
+    if drop_untagged(&topics, settings) {
+        return Ok(Processed::Untagged);
+    }
 
     // Insert content
     let content_id = uuid::Uuid::new_v4().to_string();
@@ -301,76 +806,76 @@ async fn process_tweet(tweet: &Tweet, author: Option<&&XUser>) -> Result<u32, St
     // Parse timestamp
     let published_at = tweet.created_at.clone().unwrap_or_default();
 
-    with_db(|conn| {
-        conn.execute(
+    // Photos carry a url; videos only a preview image, which doubles as the thumbnail
+    let (media_url, thumbnail_url) = match media {
+        Some(m) if settings.store_media_urls => (
+            m.url.clone().or_else(|| m.preview_image_url.clone()),
+            m.preview_image_url.clone().or_else(|| m.url.clone()),
+        ),
+        _ => (None, None),
+    };
+
+    let sentiment = sentiment::score(text);
+
+    // The creator, content row and topic links are written together or not at all
+    with_db_tx(|tx| {
+        let creator_id = match author {
+            Some(user) => get_or_create_creator(tx, user)?,
+            None => get_or_create_creator_by_id(tx, &tweet.author_id)?,
+        };
+        tx.execute(
             r#"INSERT INTO content (id, platform, platform_id, creator_id, content_type, text_content,
-               engagement_likes, engagement_comments, engagement_shares, engagement_views, published_at)
-               VALUES (?1, 'x', ?2, ?3, 'post', ?4, ?5, ?6, ?7, ?8, ?9)"#,
+               engagement_likes, engagement_comments, engagement_shares, engagement_views, published_at,
+               media_url, thumbnail_url, sentiment, url, language, source)
+               VALUES (?1, 'x', ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)"#,
             params![
                 &content_id,
                 &tweet.id,
                 &creator_id,
-                &tweet.text,
+                ContentType::Post.as_str(),
+                text,
                 likes,
                 comments,
                 shares,
                 views,
-                &published_at
+                &published_at,
+                &media_url,
+                &thumbnail_url,
+                sentiment,
+                tweet.post_url(),
+                &tweet.lang,
+                query
             ],
         )?;
-        Ok(())
+        urls::record_canonical_url(tx, &content_id, tweet.external_url(), settings.dedupe_by_url)?;
+        hashtags::record_tags(tx, &content_id, text)?;
+        topics::link_topics(tx, &content_id, &topics)
     })?;
 
-    // Extract topics from tweet text
-    let topics = extract_topics(&tweet.text)?;
-    let topics_count = topics.len() as u32;
-
-    // Link content to topics
-    for topic in &topics {
-        with_db(|conn| {
-            conn.execute(
-                "INSERT OR REPLACE INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, ?3)",
-                params![&content_id, &topic.topic_id, topic.confidence],
-            )?;
-            Ok(())
-        })?;
-    }
-
-    // Update co-occurrences if multiple topics found
-    if topics.len() > 1 {
-        let topic_ids: Vec<String> = topics.iter().map(|t| t.topic_id.clone()).collect();
-        update_cooccurrences(&topic_ids)?;
-    }
-
-    Ok(topics_count)
+    Ok(Processed::Stored(topics_count))
 }
 
 /// Get or create a creator from X user data
-fn get_or_create_creator(user: &XUser) -> Result<String, String> {
+fn get_or_create_creator(conn: &Connection, user: &XUser) -> Result<String, rusqlite::Error> {
     // Check if creator exists
-    let existing = with_db(|conn| {
-        let mut stmt = conn
-            .prepare("SELECT id FROM creators WHERE platform = 'x' AND platform_id = ?1")?;
-        let mut rows = stmt.query(params![&user.id])?;
-        if let Some(row) = rows.next()? {
-            let id: String = row.get(0)?;
-            Ok(Some(id))
-        } else {
-            Ok(None)
-        }
-    })?;
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM creators WHERE platform = 'x' AND platform_id = ?1",
+            params![&user.id],
+            |row| row.get(0),
+        )
+        .optional()?;
 
     if let Some(id) = existing {
-        // Update follower count if we have it
-        if let Some(metrics) = &user.public_metrics {
-            with_db(|conn| {
-                conn.execute(
-                    "UPDATE creators SET follower_count = ?1, display_name = ?2, updated_at = CURRENT_TIMESTAMP WHERE platform = 'x' AND platform_id = ?3",
-                    params![metrics.followers_count, &user.name, &user.id],
-                )?;
-                Ok(())
-            })?;
-        }
+        // Refresh profile fields. This also resolves placeholders created by
+        // get_or_create_creator_by_id, whose username is still the numeric id.
+        let follower_count = user.public_metrics.as_ref().map(|m| m.followers_count);
+        conn.execute(
+            r#"UPDATE creators SET username = ?1, display_name = ?2,
+               follower_count = COALESCE(?3, follower_count), updated_at = CURRENT_TIMESTAMP
+               WHERE platform = 'x' AND platform_id = ?4"#,
+            params![&user.username, &user.name, follower_count, &user.id],
+        )?;
         return Ok(id);
     }
 
@@ -382,31 +887,24 @@ fn get_or_create_creator(user: &XUser) -> Result<String, String> {
         .map(|m| m.followers_count)
         .unwrap_or(0);
 
-    with_db(|conn| {
-        conn.execute(
-            "INSERT INTO creators (id, platform, platform_id, username, display_name, follower_count) VALUES (?1, 'x', ?2, ?3, ?4, ?5)",
-            params![&creator_id, &user.id, &user.username, &user.name, follower_count],
-        )?;
-        Ok(())
-    })?;
+    conn.execute(
+        "INSERT INTO creators (id, platform, platform_id, username, display_name, follower_count) VALUES (?1, 'x', ?2, ?3, ?4, ?5)",
+        params![&creator_id, &user.id, &user.username, &user.name, follower_count],
+    )?;
 
     Ok(creator_id)
 }
 
 /// Get or create a creator when we only have the author_id (no user expansion)
-fn get_or_create_creator_by_id(author_id: &str) -> Result<String, String> {
+fn get_or_create_creator_by_id(conn: &Connection, author_id: &str) -> Result<String, rusqlite::Error> {
     // Check if creator exists
-    let existing = with_db(|conn| {
-        let mut stmt = conn
-            .prepare("SELECT id FROM creators WHERE platform = 'x' AND platform_id = ?1")?;
-        let mut rows = stmt.query(params![author_id])?;
-        if let Some(row) = rows.next()? {
-            let id: String = row.get(0)?;
-            Ok(Some(id))
-        } else {
-            Ok(None)
-        }
-    })?;
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM creators WHERE platform = 'x' AND platform_id = ?1",
+            params![author_id],
+            |row| row.get(0),
+        )
+        .optional()?;
 
     if let Some(id) = existing {
         return Ok(id);
@@ -414,39 +912,255 @@ fn get_or_create_creator_by_id(author_id: &str) -> Result<String, String> {
 
     // Create placeholder creator (we don't have username without expansion)
     let creator_id = uuid::Uuid::new_v4().to_string();
-    with_db(|conn| {
-        conn.execute(
-            "INSERT INTO creators (id, platform, platform_id, username) VALUES (?1, 'x', ?2, ?2)",
-            params![&creator_id, author_id],
-        )?;
-        Ok(())
-    })?;
+    conn.execute(
+        "INSERT INTO creators (id, platform, platform_id, username) VALUES (?1, 'x', ?2, ?2)",
+        params![&creator_id, author_id],
+    )?;
 
     Ok(creator_id)
 }
 
-/// Update topic co-occurrence counts
-fn update_cooccurrences(topic_ids: &[String]) -> Result<(), String> {
-    for i in 0..topic_ids.len() {
-        for j in (i + 1)..topic_ids.len() {
-            // Ensure consistent ordering (smaller id first)
-            let (a, b) = if topic_ids[i] < topic_ids[j] {
-                (&topic_ids[i], &topic_ids[j])
-            } else {
-                (&topic_ids[j], &topic_ids[i])
-            };
-
-            with_db(|conn| {
-                conn.execute(
-                    r#"INSERT INTO topic_cooccurrences (topic_a_id, topic_b_id, frequency, last_seen)
-                       VALUES (?1, ?2, 1, CURRENT_TIMESTAMP)
-                       ON CONFLICT(topic_a_id, topic_b_id) DO UPDATE SET
-                       frequency = frequency + 1, last_seen = CURRENT_TIMESTAMP"#,
-                    params![a, b],
-                )?;
-                Ok(())
-            })?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database;
+
+    /// Leaves next to no gap between queries
+    const OPEN_WINDOW: RateWindow = RateWindow {
+        remaining: Some(100),
+        reset_in: Some(tokio::time::Duration::from_millis(10)),
+    };
+
+    /// A search page holding tweets `ids`, all mentioning #rust
+    fn page(ids: std::ops::Range<usize>) -> TweetSearchResponse {
+        let tweets: Vec<_> = ids
+            .map(|id| {
+                serde_json::json!({
+                    "id": id.to_string(),
+                    "text": format!("Tweet {} about #rust and cargo", id),
+                    "author_id": "author",
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({ "data": tweets })).unwrap()
+    }
+
+    fn seed_rust_topic() {
+        with_db(|conn| {
+            conn.execute(
+                "INSERT INTO topics (id, name, slug, keywords) VALUES ('rust', 'Rust', 'rust', '[\"rust\"]')",
+                [],
+            )
+        })
+        .unwrap();
+    }
+
+    fn rate_window(remaining: Option<&str>, reset_at: Option<i64>) -> RateWindow {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(remaining) = remaining {
+            headers.insert("x-rate-limit-remaining", remaining.parse().unwrap());
         }
+        if let Some(reset_at) = reset_at {
+            headers.insert("x-rate-limit-reset", reset_at.to_string().parse().unwrap());
+        }
+        RateWindow::from_headers(&headers)
+    }
+
+    #[test]
+    fn an_exhausted_window_waits_until_its_reset() {
+        let now = chrono::Utc::now().timestamp();
+
+        // A second is added so the request lands after the reset, not on it
+        let window = rate_window(Some("0"), Some(now + 60));
+        let wait = window.exhausted_for().unwrap().as_secs();
+        assert!((60..=61).contains(&wait), "waited {}s", wait);
+
+        // A reset already past leaves only that second
+        let window = rate_window(Some("0"), Some(now - 60));
+        assert_eq!(window.exhausted_for(), Some(tokio::time::Duration::from_secs(1)));
+
+        assert_eq!(rate_window(Some("5"), Some(now + 60)).exhausted_for(), None);
+    }
+
+    fn search_body(ids: &[&str], next_token: Option<&str>) -> String {
+        let tweets: Vec<_> = ids
+            .iter()
+            .map(|id| serde_json::json!({"id": id, "text": format!("Tweet {}", id), "author_id": "author"}))
+            .collect();
+        serde_json::json!({
+            "data": tweets,
+            "includes": {"users": [{"id": "author", "username": "ferris", "name": "Ferris"}]},
+            "meta": {"result_count": ids.len(), "next_token": next_token},
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn search_pages_follow_next_token_until_the_last_page() {
+        let mut combined = parse_search_page(&search_body(&["1", "2"], Some("page 2"))).unwrap();
+        let next_token = next_page_token(&combined, &OPEN_WINDOW, 1, 5, 100).unwrap();
+        assert_eq!(next_token, "page 2");
+        assert!(search_url("rust", None, 100, Some(&next_token)).ends_with("&max_results=100&next_token=page%202"));
+        assert!(!search_url("rust", None, 100, None).contains("next_token"));
+
+        combined.append(parse_search_page(&search_body(&["3"], None)).unwrap());
+        let ids: Vec<&str> = combined.data.iter().flatten().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["1", "2", "3"]);
+        assert_eq!(combined.meta.as_ref().and_then(|m| m.result_count), Some(3));
+        assert_eq!(combined.includes.as_ref().and_then(|i| i.users.as_ref()).map(Vec::len), Some(2));
+        assert_eq!(next_page_token(&combined, &OPEN_WINDOW, 2, 5, 100), None);
+
+        assert!(parse_search_page("{\"data\": 5}").is_err());
+    }
+
+    #[test]
+    fn the_language_filter_reaches_the_query_once() {
+        let mut settings = AppSettings::default_settings();
+        settings.x_lang = Some(" en ".to_string());
+        let lang = search_language(settings.x_lang.as_deref());
+        assert_eq!(lang, Some("en"));
+
+        let url = search_url("rust", lang, 100, None);
+        assert!(url.contains("query=rust%20-is%3Aretweet%20lang%3Aen&"), "{}", url);
+
+        // A query with its own language operator keeps it rather than getting a second one
+        assert_eq!(search_query("rust lang:de", lang), "rust lang:de -is:retweet");
+        assert_eq!(search_query("(rust OR cargo) -LANG:ja", lang), "(rust OR cargo) -LANG:ja -is:retweet");
+        assert_eq!(search_query("rust language", lang), "rust language -is:retweet lang:en");
+        assert_eq!(search_query("rust", None), "rust -is:retweet");
+    }
+
+    #[test]
+    fn search_paging_stops_at_the_caps_or_an_exhausted_window() {
+        let first = parse_search_page(&search_body(&["1", "2"], Some("page 2"))).unwrap();
+        assert_eq!(next_page_token(&first, &OPEN_WINDOW, 2, 2, 100), None);
+        assert_eq!(next_page_token(&first, &OPEN_WINDOW, 1, 5, 2), None);
+
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(next_page_token(&first, &rate_window(Some("0"), Some(now + 60)), 1, 5, 100), None);
+        assert!(next_page_token(&first, &rate_window(Some("1"), Some(now + 60)), 1, 5, 100).is_some());
+    }
+
+    #[test]
+    fn requests_are_spread_over_what_is_left_of_the_window() {
+        let now = chrono::Utc::now().timestamp();
+
+        let gap = rate_window(Some("10"), Some(now + 99)).pace().as_secs_f64();
+        assert!((9.9..=10.1).contains(&gap), "paced {}s apart", gap);
+
+        // Without both headers, or once the window is used up, the default gap applies
+        assert_eq!(rate_window(None, None).pace(), DEFAULT_REQUEST_GAP);
+        assert_eq!(rate_window(Some("10"), None).pace(), DEFAULT_REQUEST_GAP);
+        assert_eq!(rate_window(Some("0"), Some(now + 60)).pace(), DEFAULT_REQUEST_GAP);
+        assert_eq!(rate_window(Some("many"), Some(now + 60)).remaining, None);
+    }
+
+    #[test]
+    fn topics_are_loaded_once_for_a_whole_run() {
+        let _db = database::test_database();
+        seed_rust_topic();
+        let settings = AppSettings::default_settings();
+        let queries = vec!["rust".to_string(), "cargo".to_string()];
+
+        let search = |query: String| async move {
+            let first = if query == "rust" { 0 } else { 50 };
+            Ok((page(first..first + 50), OPEN_WINDOW))
+        };
+
+        let loads_before = topics::LOAD_TOPICS_CALLS.load(Ordering::Relaxed);
+        let result = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(collect_queries(&queries, &settings, &AtomicBool::new(false), |_| {}, search))
+            .unwrap();
+
+        assert_eq!(result.posts_collected, 100);
+        assert_eq!(result.topics_extracted, 100);
+        assert_eq!(topics::LOAD_TOPICS_CALLS.load(Ordering::Relaxed) - loads_before, 1);
+    }
+
+    #[test]
+    fn progress_is_reported_once_per_query() {
+        let _db = database::test_database();
+        seed_rust_topic();
+        let settings = AppSettings::default_settings();
+        let queries = vec!["rust".to_string(), "cargo".to_string(), "ferris".to_string()];
+        let mut events = Vec::new();
+
+        let search = |query: String| async move {
+            match query.as_str() {
+                "rust" => Ok((page(0..3), OPEN_WINDOW)),
+                "cargo" => Ok((page(3..5), OPEN_WINDOW)),
+                _ => Err(FetchError::Failed("bad query".to_string())),
+            }
+        };
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(collect_queries(
+                &queries,
+                &settings,
+                &AtomicBool::new(false),
+                |progress| events.push(serde_json::to_value(progress).unwrap()),
+                search,
+            ))
+            .unwrap();
+
+        // A failed query still counts as done
+        let event = |current: u32, posts_collected: u32| {
+            serde_json::json!({"platform": "x", "current": current, "total": 3, "postsCollected": posts_collected})
+        };
+        assert_eq!(events, vec![event(1, 3), event(2, 5), event(3, 5)]);
+    }
+
+    #[test]
+    fn cancelling_stops_before_the_next_query() {
+        let _db = database::test_database();
+        seed_rust_topic();
+        let settings = AppSettings::default_settings();
+        let queries = vec!["rust".to_string(), "cargo".to_string()];
+        let cancel = AtomicBool::new(false);
+        let mut searched = Vec::new();
+
+        // Cancelled while the first query is being collected
+        let search = |query: String| {
+            searched.push(query);
+            cancel.store(true, Ordering::Relaxed);
+            async { Ok((page(0..5), OPEN_WINDOW)) }
+        };
+        let result = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(collect_queries(&queries, &settings, &cancel, |_| {}, search))
+            .unwrap();
+
+        assert!(result.cancelled);
+        assert_eq!(searched, ["rust"]);
+        assert_eq!(result.posts_collected, 5);
+        assert!(!result.rate_limited && result.skipped_sources.is_empty());
+    }
+
+    #[test]
+    fn a_failed_topic_link_rolls_back_the_whole_item() {
+        let _db = database::test_database();
+        seed_rust_topic();
+        let settings = AppSettings::default_settings();
+        let known_topics = topics::load_topics().unwrap();
+        // The topic goes away after it was loaded, so linking to it breaks its foreign key
+        with_db(|conn| conn.execute("DELETE FROM topics", [])).unwrap();
+
+        let tweet = page(0..1).data.unwrap().remove(0);
+        let processed = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(process_tweet(&tweet, None, None, "rust", &known_topics, &settings));
+        assert!(matches!(&processed, Err(TrendrError::Database(message)) if message.contains("FOREIGN KEY")));
+
+        let count = |sql: &str| -> i64 { with_db(|conn| conn.query_row(sql, [], |row| row.get(0))).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM content"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM creators"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM content_topics"), 0);
+    }
+
+    #[test]
+    fn tweets_link_to_their_status_page() {
+        let tweet = page(7..8).data.unwrap().remove(0);
+        assert_eq!(tweet.post_url(), "https://twitter.com/i/web/status/7");
     }
-    Ok(())
 }
@@ -1,8 +1,23 @@
-use crate::database::with_db;
-use crate::settings::YouTubeCredentials;
-use crate::topics::extract_topics;
-use rusqlite::params;
+use crate::content::{
+    cap_items, drop_untagged, truncate_body, CollectionProgress, ContentType, ItemOutcome, Processed,
+    UNTAGGED_REASON,
+};
+use crate::database::{with_db, with_db_tx};
+use crate::error::TrendrError;
+use crate::hashtags;
+use crate::progress;
+use crate::rate_limit::{self, FetchError, RateLimiter};
+use crate::refresh::EngagementUpdate;
+use crate::sentiment;
+use crate::settings::{AppSettings, YouTubeCredentials};
+use crate::topics::{self, extract_item_topics, ItemText, TopicData};
+use crate::urls;
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 const BASE_URL: &str = "https://www.googleapis.com/youtube/v3";
 const USER_AGENT: &str = "Trendr/1.0.0";
@@ -12,7 +27,6 @@ const USER_AGENT: &str = "Trendr/1.0.0";
 struct SearchResponse {
     items: Option<Vec<SearchItem>>,
     #[serde(rename = "nextPageToken")]
-    #[allow(dead_code)]
     next_page_token: Option<String>,
 }
 
@@ -50,6 +64,63 @@ struct VideoItem {
     id: String,
     snippet: Option<VideoSnippet>,
     statistics: Option<Statistics>,
+    #[serde(rename = "contentDetails")]
+    content_details: Option<ContentDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentDetails {
+    /// ISO-8601 duration such as PT1M30S; P0D for live and upcoming streams
+    duration: Option<String>,
+}
+
+impl VideoItem {
+    fn watch_url(&self) -> String {
+        format!("https://youtube.com/watch?v={}", self.id)
+    }
+
+    /// Length in seconds, or None for streams and durations that don't parse
+    fn duration_seconds(&self) -> Option<i64> {
+        self.content_details
+            .as_ref()
+            .and_then(|d| d.duration.as_deref())
+            .and_then(parse_duration)
+            .filter(|&secs| secs > 0)
+    }
+}
+
+/// Parse an ISO-8601 duration (PnDTnHnMnS, or PnW) into whole seconds. Fractional
+/// seconds are dropped; years and months, which YouTube never uses, aren't supported.
+fn parse_duration(duration: &str) -> Option<i64> {
+    let rest = duration.strip_prefix('P')?;
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut seconds = 0i64;
+    let mut in_time = false;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            '0'..='9' | '.' => number.push(c),
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                let unit_seconds = match (in_time, unit) {
+                    (false, 'W') => 7 * 86_400,
+                    (false, 'D') => 86_400,
+                    (true, 'H') => 3_600,
+                    (true, 'M') => 60,
+                    (true, 'S') => 1,
+                    _ => return None,
+                };
+                seconds += (value * unit_seconds as f64) as i64;
+            }
+        }
+    }
+
+    number.is_empty().then_some(seconds)
 }
 
 #[derive(Debug, Deserialize)]
@@ -64,6 +135,29 @@ struct VideoSnippet {
     published_at: String,
     #[allow(dead_code)]
     tags: Option<Vec<String>>,
+    thumbnails: Option<Thumbnails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thumbnails {
+    high: Option<Thumbnail>,
+    medium: Option<Thumbnail>,
+    default: Option<Thumbnail>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thumbnail {
+    url: String,
+}
+
+impl Thumbnails {
+    fn best_url(&self) -> Option<String> {
+        self.high
+            .as_ref()
+            .or(self.medium.as_ref())
+            .or(self.default.as_ref())
+            .map(|t| t.url.clone())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,14 +170,49 @@ struct Statistics {
     comment_count: Option<String>,
 }
 
+impl Statistics {
+    /// (views, likes, comments) — YouTube returns these as strings
+    fn parsed(&self) -> (Option<i64>, i64, i64) {
+        let parse = |v: &Option<String>| v.as_ref().and_then(|v| v.parse::<i64>().ok());
+        (
+            parse(&self.view_count),
+            parse(&self.like_count).unwrap_or(0),
+            parse(&self.comment_count).unwrap_or(0),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChannelResponse {
+    items: Option<Vec<ChannelItem>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChannelItem {
+    id: String,
+    statistics: Option<ChannelStatistics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChannelStatistics {
+    /// Absent when the channel hides its subscriber count
+    #[serde(rename = "subscriberCount")]
+    subscriber_count: Option<String>,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct CollectionResult {
     pub posts_collected: u32,
     pub topics_extracted: u32,
+    pub outcomes: Vec<ItemOutcome>,
+    pub rate_limited: bool,
+    pub skipped_sources: Vec<String>,
+    /// Whether the run was cancelled before every source was collected
+    pub cancelled: bool,
 }
 
 /// Test connection to YouTube API using the API key
-pub async fn test_connection(api_key: &str) -> Result<bool, String> {
+pub async fn test_connection(api_key: &str) -> Result<bool, TrendrError> {
     let client = reqwest::Client::new();
 
     // Simple test: search for a common term with minimal quota usage (100 units)
@@ -97,24 +226,28 @@ pub async fn test_connection(api_key: &str) -> Result<bool, String> {
         .header("User-Agent", USER_AGENT)
         .send()
         .await
-        .map_err(|e| format!("Failed to connect to YouTube API: {}", e))?;
+        .map_err(|e| TrendrError::Network(format!("Failed to connect to YouTube API: {}", e)))?;
 
-    if response.status().is_success() {
+    let status = response.status();
+    if status.is_success() {
         Ok(true)
-    } else if response.status() == 400 {
-        Err("Invalid API key format".to_string())
-    } else if response.status() == 403 {
+    } else if status == 400 {
+        Err(TrendrError::Auth("Invalid API key format".to_string()))
+    } else if status == 403 {
         // Try to parse error for more details
         let error_text = response.text().await.unwrap_or_default();
         if error_text.contains("quotaExceeded") {
-            Err("YouTube API quota exceeded for today".to_string())
+            // The daily quota resets at midnight Pacific time
+            Err(TrendrError::RateLimited { retry_after: None })
         } else if error_text.contains("API key not valid") {
-            Err("Invalid API key. Please check your credentials.".to_string())
+            Err(TrendrError::Auth("Invalid API key. Please check your credentials.".to_string()))
         } else {
-            Err("API key invalid or YouTube Data API v3 not enabled in Google Cloud Console".to_string())
+            Err(TrendrError::Auth(
+                "API key invalid or YouTube Data API v3 not enabled in Google Cloud Console".to_string(),
+            ))
         }
     } else {
-        Err(format!("YouTube API error: {}", response.status()))
+        Err(TrendrError::from_status(status, format!("YouTube API error: {}", status)))
     }
 }
 
@@ -122,101 +255,357 @@ pub async fn test_connection(api_key: &str) -> Result<bool, String> {
 pub async fn collect(
     credentials: &YouTubeCredentials,
     queries: &[String],
-) -> Result<CollectionResult, String> {
+    settings: &AppSettings,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(CollectionProgress),
+) -> Result<CollectionResult, TrendrError> {
     let client = reqwest::Client::new();
+    let min_subscribers = settings.min_follower_count.youtube;
+    let max_items = settings.max_items_per_query.youtube;
+    let max_pages = settings.max_pages_per_query.youtube;
+    let permits = Arc::new(Semaphore::new(settings.youtube_fetch.concurrency.max(1) as usize));
+    let limiter = RateLimiter::per_minute(settings.youtube_fetch.requests_per_minute);
+    // Set once the quota runs out; detail batches not yet started are skipped
+    let halted = Arc::new(AtomicBool::new(false));
+
+    // Loaded once for the whole run rather than once per video
+    let known_topics = topics::load_topics()?;
 
     let mut total_posts = 0u32;
     let mut total_topics = 0u32;
+    let mut outcomes = Vec::new();
+    let mut total_filtered = 0u32;
+    let mut total_untagged = 0u32;
+    let mut rate_limited = false;
+    let mut skipped_sources = Vec::new();
+    let mut cancelled = false;
+
+    for (query_index, query) in queries.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            log::info!("YouTube collection cancelled, {} queries not collected", queries.len() - query_index);
+            cancelled = true;
+            break;
+        }
 
-    for query in queries {
         log::info!("Searching YouTube for: {}", query);
 
-        match search_videos(&client, &credentials.api_key, query).await {
-            Ok(video_ids) => {
+        limiter.acquire().await;
+        match search_videos(&client, &credentials.api_key, query, max_items, max_pages, &limiter).await {
+            Ok(mut video_ids) => {
+                cap_items(&mut video_ids, max_items, "youtube", query);
                 if video_ids.is_empty() {
                     log::info!("No videos found for query: {}", query);
+                    progress::mark_done("youtube", query);
+                    on_progress(CollectionProgress::new("youtube", query_index + 1, queries.len(), total_posts));
                     continue;
                 }
 
                 log::info!("Found {} videos for query: {}", video_ids.len(), query);
 
-                // Batch fetch video details (up to 50 at a time for efficiency)
-                for chunk in video_ids.chunks(50) {
-                    match get_video_details(&client, &credentials.api_key, chunk).await {
-                        Ok(videos) => {
+                // Detail batches (up to 50 ids each) are fetched concurrently up to the
+                // configured limit; results are processed one batch at a time, in order,
+                // so database writes stay serialized.
+                let fetches: Vec<_> = video_ids
+                    .chunks(50)
+                    .map(|chunk| {
+                        let client = client.clone();
+                        let api_key = credentials.api_key.clone();
+                        let chunk = chunk.to_vec();
+                        let permits = Arc::clone(&permits);
+                        let limiter = limiter.clone();
+                        let halted = Arc::clone(&halted);
+                        tokio::spawn(async move {
+                            let _permit = permits
+                                .acquire_owned()
+                                .await
+                                .map_err(|e| FetchError::Failed(e.to_string()))?;
+                            if halted.load(Ordering::Relaxed) {
+                                return Err(FetchError::RateLimited { retry_after: None });
+                            }
+
+                            limiter.acquire().await;
+                            let videos = match get_video_details(&client, &api_key, &chunk).await {
+                                Ok(videos) => videos,
+                                Err(e) => {
+                                    if matches!(e, FetchError::RateLimited { .. }) {
+                                        halted.store(true, Ordering::Relaxed);
+                                    }
+                                    return Err(e);
+                                }
+                            };
+
+                            let subscribers = if min_subscribers > 0 {
+                                limiter.acquire().await;
+                                fetch_channel_subscribers(&client, &api_key, &videos)
+                                    .await
+                                    .unwrap_or_else(|e| {
+                                        log::warn!("Failed to get channel statistics: {}", e);
+                                        HashMap::new()
+                                    })
+                            } else {
+                                HashMap::new()
+                            };
+                            Ok((videos, subscribers))
+                        })
+                    })
+                    .collect();
+
+                let mut query_rate_limited = false;
+                for fetch in fetches {
+                    let fetched = fetch
+                        .await
+                        .unwrap_or_else(|e| Err(FetchError::Failed(e.to_string())));
+
+                    match fetched {
+                        Ok((videos, subscribers)) => {
                             for video in videos {
-                                match process_video(&video).await {
-                                    Ok(topics_found) => {
+                                // Channels with hidden or unknown counts are kept
+                                let channel_subscribers = video
+                                    .snippet
+                                    .as_ref()
+                                    .and_then(|s| subscribers.get(&s.channel_id));
+                                if let Some(count) = channel_subscribers.filter(|&&count| count < min_subscribers) {
+                                    total_filtered += 1;
+                                    outcomes.push(ItemOutcome::filtered(
+                                        "youtube",
+                                        &video.id,
+                                        format!("Channel has {} subscribers (minimum {})", count, min_subscribers),
+                                    ));
+                                    continue;
+                                }
+
+                                match process_video(&video, query, &known_topics, settings).await {
+                                    Ok(Processed::Stored(topics_found)) => {
                                         total_posts += 1;
                                         total_topics += topics_found;
+                                        outcomes.push(ItemOutcome::stored("youtube", &video.id, topics_found));
+                                    }
+                                    Ok(Processed::Duplicate) => {
+                                        outcomes.push(ItemOutcome::duplicate("youtube", &video.id))
+                                    }
+                                    Ok(Processed::Untagged) => {
+                                        total_untagged += 1;
+                                        outcomes.push(ItemOutcome::filtered(
+                                            "youtube",
+                                            &video.id,
+                                            UNTAGGED_REASON.to_string(),
+                                        ));
                                     }
                                     Err(e) => {
                                         log::warn!("Failed to process video {}: {}", video.id, e);
+                                        outcomes.push(ItemOutcome::failed(
+                                            "youtube",
+                                            &video.id,
+                                            e.to_string(),
+                                        ));
                                     }
                                 }
                             }
                         }
+                        Err(FetchError::RateLimited { .. }) => query_rate_limited = true,
                         Err(e) => {
                             log::error!("Failed to get video details: {}", e);
                         }
                     }
                 }
+
+                if query_rate_limited {
+                    rate_limited = true;
+                    skipped_sources = queries[query_index..].to_vec();
+                    log::warn!("YouTube quota exhausted, {} queries skipped", skipped_sources.len());
+                    break;
+                }
+                progress::mark_done("youtube", query);
+            }
+            // Quota resets daily, so there is no point waiting for it mid-run
+            Err(FetchError::RateLimited { .. }) => {
+                rate_limited = true;
+                skipped_sources = queries[query_index..].to_vec();
+                log::warn!("YouTube quota exhausted, {} queries skipped", skipped_sources.len());
+                break;
+            }
+            // Every query shares the key, so the rest would be rejected too
+            Err(FetchError::Unauthorized) => {
+                return Err(TrendrError::Auth(
+                    "YouTube rejected the API key. Please check your credentials.".to_string(),
+                ));
             }
             Err(e) => {
                 log::error!("Failed to search YouTube for '{}': {}", query, e);
             }
         }
 
+        on_progress(CollectionProgress::new("youtube", query_index + 1, queries.len(), total_posts));
+
         // Small delay between queries to be respectful
         tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
     }
 
+    if total_filtered > 0 {
+        log::info!(
+            "Skipped {} videos from channels with fewer than {} subscribers",
+            total_filtered,
+            min_subscribers
+        );
+    }
+
+    if total_untagged > 0 {
+        log::info!("Dropped {} YouTube videos that matched no topics", total_untagged);
+    }
+
     Ok(CollectionResult {
         posts_collected: total_posts,
         topics_extracted: total_topics,
+        outcomes,
+        rate_limited,
+        skipped_sources,
+        cancelled,
     })
 }
 
-/// Search for videos and return video IDs
+/// Look up current statistics for already-collected videos, 50 ids per request
+pub async fn fetch_engagement(
+    credentials: &YouTubeCredentials,
+    video_ids: &[String],
+) -> Result<Vec<EngagementUpdate>, TrendrError> {
+    let client = reqwest::Client::new();
+    let mut updates = Vec::new();
+
+    for chunk in video_ids.chunks(50) {
+        let videos = get_video_details(&client, &credentials.api_key, chunk).await?;
+
+        for video in videos {
+            if let Some(stats) = &video.statistics {
+                let (views, likes, comments) = stats.parsed();
+                updates.push(EngagementUpdate {
+                    platform_id: video.id,
+                    likes,
+                    comments,
+                    shares: 0,
+                    views,
+                });
+            }
+        }
+    }
+
+    Ok(updates)
+}
+
+/// Search for video ids, following `nextPageToken` for up to `max_pages` pages or until
+/// `max_items` ids are in. The caller has already taken a rate limiter slot for the first
+/// page. If a later page fails, the ids from earlier pages are kept.
 async fn search_videos(
     client: &reqwest::Client,
     api_key: &str,
     query: &str,
-) -> Result<Vec<String>, String> {
-    let url = format!(
-        "{}/search?part=snippet&q={}&maxResults=25&type=video&order=relevance&key={}",
+    max_items: u32,
+    max_pages: u32,
+    limiter: &RateLimiter,
+) -> Result<Vec<String>, FetchError> {
+    let max_items = max_items.max(1) as usize;
+    let mut video_ids = Vec::new();
+    let mut page_token: Option<String> = None;
+    let mut pages = 0;
+
+    loop {
+        let page_size = (max_items - video_ids.len()).min(50) as u32;
+        let fetched = rate_limit::retry_with_backoff(|| {
+            search_page(client, api_key, query, page_size, page_token.as_deref())
+        })
+        .await;
+        let page = match fetched {
+            Ok(page) => page,
+            Err(e) if !video_ids.is_empty() => {
+                log::warn!("Query '{}': stopped paging after {} videos: {}", query, video_ids.len(), e);
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        pages += 1;
+
+        page_token = next_page_token(&page, video_ids.len(), pages, max_pages, max_items);
+        video_ids.extend(page_video_ids(page));
+        if page_token.is_none() {
+            break;
+        }
+        limiter.acquire().await;
+    }
+
+    Ok(video_ids)
+}
+
+fn page_video_ids(page: SearchResponse) -> impl Iterator<Item = String> {
+    page.items.into_iter().flatten().filter_map(|item| item.id.video_id)
+}
+
+/// The `pageToken` to fetch once `page`, the `pages`th, is added to the `collected` ids so
+/// far, or None to stop: on the last page, or at `max_pages` or `max_items`.
+fn next_page_token(
+    page: &SearchResponse,
+    collected: usize,
+    pages: u32,
+    max_pages: u32,
+    max_items: usize,
+) -> Option<String> {
+    let page_ids = page.items.iter().flatten().filter(|item| item.id.video_id.is_some()).count();
+    if pages >= max_pages || collected + page_ids >= max_items {
+        return None;
+    }
+    page.next_page_token.clone().filter(|token| !token.is_empty())
+}
+
+fn search_url(api_key: &str, query: &str, max_results: u32, page_token: Option<&str>) -> String {
+    let mut url = format!(
+        "{}/search?part=snippet&q={}&maxResults={}&type=video&order=relevance&key={}",
         BASE_URL,
         urlencoding::encode(query),
+        max_results.clamp(1, 50),
         api_key
     );
+    if let Some(page_token) = page_token {
+        url.push_str(&format!("&pageToken={}", urlencoding::encode(page_token)));
+    }
+    url
+}
+
+fn parse_search_page(body: &str) -> Result<SearchResponse, FetchError> {
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse search response: {}", e).into())
+}
 
+async fn search_page(
+    client: &reqwest::Client,
+    api_key: &str,
+    query: &str,
+    max_results: u32,
+    page_token: Option<&str>,
+) -> Result<SearchResponse, FetchError> {
+    let url = search_url(api_key, query, max_results, page_token);
     let response = client
         .get(&url)
         .header("User-Agent", USER_AGENT)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .map_err(FetchError::network)?;
 
     let status = response.status();
 
     if status.is_success() {
-        let search_response: SearchResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse search response: {}", e))?;
-
-        let video_ids = search_response
-            .items
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|item| item.id.video_id)
-            .collect();
-
-        Ok(video_ids)
+        let body = response.text().await.map_err(FetchError::network)?;
+        parse_search_page(&body)
+    } else if status == 429 {
+        Err(FetchError::RateLimited { retry_after: None })
     } else if status == 403 {
-        Err("YouTube API quota exceeded or access forbidden".to_string())
+        // 403 covers both quota exhaustion and bad keys; the error reason tells them apart
+        let body = response.text().await.unwrap_or_default();
+        if body.contains("quotaExceeded") || body.contains("rateLimitExceeded") {
+            Err(FetchError::RateLimited { retry_after: None })
+        } else {
+            Err(FetchError::Unauthorized)
+        }
+    } else if status.is_server_error() {
+        Err(FetchError::server(&response, format!("YouTube API error: {}", status)))
     } else {
-        Err(format!("YouTube API error: {}", status))
+        Err(format!("YouTube API error: {}", status).into())
     }
 }
 
@@ -225,10 +614,10 @@ async fn get_video_details(
     client: &reqwest::Client,
     api_key: &str,
     video_ids: &[String],
-) -> Result<Vec<VideoItem>, String> {
+) -> Result<Vec<VideoItem>, FetchError> {
     let ids = video_ids.join(",");
     let url = format!(
-        "{}/videos?part=snippet,statistics&id={}&key={}",
+        "{}/videos?part=snippet,statistics,contentDetails&id={}&key={}",
         BASE_URL, ids, api_key
     );
 
@@ -239,8 +628,19 @@ async fn get_video_details(
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
-    if !response.status().is_success() {
-        return Err(format!("YouTube API error: {}", response.status()));
+    let status = response.status();
+    if status == 429 {
+        return Err(FetchError::RateLimited { retry_after: None });
+    }
+    if status == 403 {
+        let body = response.text().await.unwrap_or_default();
+        if body.contains("quotaExceeded") || body.contains("rateLimitExceeded") {
+            return Err(FetchError::RateLimited { retry_after: None });
+        }
+        return Err(FetchError::Unauthorized);
+    }
+    if !status.is_success() {
+        return Err(format!("YouTube API error: {}", status).into());
     }
 
     let video_response: VideoResponse = response
@@ -251,8 +651,66 @@ async fn get_video_details(
     Ok(video_response.items.unwrap_or_default())
 }
 
+/// Get subscriber counts for the channels behind a batch of videos (1 quota unit per 50)
+async fn fetch_channel_subscribers(
+    client: &reqwest::Client,
+    api_key: &str,
+    videos: &[VideoItem],
+) -> Result<HashMap<String, i64>, String> {
+    let mut channel_ids: Vec<&str> = videos
+        .iter()
+        .filter_map(|v| v.snippet.as_ref().map(|s| s.channel_id.as_str()))
+        .collect();
+    channel_ids.sort_unstable();
+    channel_ids.dedup();
+
+    let mut subscribers = HashMap::new();
+
+    for chunk in channel_ids.chunks(50) {
+        let url = format!(
+            "{}/channels?part=statistics&id={}&key={}",
+            BASE_URL,
+            chunk.join(","),
+            api_key
+        );
+
+        let response = client
+            .get(&url)
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("YouTube API error: {}", response.status()));
+        }
+
+        let channel_response: ChannelResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse channel response: {}", e))?;
+
+        for channel in channel_response.items.unwrap_or_default() {
+            let count = channel
+                .statistics
+                .and_then(|s| s.subscriber_count)
+                .and_then(|c| c.parse::<i64>().ok());
+            if let Some(count) = count {
+                subscribers.insert(channel.id, count);
+            }
+        }
+    }
+
+    Ok(subscribers)
+}
+
 /// Process a video and store in database
-async fn process_video(video: &VideoItem) -> Result<u32, String> {
+async fn process_video(
+    video: &VideoItem,
+    query: &str,
+    known_topics: &[TopicData],
+    settings: &AppSettings,
+) -> Result<Processed, TrendrError> {
     // Check if video already exists
     let exists = with_db(|conn| {
         let count: i64 = conn.query_row(
@@ -264,104 +722,107 @@ async fn process_video(video: &VideoItem) -> Result<u32, String> {
     })?;
 
     if exists {
-        return Ok(0);
+        return Ok(Processed::Duplicate);
+    }
+
+    let snippet = video
+        .snippet
+        .as_ref()
+        .ok_or_else(|| TrendrError::Parse("Missing video snippet".to_string()))?;
+
+    let (description, truncated) = truncate_body(
+        &snippet.title,
+        snippet.description.as_deref().unwrap_or(""),
+        settings.max_text_length,
+    );
+    if truncated {
+        log::info!("Truncated YouTube video {} to {} characters", video.id, settings.max_text_length);
     }
 
-    let snippet = video.snippet.as_ref().ok_or("Missing video snippet")?;
+    // Extract topics from video content
+    let topics = extract_item_topics(
+        known_topics,
+        settings,
+        "youtube",
+        Some(query),
+        ItemText::Titled {
+            title: &snippet.title,
+            body: description,
+        },
+    )?;
+    let topics_count = topics.len() as u32;
 
-    // Get or create creator (channel)
-    let creator_id = get_or_create_creator(&snippet.channel_id, &snippet.channel_title)?;
+    if drop_untagged(&topics, settings) {
+        return Ok(Processed::Untagged);
+    }
 
     // Build text content from title + description
-    let text_content = format!(
-        "{}\n\n{}",
-        snippet.title,
-        snippet.description.as_deref().unwrap_or("")
-    )
-    .trim()
-    .to_string();
-
-    // Parse engagement metrics (YouTube returns these as strings)
+    let text_content = format!("{}\n\n{}", snippet.title, description)
+        .trim()
+        .to_string();
+
+    let sentiment = sentiment::score(&text_content);
+
+    // The video itself is the media; only the thumbnail is worth storing
+    let thumbnail_url = if settings.store_media_urls {
+        snippet.thumbnails.as_ref().and_then(|t| t.best_url())
+    } else {
+        None
+    };
+
+    // Parse engagement metrics
     let (views, likes, comments) = match &video.statistics {
-        Some(stats) => (
-            stats
-                .view_count
-                .as_ref()
-                .and_then(|v| v.parse::<i64>().ok()),
-            stats
-                .like_count
-                .as_ref()
-                .and_then(|v| v.parse::<i64>().ok())
-                .unwrap_or(0),
-            stats
-                .comment_count
-                .as_ref()
-                .and_then(|v| v.parse::<i64>().ok())
-                .unwrap_or(0),
-        ),
+        Some(stats) => stats.parsed(),
         None => (None, 0, 0),
     };
 
     // Insert content
     let content_id = uuid::Uuid::new_v4().to_string();
+    let watch_url = video.watch_url();
 
-    with_db(|conn| {
-        conn.execute(
+    // The channel, content row and topic links are written together or not at all
+    with_db_tx(|tx| {
+        let creator_id = get_or_create_creator(tx, &snippet.channel_id, &snippet.channel_title)?;
+        tx.execute(
             r#"INSERT INTO content (id, platform, platform_id, creator_id, content_type, text_content,
-               engagement_likes, engagement_comments, engagement_views, published_at)
-               VALUES (?1, 'youtube', ?2, ?3, 'video', ?4, ?5, ?6, ?7, ?8)"#,
+               engagement_likes, engagement_comments, engagement_views, published_at, thumbnail_url, sentiment,
+               url, duration_seconds, source)
+               VALUES (?1, 'youtube', ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)"#,
             params![
                 &content_id,
                 &video.id,
                 &creator_id,
+                ContentType::Video.as_str(),
                 &text_content,
                 likes,
                 comments,
                 views,
-                &snippet.published_at
+                &snippet.published_at,
+                &thumbnail_url,
+                sentiment,
+                &watch_url,
+                video.duration_seconds(),
+                query
             ],
         )?;
-        Ok(())
+        urls::record_canonical_url(tx, &content_id, Some(&watch_url), settings.dedupe_by_url)?;
+        hashtags::record_tags(tx, &content_id, &text_content)?;
+        topics::link_topics(tx, &content_id, &topics)
     })?;
 
-    // Extract topics from video content
-    let topics = extract_topics(&text_content)?;
-    let topics_count = topics.len() as u32;
-
-    // Link content to topics
-    for topic in &topics {
-        with_db(|conn| {
-            conn.execute(
-                "INSERT OR REPLACE INTO content_topics (content_id, topic_id, confidence) VALUES (?1, ?2, ?3)",
-                params![&content_id, &topic.topic_id, topic.confidence],
-            )?;
-            Ok(())
-        })?;
-    }
-
-    // Update co-occurrences if multiple topics found
-    if topics.len() > 1 {
-        let topic_ids: Vec<String> = topics.iter().map(|t| t.topic_id.clone()).collect();
-        update_cooccurrences(&topic_ids)?;
-    }
-
-    Ok(topics_count)
+    Ok(Processed::Stored(topics_count))
 }
 
 /// Get or create a creator (YouTube channel)
-fn get_or_create_creator(channel_id: &str, channel_title: &str) -> Result<String, String> {
+fn get_or_create_creator(conn: &Connection, channel_id: &str, channel_title: &str) -> Result<String, rusqlite::Error> {
     // Check if creator exists
-    let existing = with_db(|conn| {
-        let mut stmt =
-            conn.prepare("SELECT id FROM creators WHERE platform = 'youtube' AND platform_id = ?1")?;
-        let mut rows = stmt.query(params![channel_id])?;
-        if let Some(row) = rows.next()? {
-            let id: String = row.get(0)?;
-            Ok(Some(id))
-        } else {
-            Ok(None)
-        }
-    })?;
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM creators WHERE platform = 'youtube' AND platform_id = ?1",
+            params![channel_id],
+            |row| row.get(0),
+        )
+        .optional()?;
 
     if let Some(id) = existing {
         return Ok(id);
@@ -369,39 +830,136 @@ fn get_or_create_creator(channel_id: &str, channel_title: &str) -> Result<String
 
     // Create new creator
     let creator_id = uuid::Uuid::new_v4().to_string();
-    with_db(|conn| {
-        conn.execute(
-            "INSERT INTO creators (id, platform, platform_id, username, display_name) VALUES (?1, 'youtube', ?2, ?2, ?3)",
-            params![&creator_id, channel_id, channel_title],
-        )?;
-        Ok(())
-    })?;
+    conn.execute(
+        "INSERT INTO creators (id, platform, platform_id, username, display_name) VALUES (?1, 'youtube', ?2, ?2, ?3)",
+        params![&creator_id, channel_id, channel_title],
+    )?;
 
     Ok(creator_id)
 }
 
-/// Update topic co-occurrence counts
-fn update_cooccurrences(topic_ids: &[String]) -> Result<(), String> {
-    for i in 0..topic_ids.len() {
-        for j in (i + 1)..topic_ids.len() {
-            // Ensure consistent ordering (smaller id first)
-            let (a, b) = if topic_ids[i] < topic_ids[j] {
-                (&topic_ids[i], &topic_ids[j])
-            } else {
-                (&topic_ids[j], &topic_ids[i])
-            };
-
-            with_db(|conn| {
-                conn.execute(
-                    r#"INSERT INTO topic_cooccurrences (topic_a_id, topic_b_id, frequency, last_seen)
-                       VALUES (?1, ?2, 1, CURRENT_TIMESTAMP)
-                       ON CONFLICT(topic_a_id, topic_b_id) DO UPDATE SET
-                       frequency = frequency + 1, last_seen = CURRENT_TIMESTAMP"#,
-                    params![a, b],
-                )?;
-                Ok(())
-            })?;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(duration: Option<&str>) -> VideoItem {
+        serde_json::from_value(serde_json::json!({
+            "id": "abc123",
+            "contentDetails": { "duration": duration },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_hours_minutes_and_seconds() {
+        assert_eq!(parse_duration("PT1H2M3S"), Some(3_723));
+        assert_eq!(parse_duration("PT15M"), Some(900));
+        assert_eq!(parse_duration("PT45S"), Some(45));
+        assert_eq!(parse_duration("PT2H"), Some(7_200));
+        assert_eq!(parse_duration("P1DT1S"), Some(86_401));
+        assert_eq!(parse_duration("P2W"), Some(1_209_600));
+        assert_eq!(parse_duration("PT1.9S"), Some(1));
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("P"), None);
+        assert_eq!(parse_duration("1H"), None);
+        assert_eq!(parse_duration("PT5"), None);
+        assert_eq!(parse_duration("P1M"), None);
+        assert_eq!(parse_duration("PT1X"), None);
+    }
+
+    #[test]
+    fn livestreams_have_no_length() {
+        assert_eq!(parse_duration("P0D"), Some(0));
+        assert_eq!(video(Some("P0D")).duration_seconds(), None);
+        assert_eq!(video(None).duration_seconds(), None);
+        assert_eq!(video(Some("PT4M13S")).duration_seconds(), Some(253));
+    }
+
+    fn search_body(video_ids: &[&str], next_page_token: Option<&str>) -> String {
+        let items: Vec<_> = video_ids
+            .iter()
+            .map(|id| serde_json::json!({"id": {"kind": "youtube#video", "videoId": id}}))
+            .collect();
+        serde_json::json!({"items": items, "nextPageToken": next_page_token}).to_string()
+    }
+
+    #[test]
+    fn search_pages_follow_page_token_until_the_last_page() {
+        let first = parse_search_page(&search_body(&["a", "b"], Some("CAIQAA"))).unwrap();
+        let page_token = next_page_token(&first, 0, 1, 5, 100).unwrap();
+        assert_eq!(page_token, "CAIQAA");
+        assert!(search_url("key", "rust lang", 50, Some(&page_token)).ends_with("&key=key&pageToken=CAIQAA"));
+        assert!(!search_url("key", "rust lang", 50, None).contains("pageToken"));
+
+        let last = parse_search_page(&search_body(&["c"], None)).unwrap();
+        assert_eq!(next_page_token(&last, 2, 2, 5, 100), None);
+        let ids: Vec<String> = page_video_ids(first).chain(page_video_ids(last)).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+
+        // YouTube sends an empty token rather than none on some last pages
+        let empty_token = parse_search_page(&search_body(&["d"], Some(""))).unwrap();
+        assert_eq!(next_page_token(&empty_token, 0, 1, 5, 100), None);
+        assert!(parse_search_page("not json").is_err());
+    }
+
+    #[test]
+    fn search_paging_stops_at_the_page_and_item_caps() {
+        let page = parse_search_page(&search_body(&["a", "b"], Some("CAIQAA"))).unwrap();
+        assert_eq!(next_page_token(&page, 0, 2, 2, 100), None);
+        assert_eq!(next_page_token(&page, 8, 1, 5, 10), None);
+        assert!(next_page_token(&page, 7, 1, 5, 10).is_some());
+    }
+
+    #[test]
+    fn videos_link_to_their_watch_page() {
+        assert_eq!(video(None).watch_url(), "https://youtube.com/watch?v=abc123");
+    }
+
+    #[test]
+    fn a_stored_video_reads_back_its_link_length_and_metrics() {
+        let _db = crate::database::test_database();
+        with_db(|conn| {
+            conn.execute(
+                "INSERT INTO topics (id, name, slug, keywords) VALUES ('rust', 'Rust', 'rust', '[\"rust\"]')",
+                [],
+            )
+        })
+        .unwrap();
+        let video: VideoItem = serde_json::from_value(serde_json::json!({
+            "id": "abc123",
+            "snippet": {
+                "title": "Learning Rust in an hour",
+                "description": "A quick tour of rust ownership",
+                "channelId": "UC1",
+                "channelTitle": "Rust Channel",
+                "publishedAt": "2024-05-01T12:00:00Z",
+            },
+            "statistics": { "viewCount": "12345", "likeCount": "678", "commentCount": "90" },
+            "contentDetails": { "duration": "PT1H2M3S" },
+        }))
+        .unwrap();
+
+        let known_topics = topics::load_topics().unwrap();
+        let processed = tokio::runtime::Runtime::new().unwrap().block_on(process_video(
+            &video,
+            "rust",
+            &known_topics,
+            &AppSettings::default_settings(),
+        ));
+        assert!(matches!(processed, Ok(Processed::Stored(1))));
+
+        let content = crate::commands::get_content(None, None, Some("youtube".to_string()), None, None, None).unwrap();
+        let stored = serde_json::to_value(&content).unwrap();
+        let stored = &stored[0];
+        assert_eq!(stored["url"], "https://youtube.com/watch?v=abc123");
+        assert_eq!(stored["durationSeconds"], 3_723);
+        assert_eq!(stored["engagementViews"], 12_345);
+        assert_eq!(stored["engagementLikes"], 678);
+        assert_eq!(stored["engagementComments"], 90);
+        assert_eq!(stored["engagementShares"], 0);
     }
-    Ok(())
 }
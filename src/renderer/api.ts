@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 // Types
 export interface RedditCredentials {
@@ -16,6 +17,96 @@ export interface YouTubeCredentials {
   apiKey: string;
 }
 
+export interface RefreshTier {
+  maxAgeHours: number;
+  intervalMinutes: number;
+}
+
+export interface EngagementRefreshSettings {
+  enabled: boolean;
+  tiers: RefreshTier[];
+  maxItemsPerRun: number;
+  markMissingAsDeleted: boolean;
+}
+
+export interface ExtractionSettings {
+  dedupeOverlappingMatches: boolean;
+  titleWeight: number;
+  bodyWeight: number;
+  allowSingleCharKeywords?: boolean;
+}
+
+export interface MinFollowerCount {
+  x: number;
+  youtube: number;
+}
+
+export interface MaxItemsPerQuery {
+  reddit: number;
+  x: number;
+  youtube: number;
+}
+
+export interface MaxPagesPerQuery {
+  x: number;
+  youtube: number;
+}
+
+export interface TrendScoreSettings {
+  windowDays: number;
+  volumeWeight: number;
+  momentumWeight: number;
+  recencyWeight: number;
+  engagementWeight: number;
+}
+
+export interface AlertSettings {
+  trendSpikeRatio: number;
+  minAbsoluteCount: number;
+  cooldownHours: number;
+  enabledAlertTypes: string[];
+}
+
+export interface SourceWeights {
+  reddit: Record<string, number>;
+  x: Record<string, number>;
+  youtube: Record<string, number>;
+}
+
+export interface RedditFetchSettings {
+  concurrency: number;
+  requestsPerMinute: number;
+  collectComments: boolean;
+  commentsPerPost: number;
+  sort: 'hot' | 'new' | 'top' | 'rising' | 'controversial';
+  time: 'hour' | 'day' | 'week' | 'month' | 'year' | 'all';
+}
+
+export interface YouTubeFetchSettings {
+  concurrency: number;
+  requestsPerMinute: number;
+}
+
+export interface AutoCreateTopicsSettings {
+  enabled: boolean;
+  minMentions: number;
+  windowHours: number;
+  maxPerDay: number;
+}
+
+export interface TextCleaners {
+  stripMarkdown: boolean;
+  stripHtml: boolean;
+  stripMentions: boolean;
+  stripUrls: boolean;
+}
+
+export interface PreprocessingSettings {
+  reddit: TextCleaners;
+  x: TextCleaners;
+  youtube: TextCleaners;
+}
+
 export interface AppSettings {
   reddit: RedditCredentials | null;
   x: XCredentials | null;
@@ -23,18 +114,289 @@ export interface AppSettings {
   collectionIntervalMinutes: number;
   subreddits: string[];
   xQueries: string[];
+  xLang?: string | null;
   youtubeQueries: string[];
   searchQueries: string[];
+  engagementRefresh?: EngagementRefreshSettings;
+  extraction?: ExtractionSettings;
+  minFollowerCount?: MinFollowerCount;
+  maxItemsPerQuery?: MaxItemsPerQuery;
+  maxPagesPerQuery?: MaxPagesPerQuery;
+  trendScore?: TrendScoreSettings;
+  alerts?: AlertSettings;
+  maxTextLength?: number;
+  storeMediaUrls?: boolean;
+  sourceWeights?: SourceWeights;
+  stalledRunThreshold?: number;
+  redditFetch?: RedditFetchSettings;
+  youtubeFetch?: YouTubeFetchSettings;
+  onlyNewerContent?: boolean;
+  autoCreateTopics?: AutoCreateTopicsSettings;
+  creatorGrouping?: boolean;
+  rateLimitMaxWaitSecs?: number;
+  dedupeByUrl?: boolean;
+  alertsRssPath?: string | null;
+  alertRetentionDays?: number;
+  maxQueriesPerRun?: number;
+  storeUntaggedContent?: boolean;
+  preprocessing?: PreprocessingSettings;
 }
 
+// A bare term, or one that counts for more or less than usual toward confidence
+export type TopicKeyword = string | { term: string; weight: number };
+
 export interface Topic {
   id: string;
   name: string;
   slug: string;
   parentTopicId: string | null;
+  keywords: TopicKeyword[];
   aliases: string[];
+  exclusions: string[];
   createdAt: string;
   contentCount?: number;
+  trendScore?: number | null;
+  autoCreatedFrom?: string | null;
+}
+
+export interface TrendScore {
+  topicId: string;
+  topicName: string;
+  score: number;
+  volume: number;
+  momentum: number;
+  recency: number;
+  engagement: number;
+  computedAt: string;
+}
+
+export interface SimulatedAlert {
+  topicId: string;
+  topicName: string;
+  date: string;
+  count: number;
+  baseline: number;
+  spikeFactor: number;
+}
+
+export interface SlugPreview {
+  slug: string;
+  slugTaken: boolean;
+  nameTaken: boolean;
+  conflictingTopicId: string | null;
+}
+
+export interface ActiveTopic {
+  id: string;
+  name: string;
+  slug: string;
+  recentCount: number;
+  lastActivityAt: string | null;
+}
+
+export interface SimilarTopic {
+  id: string;
+  name: string;
+  sharedContent: number;
+  jaccard: number;
+  cosine: number;
+}
+
+export interface TableImportCount {
+  table: string;
+  inserted: number;
+  skipped: number;
+}
+
+export interface ImportResult {
+  tables: TableImportCount[];
+  inserted: number;
+  skipped: number;
+}
+
+export interface PruneResult {
+  deleted: number;
+  vacuumed: boolean;
+}
+
+export interface TimeseriesPoint {
+  bucket: string;
+  count: number;
+}
+
+export interface ConfidenceBin {
+  min: number;
+  max: number;
+  count: number;
+}
+
+export interface HierarchyLinkSuggestion {
+  topicAId: string;
+  topicAName: string;
+  topicBId: string;
+  topicBName: string;
+  frequency: number;
+  sharedContent: number;
+  overlap: number;
+  suggestedParentId: string;
+}
+
+export interface TopicCooccurrence {
+  topicAId: string;
+  topicAName: string;
+  topicBId: string;
+  topicBName: string;
+  frequency: number;
+  lastSeen: string | null;
+}
+
+export interface KeywordHit {
+  keyword: string;
+  count: number;
+}
+
+export interface TopicMatch {
+  topicId: string;
+  topicName: string;
+  keywordHits: KeywordHit[];
+  mentions: number;
+  confidence: number;
+}
+
+export interface TextAnalysis {
+  normalizedText: string;
+  matches: TopicMatch[];
+  linkedTopicIds: string[];
+  sentiment: number | null;
+}
+
+export interface SentimentShift {
+  topicId: string;
+  topicName: string;
+  recentAverage: number;
+  trailingAverage: number;
+  delta: number;
+  recentSamples: number;
+  trailingSamples: number;
+}
+
+export interface DailyCount {
+  date: string;
+  count: number;
+}
+
+export interface ForecastPoint {
+  date: string;
+  projected: number;
+  lower: number;
+  upper: number;
+}
+
+export interface TopicForecast {
+  topicId: string;
+  topicName: string;
+  slopePerDay: number;
+  history: DailyCount[];
+  points: ForecastPoint[];
+  projectedTotal: number;
+  projectedTotalLower: number;
+  projectedTotalUpper: number;
+}
+
+export interface TrendAuthenticity {
+  topicId: string;
+  topicName: string;
+  windowHours: number;
+  contentCount: number;
+  baseline: number;
+  spikeFactor: number;
+  spiking: boolean;
+  distinctCreators: number;
+  newAccountShare: number;
+  lowFollowerShare: number | null;
+  duplicateTextShare: number;
+  topCreatorShare: number;
+  suspectedCoordinated: boolean;
+  reasons: string[];
+}
+
+export interface KeywordStat {
+  topicId: string;
+  topicName: string;
+  keyword: string;
+  matchCount: number;
+  lastMatchedAt: string | null;
+}
+
+export interface KeywordStats {
+  keywords: KeywordStat[];
+  scannedContent: number;
+  totalContent: number;
+  sampled: boolean;
+}
+
+export interface KeywordSuggestion {
+  term: string;
+  contentCount: number;
+  occurrences: number;
+}
+
+export interface ReextractResult {
+  contentProcessed: number;
+  linksCreated: number;
+}
+
+export interface ReextractProgress {
+  processed: number;
+  total: number;
+}
+
+export interface PlatformShare {
+  platform: string;
+  count: number;
+  share: number;
+}
+
+export interface TopicCreator {
+  creatorId: string;
+  platform: string;
+  username: string;
+  displayName: string | null;
+  followerCount: number | null;
+  contentCount: number;
+  share: number;
+  totalEngagement: number;
+}
+
+export interface TopicStats {
+  topicId: string;
+  name: string;
+  contentCount: number;
+  recentCount: number;
+  totalEngagement: number;
+  trendScore: number | null;
+  lastSeenAt: string | null;
+}
+
+export interface TrendingEntity {
+  id: string;
+  name: string;
+  mentions: number;
+  contentCount: number;
+  previousMentions: number;
+}
+
+export interface TrendingTag {
+  tag: string;
+  kind: 'hashtag' | 'cashtag';
+  contentCount: number;
+  previousCount: number;
+}
+
+export interface EntityTagResult {
+  scanned: number;
+  tagged: number;
+  entitiesLinked: number;
 }
 
 export interface Content {
@@ -46,8 +408,98 @@ export interface Content {
   textContent: string | null;
   engagementLikes: number;
   engagementComments: number;
+  engagementShares: number;
+  engagementViews: number | null;
   publishedAt: string | null;
   collectedAt: string;
+  mediaUrl: string | null;
+  thumbnailUrl: string | null;
+  status: 'active' | 'deleted' | 'removed';
+  duplicateOf?: string | null;
+  url?: string | null;
+  durationSeconds?: number | null;
+  language?: string | null;
+}
+
+export interface TopContent extends Content {
+  engagement: number;
+  followerCount: number | null;
+  engagementRate: number | null;
+  rankedByRaw: boolean;
+}
+
+export interface ContentDateRange {
+  earliestPublishedAt: string | null;
+  latestPublishedAt: string | null;
+  earliestCollectedAt: string | null;
+  latestCollectedAt: string | null;
+}
+
+export interface ContentTypeCount {
+  contentType: string;
+  count: number;
+}
+
+export interface TagResult {
+  scanned: number;
+  tagged: number;
+  linksCreated: number;
+}
+
+export interface Creator {
+  id: string;
+  platform: string;
+  platformId: string;
+  username: string;
+  displayName: string | null;
+  followerCount: number | null;
+  primaryTopics: string[];
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CreatorSummary extends Creator {
+  contentCount: number;
+}
+
+export interface CreatorDetails extends CreatorSummary {
+  recentContent: Content[];
+}
+
+export interface CreatorGroup {
+  handle: string;
+  platforms: string[];
+  totalFollowers: number;
+  creators: Creator[];
+}
+
+export interface SnapshotSummary {
+  label: string;
+  createdAt: string;
+  totalContent: number;
+  topicCount: number;
+}
+
+export interface TopicDelta {
+  topicId: string;
+  name: string;
+  contentBefore: number;
+  contentNow: number;
+  contentDelta: number;
+  recentBefore: number;
+  recentNow: number;
+  trendScoreBefore: number | null;
+  trendScoreNow: number | null;
+  isNew: boolean;
+  isRemoved: boolean;
+}
+
+export interface SnapshotComparison {
+  label: string;
+  pinnedAt: string;
+  totalContentBefore: number;
+  totalContentNow: number;
+  topics: TopicDelta[];
 }
 
 export interface Alert {
@@ -64,18 +516,95 @@ export interface DashboardStats {
   totalTopics: number;
   totalCreators: number;
   contentLast7Days: number;
+  windowDays: number;
+  contentInWindow: number;
   topTopics: { name: string; count: number }[];
 }
 
+export interface RotationPosition {
+  platform: string;
+  offset: number;
+  totalSources: number;
+}
+
 export interface CollectionStatus {
   isRunning: boolean;
   lastRunAt: string | null;
   lastError: string | null;
+  rotation: RotationPosition[];
+}
+
+export interface SchedulerStatus {
+  isRunning: boolean;
+  isPaused: boolean;
+  intervalMinutes: number;
+  lastRunAt: string | null;
+  nextRunAt: string | null;
+}
+
+export interface ItemOutcome {
+  platform: string;
+  platformId: string;
+  status: 'stored' | 'duplicate' | 'filtered' | 'error';
+  topicsFound: number;
+  reason: string | null;
 }
 
 export interface CollectionResult {
   postsCollected: number;
   topicsExtracted: number;
+  outcomes: ItemOutcome[];
+  rateLimited?: boolean;
+  skippedSources?: string[];
+  cancelled?: boolean;
+}
+
+export interface AllCollectionResult {
+  reddit: CollectionResult | null;
+  x: CollectionResult | null;
+  youtube: CollectionResult | null;
+  postsCollected: number;
+  topicsExtracted: number;
+  cancelled: boolean;
+  errors: string[];
+}
+
+export interface CollectionProgress {
+  platform: 'reddit' | 'x' | 'youtube';
+  current: number;
+  total: number;
+  postsCollected: number;
+}
+
+export interface RefreshResult {
+  itemsDue: number;
+  itemsUpdated: number;
+  itemsDeleted: number;
+}
+
+// Connection tests and collection commands reject with this; other commands still
+// reject with a plain string
+export interface TrendrError {
+  kind: 'auth' | 'rateLimited' | 'network' | 'database' | 'notConfigured' | 'parse' | 'other';
+  message: string;
+  retryAfterSecs: number | null;
+}
+
+export function errorMessage(error: unknown): string {
+  if (typeof error === 'string') return error;
+  if (error && typeof error === 'object' && 'message' in error) {
+    return String((error as { message: unknown }).message);
+  }
+  return String(error);
+}
+
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export interface LogLine {
+  level: string;
+  target: string;
+  message: string;
+  timestamp: string;
 }
 
 // API functions
@@ -94,6 +623,15 @@ export const api = {
   testYouTubeConnection: (apiKey: string): Promise<boolean> =>
     invoke('test_youtube_connection', { apiKey }),
 
+  // Logging
+  getLogPath: (): Promise<string> => invoke('get_log_path'),
+
+  setLogStreamLevel: (level: LogLevel): Promise<void> =>
+    invoke('set_log_stream_level', { level }),
+
+  onLogLine: (handler: (line: LogLine) => void): Promise<UnlistenFn> =>
+    listen<LogLine>('log://line', (event) => handler(event.payload)),
+
   // Collection
   runCollection: (): Promise<CollectionResult> => invoke('run_collection'),
 
@@ -101,11 +639,35 @@ export const api = {
 
   runYouTubeCollection: (): Promise<CollectionResult> => invoke('run_youtube_collection'),
 
+  runAllCollection: (): Promise<AllCollectionResult> => invoke('run_all_collection'),
+
   getCollectionStatus: (): Promise<CollectionStatus> => invoke('get_collection_status'),
 
+  cancelCollection: (): Promise<void> => invoke('cancel_collection'),
+
+  onCollectionProgress: (handler: (progress: CollectionProgress) => void): Promise<UnlistenFn> =>
+    listen<CollectionProgress>('collection-progress', (event) => handler(event.payload)),
+
+  onCollectionFinished: (handler: (result: CollectionResult) => void): Promise<UnlistenFn> =>
+    listen<CollectionResult>('collection-finished', (event) => handler(event.payload)),
+
+  getSchedulerStatus: (): Promise<SchedulerStatus> => invoke('get_scheduler_status'),
+
+  startScheduler: (): Promise<SchedulerStatus> => invoke('start_scheduler'),
+
+  stopScheduler: (): Promise<SchedulerStatus> => invoke('stop_scheduler'),
+
+  resetCursor: (platform: string, source?: string): Promise<number> =>
+    invoke('reset_cursor', { platform, source }),
+
+  refreshEngagement: (): Promise<RefreshResult> => invoke('refresh_engagement'),
+
   // Topics
-  getTopics: (limit?: number, offset?: number): Promise<Topic[]> =>
-    invoke('get_topics', { limit, offset }),
+  getTopics: (
+    limit?: number,
+    offset?: number,
+    sortBy?: 'content' | 'trend' | 'name'
+  ): Promise<Topic[]> => invoke('get_topics', { limit, offset, sortBy }),
 
   getTopicDetails: (topicId: string): Promise<Topic> =>
     invoke('get_topic_details', { topicId }),
@@ -113,21 +675,192 @@ export const api = {
   searchTopics: (query: string): Promise<Topic[]> =>
     invoke('search_topics', { query }),
 
+  previewTopicSlug: (name: string): Promise<SlugPreview> =>
+    invoke('preview_topic_slug', { name }),
+
+  computeTrendScores: (): Promise<TrendScore[]> => invoke('compute_trend_scores'),
+
+  getTrendScores: (limit?: number): Promise<TrendScore[]> =>
+    invoke('get_trend_scores', { limit }),
+
+  simulateTrendDetection: (threshold: number, days?: number): Promise<SimulatedAlert[]> =>
+    invoke('simulate_trend_detection', { threshold, days }),
+
+  getTopicForecast: (topicId: string, horizonDays?: number): Promise<TopicForecast> =>
+    invoke('get_topic_forecast', { topicId, horizonDays }),
+
+  getTrendAuthenticity: (topicId: string, hours?: number): Promise<TrendAuthenticity> =>
+    invoke('get_trend_authenticity', { topicId, hours }),
+
+  getActiveTopics: (days?: number, limit?: number): Promise<ActiveTopic[]> =>
+    invoke('get_active_topics', { days, limit }),
+
+  getTopicsStats: (topicIds: string[], days?: number): Promise<TopicStats[]> =>
+    invoke('get_topics_stats', { topicIds, days }),
+
+  getTopicCreators: (topicId: string, limit?: number): Promise<TopicCreator[]> =>
+    invoke('get_topic_creators', { topicId, limit }),
+
+  getTopicPlatformBreakdown: (topicId: string): Promise<PlatformShare[]> =>
+    invoke('get_topic_platform_breakdown', { topicId }),
+
+  getTopicConfidenceDistribution: (topicId: string): Promise<ConfidenceBin[]> =>
+    invoke('get_topic_confidence_distribution', { topicId }),
+
+  getTopicTimeseries: (topicId: string, days: number, bucket: 'day' | 'week'): Promise<TimeseriesPoint[]> =>
+    invoke('get_topic_timeseries', { topicId, days, bucket }),
+
+  getAllKeywordStats: (): Promise<KeywordStats> =>
+    invoke('get_all_keyword_stats'),
+
+  suggestKeywords: (topicId: string, limit?: number): Promise<KeywordSuggestion[]> =>
+    invoke('suggest_keywords', { topicId, limit }),
+
+  getSimilarTopics: (topicId: string, limit?: number): Promise<SimilarTopic[]> =>
+    invoke('get_similar_topics', { topicId, limit }),
+
+  suggestHierarchyLinks: (minFrequency?: number): Promise<HierarchyLinkSuggestion[]> =>
+    invoke('suggest_hierarchy_links', { minFrequency }),
+
+  getCooccurrences: (
+    topicId?: string,
+    minFrequency?: number,
+    limit?: number
+  ): Promise<TopicCooccurrence[]> => invoke('get_cooccurrences', { topicId, minFrequency, limit }),
+
+  setTopicParent: (topicId: string, parentTopicId: string | null): Promise<void> =>
+    invoke('set_topic_parent', { topicId, parentTopicId }),
+
+  createTopic: (
+    name: string,
+    keywords: TopicKeyword[],
+    aliases?: string[],
+    parentTopicId?: string | null,
+    exclusions?: string[]
+  ): Promise<Topic> =>
+    invoke('create_topic', { name, keywords, aliases, parentTopicId, exclusions }),
+
+  updateTopic: (
+    topicId: string,
+    changes: { name?: string; keywords?: TopicKeyword[]; aliases?: string[]; exclusions?: string[] }
+  ): Promise<Topic> =>
+    invoke('update_topic', { topicId, ...changes }),
+
+  deleteTopic: (topicId: string): Promise<void> =>
+    invoke('delete_topic', { topicId }),
+
+  reextractTopics: (): Promise<ReextractResult> =>
+    invoke('reextract_topics'),
+
+  onReextractProgress: (handler: (progress: ReextractProgress) => void): Promise<UnlistenFn> =>
+    listen<ReextractProgress>('reextract://progress', (event) => handler(event.payload)),
+
+  analyzeText: (text: string): Promise<TextAnalysis> => invoke('analyze_text', { text }),
+
   // Content
-  getContent: (limit?: number, offset?: number): Promise<Content[]> =>
-    invoke('get_content', { limit, offset }),
+  getContent: (
+    limit?: number,
+    offset?: number,
+    platform?: string,
+    from?: string,
+    to?: string,
+    minEngagement?: number
+  ): Promise<Content[]> => invoke('get_content', { limit, offset, platform, from, to, minEngagement }),
+
+  searchContent: (query: string, limit?: number): Promise<Content[]> =>
+    invoke('search_content', { query, limit }),
+
+  exportContent: (
+    format: 'csv' | 'json',
+    path: string,
+    platform?: string,
+    from?: string,
+    to?: string
+  ): Promise<number> => invoke('export_content', { format, path, platform, from, to }),
+
+  backupDatabase: (destPath: string): Promise<number> => invoke('backup_database', { destPath }),
+
+  exportBackup: (path: string): Promise<number> => invoke('export_backup', { path }),
+
+  importBackup: (path: string): Promise<ImportResult> => invoke('import_backup', { path }),
+
+  pruneContent: (olderThanDays?: number, keepLinked?: boolean, maxRows?: number): Promise<PruneResult> =>
+    invoke('prune_content', { olderThanDays, keepLinked, maxRows }),
 
   getContentByTopic: (topicId: string, limit?: number): Promise<Content[]> =>
     invoke('get_content_by_topic', { topicId, limit }),
 
+  getTopContent: (topicId: string, metric?: 'raw' | 'rate', limit?: number): Promise<TopContent[]> =>
+    invoke('get_top_content', { topicId, metric, limit }),
+
+  getContentTypes: (): Promise<ContentTypeCount[]> => invoke('get_content_types'),
+
+  getContentDateRange: (platform?: string): Promise<ContentDateRange> =>
+    invoke('get_content_date_range', { platform }),
+
+  tagUntaggedContent: (limit?: number): Promise<TagResult> =>
+    invoke('tag_untagged_content', { limit }),
+
+  tagContentEntities: (limit?: number): Promise<EntityTagResult> =>
+    invoke('tag_content_entities', { limit }),
+
+  getTrendingEntities: (days?: number, limit?: number): Promise<TrendingEntity[]> =>
+    invoke('get_trending_entities', { days, limit }),
+
+  getTrendingTags: (days?: number, limit?: number): Promise<TrendingTag[]> =>
+    invoke('get_trending_tags', { days, limit }),
+
+  // Creators
+  mergeCreators: (sourceId: string, targetId: string): Promise<Creator> =>
+    invoke('merge_creators', { sourceId, targetId }),
+
+  getCreatorGroup: (username: string): Promise<CreatorGroup> =>
+    invoke('get_creator_group', { username }),
+
+  getCreators: (
+    platform?: string,
+    limit?: number,
+    offset?: number,
+    sortBy?: 'content' | 'followers' | 'name'
+  ): Promise<CreatorSummary[]> => invoke('get_creators', { platform, limit, offset, sortBy }),
+
+  getCreatorDetails: (id: string, contentLimit?: number): Promise<CreatorDetails> =>
+    invoke('get_creator_details', { id, contentLimit }),
+
   // Dashboard
-  getDashboardStats: (): Promise<DashboardStats> => invoke('get_dashboard_stats'),
+  getDashboardStats: (includeDeleted?: boolean, days?: number): Promise<DashboardStats> =>
+    invoke('get_dashboard_stats', { includeDeleted, days }),
+
+  getSentimentShifts: (days?: number, threshold?: number): Promise<SentimentShift[]> =>
+    invoke('get_sentiment_shifts', { days, threshold }),
+
+  pinSnapshot: (label: string): Promise<SnapshotSummary> => invoke('pin_snapshot', { label }),
+
+  listSnapshots: (): Promise<SnapshotSummary[]> => invoke('list_snapshots'),
+
+  compareSnapshot: (label: string): Promise<SnapshotComparison> =>
+    invoke('compare_snapshot', { label }),
+
+  deleteSnapshot: (label: string): Promise<boolean> => invoke('delete_snapshot', { label }),
 
   // Alerts
   getAlerts: (limit?: number): Promise<Alert[]> => invoke('get_alerts', { limit }),
 
   markAlertRead: (alertId: string): Promise<void> =>
     invoke('mark_alert_read', { alertId }),
+
+  markAllAlertsRead: (): Promise<number> => invoke('mark_all_alerts_read'),
+
+  getUnreadAlertCount: (): Promise<number> => invoke('get_unread_alert_count'),
+
+  deleteAlert: (alertId: string): Promise<boolean> => invoke('delete_alert', { alertId }),
+
+  clearReadAlerts: (): Promise<number> => invoke('clear_read_alerts'),
+
+  exportAlertsRss: (path?: string, limit?: number): Promise<number> =>
+    invoke('export_alerts_rss', { path, limit }),
+
+  cleanupAlerts: (): Promise<number> => invoke('cleanup_alerts'),
 };
 
 export default api;
@@ -1,5 +1,5 @@
 import React, { useEffect, useState } from 'react';
-import { api, AppSettings, RedditCredentials, XCredentials, YouTubeCredentials } from '../api';
+import { api, errorMessage, AppSettings, RedditCredentials, XCredentials, YouTubeCredentials } from '../api';
 
 function Settings() {
   const [settings, setSettings] = useState<AppSettings | null>(null);
@@ -72,8 +72,9 @@ function Settings() {
     setTestResult(null);
 
     try {
+      // Username and password are optional: without them Reddit is read with an app-only token
       const reddit: RedditCredentials | null =
-        redditClientId && redditClientSecret && redditUsername && redditPassword
+        redditClientId.trim()
           ? {
               clientId: redditClientId.trim(),
               clientSecret: redditClientSecret.trim(),
@@ -106,6 +107,7 @@ function Settings() {
         .filter(s => s.length > 0);
 
       await api.saveSettings({
+        ...settings,
         reddit,
         x,
         youtube,
@@ -143,7 +145,7 @@ function Settings() {
         });
       }
     } catch (error) {
-      setTestResult({ success: false, message: String(error) || 'Connection test failed' });
+      setTestResult({ success: false, message: errorMessage(error) || 'Connection test failed' });
     } finally {
       setTestingReddit(false);
     }
@@ -172,7 +174,7 @@ function Settings() {
         });
       }
     } catch (error) {
-      setTestResult({ success: false, message: String(error) || 'X connection test failed' });
+      setTestResult({ success: false, message: errorMessage(error) || 'X connection test failed' });
     } finally {
       setTestingX(false);
     }
@@ -201,7 +203,7 @@ function Settings() {
         });
       }
     } catch (error) {
-      setTestResult({ success: false, message: String(error) || 'YouTube connection test failed' });
+      setTestResult({ success: false, message: errorMessage(error) || 'YouTube connection test failed' });
     } finally {
       setTestingYouTube(false);
     }
@@ -264,7 +266,7 @@ function Settings() {
               type="text"
               value={redditUsername}
               onChange={e => setRedditUsername(e.target.value)}
-              placeholder="Your Reddit username"
+              placeholder="Optional: leave blank for app-only access"
               className="input"
             />
           </div>
@@ -275,7 +277,7 @@ function Settings() {
               type="password"
               value={redditPassword}
               onChange={e => setRedditPassword(e.target.value)}
-              placeholder="Your Reddit password"
+              placeholder="Optional: leave blank for app-only access"
               className="input"
             />
           </div>
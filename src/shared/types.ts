@@ -1,6 +1,6 @@
 // Platform types
 export type Platform = 'youtube' | 'x' | 'reddit';
-export type ContentType = 'video' | 'post' | 'thread' | 'comment';
+export type ContentType = 'video' | 'post' | 'comment' | 'article' | 'story';
 
 // Motivation types
 export type MotivationType =
@@ -31,6 +31,7 @@ export interface Topic {
   slug: string;
   parentTopicId: string | null;
   aliases: string[];
+  exclusions: string[];
   createdAt: string;
 }
 
@@ -158,5 +159,7 @@ export interface DashboardStats {
   totalTopics: number;
   totalCreators: number;
   contentLast7Days: number;
+  windowDays: number;
+  contentInWindow: number;
   topTopics: { name: string; count: number }[];
 }